@@ -0,0 +1,121 @@
+use std::io::{Read, Write};
+
+#[cfg(feature = "flate2")]
+use flate2::{
+    read::{GzDecoder, GzEncoder, ZlibDecoder, ZlibEncoder},
+    Compression,
+};
+
+#[cfg(not(feature = "flate2"))]
+use crate::Error;
+use crate::{CompressionScheme, Result};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const LZ4_MAGIC: &[u8] = b"LZ4Block";
+
+impl CompressionScheme {
+    /// Guess the compression scheme of some data from its leading bytes. Data
+    /// not recognised as compressed is assumed to be uncompressed.
+    pub fn detect(data: &[u8]) -> CompressionScheme {
+        if data.starts_with(GZIP_MAGIC) {
+            CompressionScheme::Gzip
+        } else if data.starts_with(LZ4_MAGIC) {
+            CompressionScheme::Lz4
+        } else if is_zlib_header(data) {
+            CompressionScheme::Zlib
+        } else {
+            CompressionScheme::Uncompressed
+        }
+    }
+}
+
+// A zlib stream starts with a CMF byte using the deflate method (8), and the
+// CMF and FLG bytes together are a multiple of 31. Raw NBT starts with a tag
+// byte, which never uses the deflate method.
+fn is_zlib_header(data: &[u8]) -> bool {
+    match data {
+        [cmf, flg, ..] => cmf & 0x0f == 8 && u16::from_be_bytes([*cmf, *flg]) % 31 == 0,
+        _ => false,
+    }
+}
+
+/// Decompress data compressed with any of the [`CompressionScheme`]s. The
+/// scheme is detected with [`CompressionScheme::detect`].
+///
+/// Gzip and zlib require the `flate2` feature, and give
+/// [`Error::UnsupportedCompression`][`crate::Error::UnsupportedCompression`]
+/// without it.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    match CompressionScheme::detect(data) {
+        #[cfg(feature = "flate2")]
+        CompressionScheme::Gzip => GzDecoder::new(data).read_to_end(&mut buf)?,
+        #[cfg(feature = "flate2")]
+        CompressionScheme::Zlib => ZlibDecoder::new(data).read_to_end(&mut buf)?,
+        #[cfg(not(feature = "flate2"))]
+        scheme @ (CompressionScheme::Gzip | CompressionScheme::Zlib) => {
+            return Err(Error::UnsupportedCompression(scheme))
+        }
+        CompressionScheme::Lz4 => lz4_java_wrc::Lz4BlockInput::new(data).read_to_end(&mut buf)?,
+        CompressionScheme::Uncompressed => return Ok(data.to_vec()),
+    };
+    Ok(buf)
+}
+
+/// Compress uncompressed data with the given scheme. Gzip and zlib require the
+/// `flate2` feature, as for [`decompress`].
+pub fn compress(data: &[u8], scheme: CompressionScheme) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    match scheme {
+        #[cfg(feature = "flate2")]
+        CompressionScheme::Gzip => {
+            GzEncoder::new(data, Compression::fast()).read_to_end(&mut buf)?;
+        }
+        #[cfg(feature = "flate2")]
+        CompressionScheme::Zlib => {
+            ZlibEncoder::new(data, Compression::fast()).read_to_end(&mut buf)?;
+        }
+        #[cfg(not(feature = "flate2"))]
+        scheme @ (CompressionScheme::Gzip | CompressionScheme::Zlib) => {
+            return Err(Error::UnsupportedCompression(scheme))
+        }
+        CompressionScheme::Lz4 => {
+            let mut enc = lz4_java_wrc::Lz4BlockOutput::new(&mut buf);
+            enc.write_all(data)?;
+            enc.flush()?;
+        }
+        CompressionScheme::Uncompressed => buf.extend_from_slice(data),
+    }
+    Ok(buf)
+}
+
+/// Convert compressed data, such as a chunk from a region, to a different
+/// compression scheme. The input scheme is detected from the data. If the data
+/// is already compressed with `to` it is returned unchanged.
+///
+/// This works on the compressed data alone and does not parse the NBT inside,
+/// so it is lossless. The result can be written to a region with
+/// [`Region::write_compressed_chunk`][`crate::Region::write_compressed_chunk`].
+///
+/// This lives here rather than in fastnbt because the schemes are those of
+/// region files, given by [`CompressionScheme`], and fastnbt has no
+/// compression dependency of its own.
+///
+/// ```
+/// # use fastanvil::{compress, recompress, CompressionScheme, Result};
+/// # fn main() -> Result<()> {
+/// let nbt = fastnbt::to_bytes(&fastnbt::nbt!({"DataVersion": 3465}))?;
+/// let compressed = compress(&nbt, CompressionScheme::Lz4)?;
+///
+/// let raw = recompress(&compressed, CompressionScheme::Uncompressed)?;
+/// assert_eq!(raw, nbt);
+/// # Ok(())
+/// # }
+/// ```
+pub fn recompress(data: &[u8], to: CompressionScheme) -> Result<Vec<u8>> {
+    if CompressionScheme::detect(data) == to {
+        return Ok(data.to_vec());
+    }
+
+    compress(&decompress(data)?, to)
+}
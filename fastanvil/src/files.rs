@@ -1,6 +1,7 @@
-use crate::{JavaChunk, LoaderError};
-use crate::{LoaderResult, Region};
+use crate::{CCoord, JavaChunk, LoaderError};
+use crate::{LoaderResult, Region, Result};
 use crate::{RCoord, RegionLoader};
+use fastnbt::Value;
 use std::fs::File;
 use std::io::ErrorKind;
 use std::marker::PhantomData;
@@ -66,6 +67,176 @@ impl RegionLoader<File> for RegionFileLoader {
     }
 }
 
+/// A dimension of a [`World`], each of which has its own region files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorldDimension {
+    /// Regions in `region`.
+    Overworld,
+    /// Regions in `DIM-1/region`.
+    Nether,
+    /// Regions in `DIM1/region`.
+    End,
+}
+
+impl WorldDimension {
+    fn region_dir(self) -> &'static Path {
+        Path::new(match self {
+            WorldDimension::Overworld => "region",
+            WorldDimension::Nether => "DIM-1/region",
+            WorldDimension::End => "DIM1/region",
+        })
+    }
+}
+
+/// A world directory, the folder containing `level.dat`. This is for reading
+/// every chunk of a dimension, each with its coordinates in the world rather
+/// than within its region.
+///
+/// ```no_run
+/// # use fastanvil::{World, WorldDimension};
+/// # fn main() -> fastanvil::Result<()> {
+/// let world = World::open("saves/New World")?;
+///
+/// for chunk in world.chunks(WorldDimension::Nether)? {
+///     let (x, z, _nbt) = chunk?;
+///     println!("chunk at {}, {}", x.0, z.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct World {
+    dir: PathBuf,
+}
+
+impl World {
+    /// Open the world at `dir`. This errors if `dir` is not a directory.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        if !fs::metadata(&dir)?.is_dir() {
+            return Err(std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("{} is not a directory", dir.display()),
+            )
+            .into());
+        }
+        Ok(Self { dir })
+    }
+
+    /// The directory containing the region files of `dimension`.
+    pub fn region_dir(&self, dimension: WorldDimension) -> PathBuf {
+        self.dir.join(dimension.region_dir())
+    }
+
+    /// Iterate over every chunk of `dimension`, giving the chunk coordinates
+    /// in the world along with the chunk's NBT. Regions are read one at a
+    /// time in order of file name.
+    ///
+    /// Files in the region directory that are not named like region files,
+    /// eg `r.-1.2.mca`, are skipped, as are empty region files. A dimension
+    /// with no region directory has no chunks. A region or chunk that cannot
+    /// be read gives an error without stopping the iteration.
+    pub fn chunks(&self, dimension: WorldDimension) -> Result<WorldChunks> {
+        let dir = self.region_dir(dimension);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(WorldChunks::new(vec![])),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut regions = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "mca") {
+                if let Some(coords) = coords_from_region(&path) {
+                    regions.push((path, coords));
+                }
+            }
+        }
+        regions.sort();
+
+        Ok(WorldChunks::new(regions))
+    }
+}
+
+/// Iterator over the chunks of a [`World`], made by [`World::chunks`].
+pub struct WorldChunks {
+    // Regions left to read, last first.
+    regions: Vec<(PathBuf, (RCoord, RCoord))>,
+    current: Option<(Region<File>, RCoord, RCoord)>,
+    index: usize,
+}
+
+impl WorldChunks {
+    fn new(mut regions: Vec<(PathBuf, (RCoord, RCoord))>) -> Self {
+        regions.reverse();
+        Self {
+            regions,
+            current: None,
+            index: 0,
+        }
+    }
+
+    fn open_next_region(&mut self) -> Option<Result<()>> {
+        while let Some((path, (x, z))) = self.regions.pop() {
+            let file = match File::open(path) {
+                Ok(file) => file,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+            // Minecraft sometimes leaves empty region files around.
+            match file.metadata() {
+                Ok(meta) if meta.len() == 0 => continue,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e.into())),
+            }
+
+            return Some(Region::from_stream(file).map(|region| {
+                self.current = Some((region, x, z));
+                self.index = 0;
+            }));
+        }
+        None
+    }
+}
+
+impl Iterator for WorldChunks {
+    type Item = Result<(CCoord, CCoord, Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some((region, rx, rz)) = &mut self.current else {
+                match self.open_next_region()? {
+                    Ok(()) => continue,
+                    Err(e) => return Some(Err(e)),
+                }
+            };
+
+            if self.index == 32 * 32 {
+                self.current = None;
+                continue;
+            }
+
+            let (x, z) = (self.index % 32, self.index / 32);
+            self.index += 1;
+
+            let global_x = CCoord(rx.0 * 32 + x as isize);
+            let global_z = CCoord(rz.0 * 32 + z as isize);
+
+            match region.read_chunk(x, z) {
+                Ok(Some(data)) => {
+                    let chunk = fastnbt::from_bytes(&data)
+                        .map(|value| (global_x, global_z, value))
+                        .map_err(Into::into);
+                    return Some(chunk);
+                }
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
 fn coords_from_region(region: &Path) -> Option<(RCoord, RCoord)> {
     let filename = region.file_name()?.to_str()?;
     let mut parts = filename.split('.').skip(1);
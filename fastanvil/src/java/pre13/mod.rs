@@ -233,35 +233,74 @@ pub struct Pre13Section {
 impl Pre13Section {
     pub(crate) fn block(&self, x: usize, sec_y: usize, z: usize) -> RawBlock {
         let idx: usize = (sec_y << 8) + (z << 4) + x;
+        raw_block(&self.blocks, self.add.as_ref(), &self.data, idx)
+    }
 
-        // Important: byte array can have negative values, we want to convert -1 into 255
-        // so we cast first to u8, and then to usize
-        let mut block_id = self.blocks[idx] as u8 as usize;
-
-        // Add extra bits from add field if present
-        if let Some(add) = &self.add {
-            let mut add_id = add[idx / 2] as u8;
-            if idx % 2 == 0 {
-                add_id &= 0x0F;
-            } else {
-                add_id = (add_id & 0xF0) >> 4;
-            }
-            block_id += (add_id as usize) << 8;
-        }
+    /// Decode every block of this section, see [`decode_legacy_blocks`].
+    pub fn blocks(&self) -> impl Iterator<Item = RawBlock> + '_ {
+        decode_legacy_blocks(&self.blocks, self.add.as_ref(), &self.data)
+    }
+}
 
-        let block_data = {
-            let mut add_id = self.data[idx / 2] as u8;
-            if idx % 2 == 0 {
-                add_id &= 0x0F;
-            } else {
-                add_id = (add_id & 0xF0) >> 4;
-            }
+/// Decode the blocks of a section from before 1.13, given its `Blocks`, `Add`
+/// and `Data` byte arrays. This gives the block id and data value of each
+/// block, in the order they are stored: the index of the block at `x`, `y`
+/// and `z` within the section is `y * 256 + z * 16 + x`.
+///
+/// `Blocks` holds the low 8 bits of each block id. The optional `Add` array
+/// holds 4 more bits of the id for blocks above 255, and `Data` holds the 4
+/// bit data value. Both pack two blocks into each byte, the block with the even
+/// index in the low 4 bits. If `Add` or `Data` are shorter than they should be
+/// the missing values are taken as zero.
+///
+/// ```
+/// # use fastanvil::pre13::decode_legacy_blocks;
+/// # use fastnbt::ByteArray;
+/// let blocks = ByteArray::new(vec![1, 35, -1]);
+/// let data = ByteArray::new(vec![0x40, 0x00]);
+/// let add = ByteArray::new(vec![0x00, 0x01]);
+///
+/// let decoded: Vec<_> = decode_legacy_blocks(&blocks, Some(&add), &data)
+///     .map(|b| (b.block_id(), b.data_value()))
+///     .collect();
+///
+/// // Stone, then orange wool, then a modded block with id 511.
+/// assert_eq!(decoded, [(1, 0), (35, 4), (511, 0)]);
+/// ```
+pub fn decode_legacy_blocks<'a>(
+    blocks: &'a ByteArray,
+    add: Option<&'a ByteArray>,
+    data: &'a ByteArray,
+) -> impl Iterator<Item = RawBlock> + 'a {
+    (0..blocks.len()).map(move |idx| raw_block(blocks, add, data, idx))
+}
+
+fn raw_block(
+    blocks: &ByteArray,
+    add: Option<&ByteArray>,
+    data: &ByteArray,
+    idx: usize,
+) -> RawBlock {
+    // Important: byte array can have negative values, we want to convert -1 into 255
+    // so we cast first to u8, and then to u16
+    let mut block_id = blocks[idx] as u8 as u16;
+
+    // Add extra bits from add field if present
+    if let Some(add) = add {
+        block_id += (nibble(add, idx) as u16) << 8;
+    }
 
-            add_id
-        };
-        let block_list_index = (block_id << 4) + block_data as usize;
+    RawBlock::new(block_id, nibble(data, idx))
+}
 
-        RawBlock(block_list_index as u16)
+/// Get the 4 bit value at `idx` of an array packing two values into each
+/// byte, with the even index in the low bits. Missing values are zero.
+fn nibble(arr: &[i8], idx: usize) -> u8 {
+    let byte = arr.get(idx / 2).map_or(0, |b| *b as u8);
+    if idx.is_multiple_of(2) {
+        byte & 0x0F
+    } else {
+        (byte & 0xF0) >> 4
     }
 }
 
@@ -279,7 +318,7 @@ impl SectionLike for Pre13Section {
 /// Raw block representation: block_id:data_value
 ///
 /// block_id is 12 bits, data is 4 bits
-#[derive(Default, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
 pub struct RawBlock(u16);
 
 impl fmt::Debug for RawBlock {
@@ -289,6 +328,12 @@ impl fmt::Debug for RawBlock {
 }
 
 impl RawBlock {
+    /// Create a block from a 12-bit block id and a 4-bit data value. Higher
+    /// bits of either are ignored.
+    pub fn new(block_id: u16, data_value: u8) -> Self {
+        RawBlock(((block_id & 0x0FFF) << 4) | (data_value & 0x0F) as u16)
+    }
+
     /// Return 12-bit block id
     pub fn block_id(&self) -> u16 {
         self.0 >> 4
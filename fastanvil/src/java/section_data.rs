@@ -88,6 +88,46 @@ impl<T: Debug> BiomeData<T> {
     }
 }
 
+/// Decode the biomes of a section, as stored since 1.18, into its 64 cells of
+/// 4x4x4 blocks. The cells are ordered increasing in x, then z, then y, so the
+/// cell containing the block at section relative `x`, `y`, `z` is at index
+/// `(y / 4) * 16 + (z / 4) * 4 + x / 4`.
+///
+/// The data packs an index into the palette for each cell, using the fewest
+/// bits that can hold every index but at least 1. When the palette has a
+/// single entry the game leaves the data out, and every cell is that entry.
+///
+/// This returns `None` if the data does not fit the palette, ie it is missing
+/// for a palette of more than one entry, is too short, or has an index past
+/// the end of the palette.
+///
+/// ```
+/// # use fastanvil::decode_biomes;
+/// # use fastnbt::LongArray;
+/// let palette = ["minecraft:plains".to_string()];
+/// let cells = decode_biomes(&palette, None).unwrap();
+/// assert!(cells.iter().all(|b| *b == "minecraft:plains"));
+///
+/// // With two entries each cell takes 1 bit, so one long holds all 64.
+/// let palette = ["minecraft:plains", "minecraft:river"];
+/// let data = LongArray::new(vec![0b10]);
+/// let cells = decode_biomes(&palette, Some(&data)).unwrap();
+/// assert_eq!((*cells[0], *cells[1], *cells[2]), ("minecraft:plains", "minecraft:river", "minecraft:plains"));
+/// ```
+pub fn decode_biomes<'a, T>(palette: &'a [T], data: Option<&LongArray>) -> Option<[&'a T; 64]> {
+    let data = match (palette, data) {
+        ([], _) => return None,
+        ([single], None) => return Some([single; 64]),
+        (_, data) => data?,
+    };
+
+    let bits = biomes_bits_per_block(palette.len());
+    let cells: Vec<_> = StatesIter::new(bits, 4 * 4 * 4, data)
+        .map(|i| palette.get(i))
+        .collect::<Option<_>>()?;
+    cells.try_into().ok()
+}
+
 #[derive(Deserialize, Debug)]
 struct DataInner<T: Debug> {
     data: Option<LongArray>,
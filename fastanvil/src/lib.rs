@@ -16,15 +16,28 @@
 //!
 //! * **render** -
 //!   This feature is enabled by default and encapsulates all world-rendering related functionality.
+//!   It requires the `flate2` feature.
+//! * **flate2** -
+//!   This feature is enabled by default and adds support for zlib and gzip
+//!   compression, which most region files use. Without it only uncompressed and
+//!   LZ4 chunks can be read and written, and compressed chunks give
+//!   [`Error::UnsupportedCompression`]. fastnbt's core has no compression
+//!   dependency; only its optional `minecraft` feature uses flate2.
+//! * **rayon** -
+//!   Enables `par_map_chunks` and `par_map_region_dir` for processing the
+//!   chunks of regions in parallel.
 
 pub mod biome;
 pub mod tex;
 pub mod complete;
 
 mod bits;
+mod compression;
 mod dimension;
 mod files;
 mod java;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod region;
 #[cfg(feature = "render")]
 mod render;
@@ -32,9 +45,12 @@ mod render;
 mod rendered_palette;
 
 pub use bits::*;
+pub use compression::*;
 pub use dimension::*;
 pub use files::*;
 pub use java::*;
+#[cfg(feature = "rayon")]
+pub use parallel::*;
 pub use region::*;
 #[cfg(feature = "render")]
 pub use render::*;
@@ -49,7 +65,9 @@ pub enum Error {
     IO(std::io::Error),
     InvalidOffset(isize, isize),
     UnknownCompression(u8),
+    UnsupportedCompression(CompressionScheme),
     ChunkTooLarge,
+    Nbt(fastnbt::error::Error),
 }
 
 impl From<std::io::Error> for Error {
@@ -58,6 +76,12 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<fastnbt::error::Error> for Error {
+    fn from(err: fastnbt::error::Error) -> Error {
+        Error::Nbt(err)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 impl std::fmt::Display for Error {
@@ -70,7 +94,11 @@ impl std::fmt::Display for Error {
             Error::UnknownCompression(scheme) => f.write_fmt(format_args!(
                 "compression scheme ({scheme}) was not recognised for chunk"
             )),
+            Error::UnsupportedCompression(scheme) => f.write_fmt(format_args!(
+                "{scheme:?} compression requires the flate2 feature"
+            )),
             Error::ChunkTooLarge => f.write_str("chunk too large to store"),
+            Error::Nbt(e) => f.write_fmt(format_args!("nbt error: {e}")),
         }
     }
 }
@@ -0,0 +1,111 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::{Region, Result};
+
+/// The result of processing one chunk with [`par_map_chunks`].
+#[derive(Debug)]
+pub struct ChunkResult<T> {
+    pub x: usize,
+    pub z: usize,
+    /// The closure's output, or the error reading the chunk.
+    pub result: Result<T>,
+}
+
+/// The results of processing one region file with [`par_map_region_dir`].
+#[derive(Debug)]
+pub struct RegionResult<T> {
+    pub path: PathBuf,
+    /// The chunk results, or the error reading the region file.
+    pub chunks: Result<Vec<ChunkResult<T>>>,
+}
+
+/// Decompress every chunk of a region in parallel and apply `f` to the
+/// uncompressed NBT data of each, along with the chunk's coordinates within
+/// the region. `region` is the contents of a region file.
+///
+/// Results are in the same order as [`Region::iter`]. A chunk that fails to
+/// decompress gives an error in its [`ChunkResult`] without stopping the
+/// others. The returned error is only for a region whose header cannot be
+/// read. An empty file is a region with no chunks.
+///
+/// This requires the `rayon` feature.
+///
+/// ```no_run
+/// # use fastanvil::{par_map_chunks, Result};
+/// # fn main() -> Result<()> {
+/// let region = std::fs::read("r.0.0.mca")?;
+/// let sizes = par_map_chunks(&region, |_x, _z, chunk| chunk.len())?;
+///
+/// let total: usize = sizes.iter().filter_map(|c| c.result.as_ref().ok()).sum();
+/// # Ok(())
+/// # }
+/// ```
+pub fn par_map_chunks<T, F>(region: &[u8], f: F) -> Result<Vec<ChunkResult<T>>>
+where
+    T: Send,
+    F: Fn(usize, usize, &[u8]) -> T + Sync,
+{
+    // Minecraft sometimes leaves empty region files around.
+    if region.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Read the header once to catch a broken region before spawning work.
+    Region::from_stream(Cursor::new(region))?;
+
+    let results = (0..32 * 32)
+        .into_par_iter()
+        .map_init(
+            || Region::from_stream(Cursor::new(region)).expect("header already read once"),
+            |reader, i| {
+                let (x, z) = (i % 32, i / 32);
+                let chunk = reader.read_chunk(x, z).transpose()?;
+                Some(ChunkResult {
+                    x,
+                    z,
+                    result: chunk.map(|data| f(x, z, &data)),
+                })
+            },
+        )
+        .flatten()
+        .collect();
+
+    Ok(results)
+}
+
+/// Apply `f` to every chunk of every region file (`*.mca`) in a directory, in
+/// parallel. `f` is given the path of the region file as well as the chunk
+/// coordinates and uncompressed data. See [`par_map_chunks`].
+///
+/// A region that cannot be read gives an error in its [`RegionResult`]
+/// without stopping the others. The returned error is only for a directory
+/// that cannot be listed.
+///
+/// This requires the `rayon` feature.
+pub fn par_map_region_dir<T, F>(dir: impl AsRef<Path>, f: F) -> Result<Vec<RegionResult<T>>>
+where
+    T: Send,
+    F: Fn(&Path, usize, usize, &[u8]) -> T + Sync,
+{
+    let mut paths: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "mca"))
+        .collect();
+    paths.sort();
+
+    let results = paths
+        .into_par_iter()
+        .map(|path| {
+            let chunks = std::fs::read(&path)
+                .map_err(Into::into)
+                .and_then(|data| par_map_chunks(&data, |x, z, chunk| f(&path, x, z, chunk)));
+            RegionResult { path, chunks }
+        })
+        .collect();
+
+    Ok(results)
+}
@@ -2,8 +2,8 @@ use std::convert::TryFrom;
 use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use flate2::read::ZlibEncoder;
-use flate2::Compression;
+#[cfg(feature = "flate2")]
+use flate2::{read::ZlibEncoder, Compression};
 use num_enum::TryFromPrimitive;
 
 use crate::{Error, Result};
@@ -29,6 +29,13 @@ pub(crate) const CHUNK_HEADER_SIZE: usize = 5;
 /// If using a stream with `Read` and `Seek` you can only read chunk data. If
 /// you want to be able to modify chunk data you also need a stream implementing
 /// `Write`. [`File`][`std::fs::File`] implements these, as does [`Cursor<Vec<u8>>`][`std::io::Cursor`].
+///
+/// Since 1.17 the entities of each chunk are stored apart from the rest of
+/// it, in region files in the `entities` directory of each dimension next to
+/// the `region` directory. These use the same format, so are read and written
+/// with this type as well, along with the region files in the `poi`
+/// directory. Only the NBT inside each chunk differs, see
+/// `fastnbt::minecraft::EntityChunk` for the entities.
 #[derive(Clone)]
 pub struct Region<S> {
     stream: S,
@@ -132,16 +139,22 @@ where
     pub fn read_chunk(&mut self, x: usize, z: usize) -> Result<Option<Vec<u8>>> {
         self.compression_scheme(x, z)?
             .map(|scheme| match scheme {
+                #[cfg(feature = "flate2")]
                 CompressionScheme::Zlib => {
                     let mut decoder = flate2::write::ZlibDecoder::new(vec![]);
                     self.read_compressed_chunk(x, z, &mut decoder)?;
                     Ok(decoder.finish()?)
                 }
+                #[cfg(feature = "flate2")]
                 CompressionScheme::Gzip => {
                     let mut decoder = flate2::write::GzDecoder::new(vec![]);
                     self.read_compressed_chunk(x, z, &mut decoder)?;
                     Ok(decoder.finish()?)
                 }
+                #[cfg(not(feature = "flate2"))]
+                scheme @ (CompressionScheme::Zlib | CompressionScheme::Gzip) => {
+                    Err(Error::UnsupportedCompression(scheme))
+                }
                 CompressionScheme::Uncompressed => {
                     let mut buf = vec![];
                     self.read_compressed_chunk(x, z, &mut buf)?;
@@ -167,13 +180,35 @@ where
         let mut buf = [0u8; 4];
         self.stream.read_exact(&mut buf[..])?;
 
-        let mut offset = 0u64;
-        offset |= (buf[0] as u64) << 16;
-        offset |= (buf[1] as u64) << 8;
-        offset |= buf[2] as u64;
-        let sectors = buf[3] as u64;
+        Ok(ChunkLocation::from_header(buf))
+    }
 
-        Ok((offset != 0 || sectors != 0).then_some(ChunkLocation { offset, sectors }))
+    /// Read the header of the region, which gives where each chunk is and when
+    /// it was last saved. This reads only the first 8 kiB of the stream, so is
+    /// a cheap way to find chunks that are missing, oversized or never saved
+    /// without reading any of them.
+    ///
+    /// ```no_run
+    /// # use fastanvil::Region;
+    /// # use fastanvil::Result;
+    /// # use std::fs::File;
+    /// # fn main() -> Result<()> {
+    /// let file = File::open("foo.mca")?;
+    /// let mut region = Region::from_stream(file)?;
+    /// let header = region.header()?;
+    ///
+    /// for (x, z) in header.present_chunks() {
+    ///     let sectors = header.chunk_size_sectors(x, z)?.unwrap();
+    ///     if sectors > 200 {
+    ///         println!("chunk {x}, {z} is {sectors} sectors");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn header(&mut self) -> Result<RegionHeader> {
+        self.stream.rewind()?;
+        RegionHeader::from_stream(&mut self.stream)
     }
 
     /// Low level method. Read a compressed chunk into the given writer. The
@@ -319,6 +354,9 @@ where
     /// The chunk data will be compressed with zlib by default. You can use
     /// write_compressed_chunk if you want more control. If `x` or `z` are
     /// outside `0..32`, [`Error::InvalidOffset`] is returned.
+    ///
+    /// This requires the `flate2` feature.
+    #[cfg(feature = "flate2")]
     pub fn write_chunk(&mut self, x: usize, z: usize, uncompressed_chunk: &[u8]) -> Result<()> {
         let mut buf = vec![];
         let mut enc = ZlibEncoder::new(uncompressed_chunk, Compression::fast());
@@ -447,8 +485,77 @@ where
     }
 }
 
+/// The header of a region, the first 8 kiB of the file. The first 4 kiB gives
+/// the location of each chunk, the next 4 kiB the time each chunk was last
+/// saved. See [`Region::header`].
+///
+/// Methods taking chunk coordinates return [`Error::InvalidOffset`] if `x` or
+/// `z` are outside `0..32`, like [`Region::read_chunk`].
+#[derive(Clone)]
+pub struct RegionHeader {
+    data: Box<[u8; REGION_HEADER_SIZE]>,
+}
+
+impl RegionHeader {
+    /// Read a header from the current position of a stream, meaning something
+    /// that implements [`Read`]. Exactly 8 kiB are read.
+    pub fn from_stream<R: Read>(mut stream: R) -> Result<Self> {
+        let mut data = Box::new([0; REGION_HEADER_SIZE]);
+        stream.read_exact(&mut data[..])?;
+        Ok(Self { data })
+    }
+
+    /// The location of the chunk at `x`, `z` in the region, or `None` if the
+    /// chunk is not present.
+    pub fn location(&self, x: usize, z: usize) -> Result<Option<ChunkLocation>> {
+        let pos = checked_header_pos(x, z)?;
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(&self.data[pos..pos + 4]);
+        Ok(ChunkLocation::from_header(buf))
+    }
+
+    /// The chunk coordinates of every chunk present in the region, in the
+    /// same order as [`Region::iter`].
+    pub fn present_chunks(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..32 * 32)
+            .map(|i| (i % 32, i / 32))
+            .filter(|&(x, z)| matches!(self.location(x, z), Ok(Some(_))))
+    }
+
+    /// The number of 4 kiB sectors the chunk at `x`, `z` takes up in the
+    /// region file, or `None` if the chunk is not present. This is the space
+    /// set aside for the chunk, so its compressed data may be up to a sector
+    /// smaller.
+    ///
+    /// The header has one byte for this, so a chunk of more than 255 sectors,
+    /// just under 1 MiB, cannot be stored in the region file itself. The game
+    /// stores them in a separate `.mcc` file instead, which this crate does
+    /// not read.
+    pub fn chunk_size_sectors(&self, x: usize, z: usize) -> Result<Option<u64>> {
+        Ok(self.location(x, z)?.map(|loc| loc.sectors))
+    }
+
+    /// The time the chunk at `x`, `z` was last saved, in seconds since the
+    /// Unix epoch. This is zero if it was never set, which is the case for
+    /// regions written by this crate.
+    pub fn timestamp(&self, x: usize, z: usize) -> Result<u32> {
+        let pos = SECTOR_SIZE + checked_header_pos(x, z)?;
+        let mut buf = &self.data[pos..pos + 4];
+        Ok(buf.read_u32::<BigEndian>()?)
+    }
+}
+
+// The header is 8 kiB, so only show how many chunks it has.
+impl std::fmt::Debug for RegionHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegionHeader")
+            .field("present_chunks", &self.present_chunks().count())
+            .finish()
+    }
+}
+
 /// Various compression schemes that NBT data is typically compressed with.
-#[derive(Debug, TryFromPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum CompressionScheme {
     Gzip = 1,
@@ -541,6 +648,13 @@ fn header_pos(x: usize, z: usize) -> u64 {
     (4 * ((x % 32) + (z % 32) * 32)) as u64
 }
 
+fn checked_header_pos(x: usize, z: usize) -> Result<usize> {
+    if x >= 32 || z >= 32 {
+        return Err(Error::InvalidOffset(x as isize, z as isize));
+    }
+    Ok(header_pos(x, z) as usize)
+}
+
 #[derive(Debug)]
 pub struct ChunkLocation {
     /// The offset, in units of 4kiB sectors, into the region file this chunk is
@@ -551,6 +665,19 @@ pub struct ChunkLocation {
     pub sectors: u64,
 }
 
+impl ChunkLocation {
+    /// Parse the 4 bytes of a chunk's location in the region header.
+    fn from_header(buf: [u8; 4]) -> Option<Self> {
+        let mut offset = 0u64;
+        offset |= (buf[0] as u64) << 16;
+        offset |= (buf[1] as u64) << 8;
+        offset |= buf[2] as u64;
+        let sectors = buf[3] as u64;
+
+        (offset != 0 || sectors != 0).then_some(ChunkLocation { offset, sectors })
+    }
+}
+
 /// Encodes how the NBT-Data is compressed
 #[derive(Debug)]
 struct ChunkMeta {
@@ -607,3 +734,69 @@ impl<W: Write> Write for Lz4DecoderWrapper<W> {
         Ok(())
     }
 }
+
+const BLOCK_POS_XZ_BITS: u32 = 26;
+const BLOCK_POS_Y_BITS: u32 = 12;
+const SECTION_POS_XZ_BITS: u32 = 22;
+const SECTION_POS_Y_BITS: u32 = 20;
+
+/// Pack a block position into a long the same way as vanilla's
+/// `BlockPos.asLong`, as used by some block entities and fields. X and Z are
+/// stored in 26 bits each and Y in 12 bits, so values outside those ranges
+/// wrap, as they do in vanilla.
+pub fn encode_block_pos(x: i32, y: i32, z: i32) -> i64 {
+    pack(
+        [x, z, y],
+        [BLOCK_POS_XZ_BITS, BLOCK_POS_XZ_BITS, BLOCK_POS_Y_BITS],
+    )
+}
+
+/// Unpack a block position packed with [`encode_block_pos`], giving `(x, y,
+/// z)`.
+pub fn decode_block_pos(packed: i64) -> (i32, i32, i32) {
+    let [x, z, y] = unpack(
+        packed,
+        [BLOCK_POS_XZ_BITS, BLOCK_POS_XZ_BITS, BLOCK_POS_Y_BITS],
+    );
+    (x, y, z)
+}
+
+/// Pack a chunk section position, in units of sections rather than blocks,
+/// into a long the same way as vanilla's `SectionPos.asLong`. X and Z are
+/// stored in 22 bits each and Y in 20 bits, so values outside those ranges
+/// wrap, as they do in vanilla.
+pub fn encode_section_pos(x: i32, y: i32, z: i32) -> i64 {
+    pack(
+        [x, z, y],
+        [SECTION_POS_XZ_BITS, SECTION_POS_XZ_BITS, SECTION_POS_Y_BITS],
+    )
+}
+
+/// Unpack a chunk section position packed with [`encode_section_pos`], giving
+/// `(x, y, z)`.
+pub fn decode_section_pos(packed: i64) -> (i32, i32, i32) {
+    let [x, z, y] = unpack(
+        packed,
+        [SECTION_POS_XZ_BITS, SECTION_POS_XZ_BITS, SECTION_POS_Y_BITS],
+    );
+    (x, y, z)
+}
+
+// Pack values into a long, the first value in the most significant bits.
+fn pack(values: [i32; 3], bits: [u32; 3]) -> i64 {
+    values.iter().zip(bits).fold(0u64, |acc, (v, bits)| {
+        acc << bits | (*v as u64 & ((1 << bits) - 1))
+    }) as i64
+}
+
+// Unpack values packed with `pack`, sign extending each.
+fn unpack(packed: i64, bits: [u32; 3]) -> [i32; 3] {
+    let mut shift = 0;
+    bits.map(|bits| {
+        // Move the value to the top of the long, then arithmetic shift it
+        // back down to sign extend it.
+        let v = (packed << shift) >> (64 - bits);
+        shift += bits;
+        v as i32
+    })
+}
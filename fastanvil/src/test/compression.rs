@@ -0,0 +1,77 @@
+#[cfg(not(feature = "flate2"))]
+use crate::Error;
+use crate::{compress, decompress, recompress, CompressionScheme};
+
+const CHUNK: &[u8] = include_bytes!("../../resources/1.17.1.chunk");
+
+#[cfg(feature = "flate2")]
+const ALL: [CompressionScheme; 4] = [
+    CompressionScheme::Gzip,
+    CompressionScheme::Zlib,
+    CompressionScheme::Uncompressed,
+    CompressionScheme::Lz4,
+];
+
+#[cfg(not(feature = "flate2"))]
+const ALL: [CompressionScheme; 2] = [CompressionScheme::Uncompressed, CompressionScheme::Lz4];
+
+#[test]
+fn raw_nbt_detected_as_uncompressed() {
+    assert_eq!(
+        CompressionScheme::detect(CHUNK),
+        CompressionScheme::Uncompressed
+    );
+    assert_eq!(
+        CompressionScheme::detect(&[]),
+        CompressionScheme::Uncompressed
+    );
+}
+
+#[test]
+fn compress_detect_decompress_roundtrip() {
+    for scheme in ALL {
+        let compressed = compress(CHUNK, scheme).unwrap();
+        assert_eq!(CompressionScheme::detect(&compressed), scheme);
+        assert_eq!(decompress(&compressed).unwrap(), CHUNK);
+    }
+}
+
+#[test]
+fn recompress_between_all_schemes() {
+    for from in ALL {
+        let compressed = compress(CHUNK, from).unwrap();
+
+        for to in ALL {
+            let converted = recompress(&compressed, to).unwrap();
+            assert_eq!(CompressionScheme::detect(&converted), to);
+            assert_eq!(decompress(&converted).unwrap(), CHUNK);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "flate2")]
+fn recompress_to_same_scheme_is_unchanged() {
+    let compressed = compress(CHUNK, CompressionScheme::Zlib).unwrap();
+    assert_eq!(
+        recompress(&compressed, CompressionScheme::Zlib).unwrap(),
+        compressed
+    );
+}
+
+#[test]
+#[cfg(not(feature = "flate2"))]
+fn zlib_and_gzip_need_flate2() {
+    for scheme in [CompressionScheme::Gzip, CompressionScheme::Zlib] {
+        assert!(matches!(
+            compress(CHUNK, scheme),
+            Err(Error::UnsupportedCompression(s)) if s == scheme
+        ));
+    }
+
+    // A zlib header, as would start a compressed chunk.
+    assert!(matches!(
+        decompress(&[0x78, 0x9c, 0, 0]),
+        Err(Error::UnsupportedCompression(CompressionScheme::Zlib))
+    ));
+}
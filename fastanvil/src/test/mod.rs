@@ -7,13 +7,19 @@ use std::{collections::hash_map::DefaultHasher, hash::Hasher};
 
 use fastnbt::{nbt, LongArray, Value};
 
+#[cfg(feature = "flate2")]
+mod complete_chunk;
+mod compression;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pre13;
 mod region;
 mod rogue_chunks;
 mod section_data;
-mod complete_chunk;
 #[cfg(feature = "render")]
 mod standard_chunks;
 mod unicode_chunk;
+mod world;
 
 #[test]
 fn nbt_macro_use() {
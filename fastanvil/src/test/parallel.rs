@@ -0,0 +1,72 @@
+use std::io::Cursor;
+
+use crate::{par_map_chunks, par_map_region_dir, CompressionScheme, Region};
+
+const REGION: &[u8] = include_bytes!("../../resources/1.19.4.mca");
+
+#[test]
+fn par_map_matches_sequential_iter() {
+    let mut region = Region::from_stream(Cursor::new(REGION)).unwrap();
+    let expected: Vec<_> = region
+        .iter()
+        .map(|c| c.unwrap())
+        .map(|c| (c.x, c.z, c.data.len()))
+        .collect();
+
+    let results = par_map_chunks(REGION, |_, _, data| data.len()).unwrap();
+    let results: Vec<_> = results
+        .into_iter()
+        .map(|c| (c.x, c.z, c.result.unwrap()))
+        .collect();
+
+    assert!(!expected.is_empty());
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn par_map_reports_bad_chunks_individually() {
+    let mut region = Region::create(Cursor::new(vec![])).unwrap();
+    region.write_chunk(0, 0, b"good").unwrap();
+    region
+        .write_compressed_chunk(1, 0, CompressionScheme::Zlib, b"not zlib")
+        .unwrap();
+    region.write_chunk(2, 0, b"also good").unwrap();
+    let data = region.into_inner().unwrap().into_inner();
+
+    let results = par_map_chunks(&data, |_, _, chunk| chunk.to_vec()).unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].result.as_ref().unwrap(), b"good");
+    assert!(results[1].result.is_err());
+    assert_eq!(results[2].result.as_ref().unwrap(), b"also good");
+}
+
+#[test]
+fn par_map_empty_and_broken_regions() {
+    assert!(par_map_chunks(&[], |_, _, _| ()).unwrap().is_empty());
+    assert!(par_map_chunks(&[1, 2, 3], |_, _, _| ()).is_err());
+}
+
+#[test]
+fn par_map_region_dir_reads_every_region() {
+    let dir = std::env::temp_dir().join(format!("fastanvil-par-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("r.0.0.mca"), REGION).unwrap();
+    std::fs::write(dir.join("r.0.1.mca"), [1, 2, 3]).unwrap();
+    std::fs::write(dir.join("not-a-region.txt"), REGION).unwrap();
+
+    let results = par_map_region_dir(&dir, |path, _, _, data| {
+        (path.file_name().unwrap().to_owned(), data.len())
+    });
+    std::fs::remove_dir_all(&dir).unwrap();
+    let results = results.unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].path.ends_with("r.0.0.mca"));
+    let chunks = results[0].chunks.as_ref().unwrap();
+    assert!(!chunks.is_empty());
+    assert!(chunks
+        .iter()
+        .all(|c| c.result.as_ref().unwrap().0 == "r.0.0.mca"));
+    assert!(results[1].chunks.is_err());
+}
@@ -0,0 +1,54 @@
+use fastnbt::ByteArray;
+
+use crate::pre13::{decode_legacy_blocks, Pre13Section, RawBlock};
+
+#[test]
+fn decode_full_legacy_section() {
+    // Index of x=3, y=2, z=1 is 2*256 + 1*16 + 3.
+    let idx = 2 * 256 + 16 + 3;
+    let mut blocks = vec![0; 4096];
+    let mut add = vec![0; 2048];
+    let mut data = vec![0; 2048];
+    blocks[idx] = -56; // 200
+    add[idx / 2] = 0x30; // idx is odd, so the high nibble.
+    data[idx / 2] = 0x70;
+    data[(idx - 1) / 2] |= 0x05; // low nibble for the even neighbour.
+    blocks[idx - 1] = 35;
+
+    let section = Pre13Section {
+        y: 0,
+        blocks: ByteArray::new(blocks),
+        add: Some(ByteArray::new(add)),
+        data: ByteArray::new(data),
+    };
+
+    let decoded: Vec<_> = section.blocks().collect();
+    assert_eq!(decoded.len(), 4096);
+    assert_eq!(decoded[idx], RawBlock::new(3 * 256 + 200, 7));
+    assert_eq!(decoded[idx - 1], RawBlock::new(35, 5));
+    assert_eq!(section.block(3, 2, 1), decoded[idx]);
+    assert_eq!(
+        decoded
+            .iter()
+            .filter(|b| **b != RawBlock::default())
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn decode_legacy_blocks_with_short_arrays() {
+    let blocks = ByteArray::new(vec![1, 2, 3]);
+    let data = ByteArray::new(vec![0x21]);
+
+    let decoded: Vec<_> = decode_legacy_blocks(&blocks, None, &data).collect();
+    assert_eq!(
+        decoded,
+        [
+            RawBlock::new(1, 1),
+            RawBlock::new(2, 2),
+            RawBlock::new(3, 0)
+        ]
+    );
+    assert_eq!(RawBlock::new(0xFFFF, 0xFF), RawBlock::new(0xFFF, 0xF));
+}
@@ -1,7 +1,8 @@
 use std::io::{Cursor, Read, Seek, Write};
 
 use crate::{
-    ChunkLocation, CompressionScheme::Uncompressed, Error, Region, CHUNK_HEADER_SIZE,
+    decode_block_pos, decode_section_pos, encode_block_pos, encode_section_pos, ChunkLocation,
+    CompressionScheme::Uncompressed, Error, Region, RegionHeader, CHUNK_HEADER_SIZE,
     REGION_HEADER_SIZE, SECTOR_SIZE,
 };
 
@@ -117,6 +118,25 @@ fn write_and_get_chunk() {
     assert_eq!(c, &[1, 2, 3]);
 }
 
+#[test]
+fn entity_chunks_are_read_like_any_other() {
+    // A chunk from an `entities` region file rather than a `region` one.
+    let entities = fastnbt::nbt!({
+        "DataVersion": 3465,
+        "Position": [I; 35, -9],
+        "Entities": [{"id": "minecraft:cow", "Pos": [561.5, 70.0, -140.5]}],
+    });
+    let data = fastnbt::to_bytes(&entities).unwrap();
+
+    let mut r = new_empty();
+    r.write_compressed_chunk(3, 23, Uncompressed, &data)
+        .unwrap();
+
+    let mut r = Region::from_stream(r.into_inner().unwrap()).unwrap();
+    let c = r.read_chunk(3, 23).unwrap().unwrap();
+    assert_eq!(fastnbt::from_bytes::<fastnbt::Value>(&c).unwrap(), entities);
+}
+
 #[test]
 fn getting_other_chunks_404s() {
     let mut r = new_empty();
@@ -268,3 +288,107 @@ fn into_inner_rewinds_behind_header_if_empty_region() {
 // abstraction on top of this providing this. Something that copies a region and
 // only write the to copy until done, then atomically moves the file over the
 // old region.
+
+#[test]
+fn header_reports_chunks_without_reading_them() {
+    let mut r = new_empty();
+    r.write_compressed_chunk(1, 0, Uncompressed, &[1, 2, 3])
+        .unwrap();
+    r.write_compressed_chunk(0, 2, Uncompressed, &n_sector_chunk(3))
+        .unwrap();
+
+    // Vanilla writes the time each chunk was saved, this crate does not.
+    let mut data = r.into_inner().unwrap().into_inner();
+    let timestamp = SECTOR_SIZE + 4 * (2 * 32);
+    data[timestamp..timestamp + 4].copy_from_slice(&1_700_000_000_u32.to_be_bytes());
+
+    // Anything after the header is not needed.
+    let header = RegionHeader::from_stream(&data[..REGION_HEADER_SIZE]).unwrap();
+    assert_eq!(
+        header.present_chunks().collect::<Vec<_>>(),
+        [(1, 0), (0, 2)]
+    );
+    assert_eq!(header.chunk_size_sectors(1, 0).unwrap(), Some(1));
+    assert_eq!(header.chunk_size_sectors(0, 2).unwrap(), Some(3));
+    assert_eq!(header.chunk_size_sectors(0, 0).unwrap(), None);
+    assert_eq!(header.location(0, 2).unwrap().unwrap().offset, 3);
+    assert_eq!(header.timestamp(0, 2).unwrap(), 1_700_000_000);
+    assert_eq!(header.timestamp(1, 0).unwrap(), 0);
+    assert_eq!(
+        format!("{:?}", header),
+        "RegionHeader { present_chunks: 2 }"
+    );
+
+    assert!(matches!(
+        header.chunk_size_sectors(32, 0),
+        Err(Error::InvalidOffset(..))
+    ));
+    assert!(matches!(
+        header.timestamp(0, 32),
+        Err(Error::InvalidOffset(..))
+    ));
+
+    let mut r = Region::from_stream(Cursor::new(data)).unwrap();
+    r.read_chunk(0, 0).unwrap();
+    let header = r.header().unwrap();
+    assert_eq!(header.present_chunks().count(), 2);
+    assert_eq!(header.timestamp(0, 2).unwrap(), 1_700_000_000);
+}
+
+#[test]
+fn header_of_truncated_region_errors() {
+    assert!(matches!(
+        RegionHeader::from_stream(&[0; REGION_HEADER_SIZE - 1][..]),
+        Err(Error::IO(..))
+    ));
+}
+
+#[test]
+fn block_pos_matches_vanilla_packing() {
+    // Values from vanilla's BlockPos.asLong.
+    let cases = [
+        ((0, 0, 0), 0),
+        ((1, 0, 0), 274877906944),
+        ((0, 1, 0), 1),
+        ((0, 0, 1), 4096),
+        ((-1, -1, -1), -1),
+        ((-1, 64, -1), -4032),
+        ((100, -64, -200), 27762667786176),
+        ((33554431, 2047, -33554432), 9223371899415824383),
+        ((-33554432, -2048, 33554431), -9223371899415824384),
+    ];
+
+    for ((x, y, z), packed) in cases {
+        assert_eq!(encode_block_pos(x, y, z), packed);
+        assert_eq!(decode_block_pos(packed), (x, y, z));
+    }
+}
+
+#[test]
+fn block_pos_out_of_range_wraps() {
+    assert_eq!(
+        decode_block_pos(encode_block_pos(0, 2048, 0)),
+        (0, -2048, 0)
+    );
+    assert_eq!(
+        decode_block_pos(encode_block_pos(33554432, 0, 0)),
+        (-33554432, 0, 0)
+    );
+}
+
+#[test]
+fn section_pos_matches_vanilla_packing() {
+    // Values from vanilla's SectionPos.asLong.
+    let cases = [
+        ((0, 0, 0), 0),
+        ((1, 0, 0), 4398046511104),
+        ((-1, -4, -1), -4),
+        ((3, -2, -7), 17592179752958),
+        ((2097151, 524287, -2097152), 9223369837832044543),
+    ];
+
+    for ((x, y, z), packed) in cases {
+        assert_eq!(encode_section_pos(x, y, z), packed);
+        assert_eq!(decode_section_pos(packed), (x, y, z));
+    }
+}
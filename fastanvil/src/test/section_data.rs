@@ -1,4 +1,6 @@
-use crate::{min_bits_for_n_states, StatesIter};
+use fastnbt::LongArray;
+
+use crate::{decode_biomes, min_bits_for_n_states, StatesIter};
 
 #[test]
 fn iter_zeroes() {
@@ -43,3 +45,52 @@ fn min_bits() {
         assert_eq!(ideal(i), min_bits_for_n_states(i));
     }
 }
+
+#[test]
+fn decode_biomes_single_and_packed() {
+    let palette = ["a", "b", "c"];
+
+    // One entry needs no data.
+    let cells = decode_biomes(&palette[..1], None).unwrap();
+    assert_eq!(cells, [&"a"; 64]);
+
+    // Three entries take 2 bits per cell, 32 cells per long.
+    let mut longs = [0_u64; 2];
+    for i in 0..64 {
+        longs[i / 32] |= ((i % 3) as u64) << (2 * (i % 32));
+    }
+    let data = LongArray::new(longs.iter().map(|l| *l as i64).collect());
+    let cells = decode_biomes(&palette, Some(&data)).unwrap();
+    for (i, cell) in cells.iter().enumerate() {
+        assert_eq!(**cell, palette[i % 3], "cell {}", i);
+    }
+
+    // The cell of a block, x then z then y.
+    let at = |x: usize, y: usize, z: usize| cells[(y / 4) * 16 + (z / 4) * 4 + x / 4];
+    assert_eq!(*at(4, 0, 0), "b");
+    assert_eq!(*at(0, 0, 4), palette[4 % 3]);
+    assert_eq!(*at(0, 15, 15), palette[63 % 3]);
+}
+
+#[test]
+fn decode_biomes_rejects_bad_data() {
+    let palette = ["a", "b"];
+    assert_eq!(decode_biomes(&palette, None), None);
+    assert_eq!(decode_biomes::<&str>(&[], None), None);
+    assert_eq!(
+        decode_biomes::<&str>(&[], Some(&LongArray::new(vec![0]))),
+        None
+    );
+
+    // Too short.
+    assert_eq!(decode_biomes(&palette, Some(&LongArray::new(vec![]))), None);
+
+    // Three entries take 2 bits, which can hold an index of 3 that is past
+    // the end of the palette.
+    let three = ["a", "b", "c"];
+    assert!(decode_biomes(&three, Some(&LongArray::new(vec![2, 0]))).is_some());
+    assert_eq!(
+        decode_biomes(&three, Some(&LongArray::new(vec![3, 0]))),
+        None
+    );
+}
@@ -0,0 +1,75 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use fastnbt::nbt;
+
+use crate::{CompressionScheme, Region, World, WorldDimension};
+
+fn write_region(path: &Path, chunks: &[(usize, usize, i32)]) {
+    let mut region = Region::create(Cursor::new(vec![])).unwrap();
+    for &(x, z, id) in chunks {
+        let data = fastnbt::to_bytes(&nbt!({ "id": id })).unwrap();
+        region
+            .write_compressed_chunk(x, z, CompressionScheme::Uncompressed, &data)
+            .unwrap();
+    }
+    std::fs::write(path, region.into_inner().unwrap().into_inner()).unwrap();
+}
+
+#[test]
+fn world_chunks_have_global_coords() {
+    let dir = std::env::temp_dir().join(format!("fastanvil-world-{}", std::process::id()));
+    let region_dir = dir.join("DIM-1/region");
+    std::fs::create_dir_all(&region_dir).unwrap();
+    write_region(&region_dir.join("r.0.0.mca"), &[(1, 2, 1)]);
+    write_region(&region_dir.join("r.-1.1.mca"), &[(31, 0, 2), (0, 1, 3)]);
+    std::fs::write(region_dir.join("r.1.1.mca"), []).unwrap();
+    std::fs::write(region_dir.join("r.0.0.mca.bak"), [1, 2, 3]).unwrap();
+    std::fs::write(region_dir.join("notes.txt"), "hello").unwrap();
+
+    let world = World::open(&dir).unwrap();
+    let chunks: Vec<_> = world
+        .chunks(WorldDimension::Nether)
+        .unwrap()
+        .map(|c| c.unwrap())
+        .map(|(x, z, nbt)| (x.0, z.0, nbt))
+        .collect();
+    let overworld = world.chunks(WorldDimension::Overworld).unwrap().count();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        chunks,
+        [
+            (-1, 32, nbt!({ "id": 2 })),
+            (-32, 33, nbt!({ "id": 3 })),
+            (1, 2, nbt!({ "id": 1 })),
+        ]
+    );
+    assert_eq!(overworld, 0);
+}
+
+#[test]
+fn world_chunks_continue_after_bad_region() {
+    let dir = std::env::temp_dir().join(format!("fastanvil-world-bad-{}", std::process::id()));
+    let region_dir = dir.join("region");
+    std::fs::create_dir_all(&region_dir).unwrap();
+    std::fs::write(region_dir.join("r.0.0.mca"), [1, 2, 3]).unwrap();
+    write_region(&region_dir.join("r.0.1.mca"), &[(0, 0, 1)]);
+
+    let chunks: Vec<_> = World::open(&dir)
+        .unwrap()
+        .chunks(WorldDimension::Overworld)
+        .unwrap()
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks[0].is_err());
+    let (x, z, _) = chunks[1].as_ref().unwrap();
+    assert_eq!((x.0, z.0), (0, 32));
+}
+
+#[test]
+fn world_open_requires_dir() {
+    assert!(World::open("does/not/exist").is_err());
+}
@@ -165,7 +165,7 @@ impl Renderer {
             )
         })?;
 
-        let el = els.get(0).ok_or_else(|| {
+        let el = els.first().ok_or_else(|| {
             Error::MissingElements(
                 id.to_owned(),
                 encoded_props.to_owned(),
@@ -0,0 +1,39 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fastnbt::Value;
+use serde::Deserialize;
+use smallvec::SmallVec;
+
+#[derive(Deserialize)]
+struct Entities<T> {
+    #[allow(dead_code)]
+    positions: Vec<T>,
+}
+
+fn positions() -> Vec<u8> {
+    let pos = |i: usize| Value::List(vec![Value::Double(i as f64); 3]);
+    let positions = Value::List((0..10_000).map(pos).collect());
+    fastnbt::to_bytes(&fastnbt::nbt!({ "positions": positions })).unwrap()
+}
+
+pub fn small_lists_benchmark(c: &mut Criterion) {
+    let data = positions();
+
+    c.bench_function("small lists into Vec", |b| {
+        b.iter(|| {
+            let entities: Entities<Vec<f64>> = fastnbt::from_bytes(&data).unwrap();
+            black_box(entities);
+        });
+    });
+
+    c.bench_function("small lists into SmallVec", |b| {
+        b.iter(|| {
+            let entities: Entities<SmallVec<[f64; 3]>> = fastnbt::from_bytes(&data).unwrap();
+            black_box(entities);
+        });
+    });
+}
+
+criterion_group!(benches, small_lists_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,81 @@
+//! Compares decoding tags with the `match` in `Tag::try_from` against a lookup
+//! table, over the tags of a whole chunk. Parsing the same chunk into a
+//! `Value` is benchmarked alongside, to show how much of parsing that is.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fastnbt::{
+    stream::{Parser, ValueRef},
+    Tag, Value,
+};
+
+const CHUNK: &[u8] = include_bytes!("../src/test/resources/chunk.nbt");
+
+// The table this compares against, in the order of the tag ids. The test
+// `tag_table_matches_try_from` checks it decodes the same as the match.
+static TAGS: [Option<Tag>; 256] = {
+    let mut table = [None; 256];
+    let mut i = 0;
+    while i < Tag::ALL.len() {
+        table[i] = Some(Tag::ALL[i]);
+        i += 1;
+    }
+    table
+};
+
+/// The tags of every value in the chunk, in the order they are parsed.
+fn chunk_tags() -> Vec<u8> {
+    let mut parser = Parser::new(CHUNK);
+    let mut tags = vec![];
+    while let Ok(value) = parser.next_ref() {
+        let tag = match value {
+            ValueRef::CompoundEnd => Tag::End,
+            ValueRef::Byte(..) => Tag::Byte,
+            ValueRef::Short(..) => Tag::Short,
+            ValueRef::Int(..) => Tag::Int,
+            ValueRef::Long(..) => Tag::Long,
+            ValueRef::Float(..) => Tag::Float,
+            ValueRef::Double(..) => Tag::Double,
+            ValueRef::ByteArray(..) => Tag::ByteArray,
+            ValueRef::String(..) => Tag::String,
+            ValueRef::List(..) => Tag::List,
+            ValueRef::ListEnd => continue,
+            ValueRef::Compound(_) => Tag::Compound,
+            ValueRef::IntArray(..) => Tag::IntArray,
+            ValueRef::LongArray(..) => Tag::LongArray,
+        };
+        tags.push(u8::from(tag));
+    }
+    tags
+}
+
+pub fn tag_decoding_benchmark(c: &mut Criterion) {
+    let tags = chunk_tags();
+
+    c.bench_function("tags by match", |b| {
+        b.iter(|| {
+            for &t in black_box(&tags) {
+                black_box(Tag::try_from(t).ok());
+            }
+        });
+    });
+
+    c.bench_function("tags by table", |b| {
+        b.iter(|| {
+            for &t in black_box(&tags) {
+                black_box(TAGS[t as usize]);
+            }
+        });
+    });
+
+    c.bench_function("chunk into Value", |b| {
+        b.iter(|| {
+            let chunk: Value = fastnbt::from_bytes(black_box(CHUNK)).unwrap();
+            black_box(chunk);
+        });
+    });
+}
+
+criterion_group!(benches, tag_decoding_benchmark);
+criterion_main!(benches);
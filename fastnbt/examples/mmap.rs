@@ -0,0 +1,84 @@
+#![allow(dead_code)]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use fastnbt::borrow::LongArray;
+use fastnbt::from_bytes;
+use memmap2::Mmap;
+use serde::Deserialize;
+
+// This example shows deserializing an uncompressed NBT file by memory mapping
+// it rather than reading it into a buffer. Borrowed fields point directly into
+// the mapped file, so deserializing allocates nothing at all, which we check
+// by counting allocations.
+//
+// Chunks inside region files are compressed, so this is most useful for NBT
+// you have stored uncompressed yourself, eg a cache of extracted chunks.
+//
+// cargo run --example mmap -- src/test/resources/chunk.nbt
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// The layout of chunks before Minecraft 1.18, like the bundled
+// src/test/resources/chunk.nbt.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Chunk<'a> {
+    data_version: i32,
+
+    #[serde(borrow)]
+    level: Level<'a>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Level<'a> {
+    #[serde(borrow)]
+    status: &'a str,
+
+    #[serde(borrow)]
+    heightmaps: Option<Heightmaps<'a>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Heightmaps<'a> {
+    #[serde(borrow, rename = "WORLD_SURFACE")]
+    world_surface: Option<LongArray<'a>>,
+}
+
+fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let file = std::fs::File::open(&args[0]).unwrap();
+
+    // Safety: the file must not be modified while it is mapped.
+    let mmap = unsafe { Mmap::map(&file) }.unwrap();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let chunk: Chunk = from_bytes(&mmap).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    assert_eq!(allocations, 0);
+
+    println!("data version: {}", chunk.data_version);
+    println!("status: {}", chunk.level.status);
+    if let Some(surface) = chunk.level.heightmaps.and_then(|h| h.world_surface) {
+        println!("world surface: {} longs", surface.iter().count());
+    }
+    println!("allocations while deserializing: {}", allocations);
+}
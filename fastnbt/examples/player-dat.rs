@@ -0,0 +1,42 @@
+use fastnbt::minecraft::read_player_dat;
+
+// This example prints a summary of a player from a player.dat file, found in
+// the world/playerdata directory.
+//
+// cargo run --example player-dat --features minecraft -- player.dat
+
+fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let file = std::fs::File::open(&args[0]).unwrap();
+    let player = read_player_dat(file).unwrap();
+
+    let (x, y, z) = player.pos;
+    println!("position: {:.1} {:.1} {:.1}", x, y, z);
+    if let Some(dimension) = &player.dimension {
+        println!("dimension: {}", dimension);
+    }
+    println!("health: {}", player.health);
+    println!("level: {}", player.xp_level);
+
+    println!("inventory:");
+    for item in &player.inventory {
+        println!(
+            "  {:>3} {} x{}",
+            item.slot.unwrap_or(-1),
+            item.id,
+            item.count
+        );
+    }
+
+    println!("ender chest:");
+    for item in &player.ender_items {
+        println!(
+            "  {:>3} {} x{}",
+            item.slot.unwrap_or(-1),
+            item.id,
+            item.count
+        );
+    }
+
+    println!("other fields: {}", player.other.len());
+}
@@ -0,0 +1,324 @@
+//! This module contains [`ArenaValue`], a value type like [`Value`] that
+//! allocates from a [`bumpalo::Bump`] arena. This requires the `bumpalo`
+//! feature.
+//!
+//! Parsing many small documents into [`Value`] allocates for every string,
+//! list, compound and array, and frees each of them again when the value is
+//! dropped. Parsing into an arena instead makes each allocation a pointer bump,
+//! and everything parsed into the arena is freed at once when the arena is
+//! reset or dropped.
+//!
+//! # Lifetimes
+//!
+//! An `ArenaValue<'bump>` borrows from the arena it was parsed into, so the
+//! arena must outlive it, and the arena cannot be reset while any value parsed
+//! into it is still alive. Values do not borrow from the input, which can be
+//! dropped or reused straight after parsing.
+//!
+//! Nothing in an `ArenaValue` needs dropping, which is why compounds are a
+//! slice of entries rather than a map. Key lookup with
+//! [`get`][`ArenaValue::get`] is a linear search, which is fast for the small
+//! compounds this type is meant for.
+//!
+//! ```
+//! use bumpalo::Bump;
+//! use fastnbt::{arena::ArenaValue, nbt};
+//!
+//! # fn main() -> fastnbt::error::Result<()> {
+//! let documents: Vec<Vec<u8>> = (0..100)
+//!     .map(|i| fastnbt::to_bytes(&nbt!({"id": "minecraft:stone", "Count": i})).unwrap())
+//!     .collect();
+//!
+//! let mut bump = Bump::new();
+//! for chunk in documents.chunks(10) {
+//!     for data in chunk {
+//!         let value = fastnbt::arena::from_bytes_in(data, &bump)?;
+//!         assert_eq!(value.get("id"), Some(&ArenaValue::String("minecraft:stone")));
+//!     }
+//!     // Free everything parsed so far in one go.
+//!     bump.reset();
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use bumpalo::{collections::Vec as BumpVec, Bump};
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+
+use crate::{
+    de::Deserializer, error::Result, value::VALUE_TOKEN, ByteArray, DeOpts, IntArray, LongArray,
+    Tag, Value,
+};
+
+/// A value like [`Value`] whose strings, lists, compounds and arrays are
+/// allocated in a [`Bump`]. See the [module documentation][`crate::arena`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'bump> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(&'bump str),
+    ByteArray(&'bump [i8]),
+    IntArray(&'bump [i32]),
+    LongArray(&'bump [i64]),
+    List(&'bump [ArenaValue<'bump>]),
+    /// The entries of a compound, in the order they were parsed.
+    Compound(&'bump [(&'bump str, ArenaValue<'bump>)]),
+}
+
+impl<'bump> ArenaValue<'bump> {
+    /// The tag of this value.
+    pub fn tag(&self) -> Tag {
+        match self {
+            ArenaValue::Byte(_) => Tag::Byte,
+            ArenaValue::Short(_) => Tag::Short,
+            ArenaValue::Int(_) => Tag::Int,
+            ArenaValue::Long(_) => Tag::Long,
+            ArenaValue::Float(_) => Tag::Float,
+            ArenaValue::Double(_) => Tag::Double,
+            ArenaValue::String(_) => Tag::String,
+            ArenaValue::ByteArray(_) => Tag::ByteArray,
+            ArenaValue::IntArray(_) => Tag::IntArray,
+            ArenaValue::LongArray(_) => Tag::LongArray,
+            ArenaValue::List(_) => Tag::List,
+            ArenaValue::Compound(_) => Tag::Compound,
+        }
+    }
+
+    /// Get the value of `key` if this is a compound containing it. If the key
+    /// appears more than once the first is returned.
+    pub fn get(&self, key: &str) -> Option<&ArenaValue<'bump>> {
+        match self {
+            ArenaValue::Compound(entries) => {
+                entries.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Copy this value out of the arena into a [`Value`].
+    pub fn to_value(&self) -> Value {
+        match *self {
+            ArenaValue::Byte(v) => Value::Byte(v),
+            ArenaValue::Short(v) => Value::Short(v),
+            ArenaValue::Int(v) => Value::Int(v),
+            ArenaValue::Long(v) => Value::Long(v),
+            ArenaValue::Float(v) => Value::Float(v),
+            ArenaValue::Double(v) => Value::Double(v),
+            ArenaValue::String(v) => Value::String(v.to_owned()),
+            ArenaValue::ByteArray(v) => Value::ByteArray(ByteArray::new(v.to_vec())),
+            ArenaValue::IntArray(v) => Value::IntArray(IntArray::new(v.to_vec())),
+            ArenaValue::LongArray(v) => Value::LongArray(LongArray::new(v.to_vec())),
+            ArenaValue::List(v) => Value::List(v.iter().map(ArenaValue::to_value).collect()),
+            ArenaValue::Compound(v) => Value::Compound(
+                v.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Parse NBT data into an [`ArenaValue`] allocated in `bump`.
+pub fn from_bytes_in<'bump>(input: &[u8], bump: &'bump Bump) -> Result<ArenaValue<'bump>> {
+    from_bytes_in_with_opts(input, bump, Default::default())
+}
+
+/// Similar to [`from_bytes_in`] but with options.
+pub fn from_bytes_in_with_opts<'bump>(
+    input: &[u8],
+    bump: &'bump Bump,
+    opts: DeOpts,
+) -> Result<ArenaValue<'bump>> {
+    let mut des = Deserializer::from_bytes(input, opts);
+    ValueSeed { bump }.deserialize(&mut des)
+}
+
+struct ValueSeed<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'de, 'bump> DeserializeSeed<'de> for ValueSeed<'bump> {
+    type Value = ArenaValue<'bump>;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, self)
+    }
+}
+
+impl<'de, 'bump> Visitor<'de> for ValueSeed<'bump> {
+    type Value = ArenaValue<'bump>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("valid NBT")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_i8<E: serde::de::Error>(self, v: i8) -> std::result::Result<Self::Value, E> {
+        Ok(ArenaValue::Byte(v))
+    }
+
+    fn visit_i16<E: serde::de::Error>(self, v: i16) -> std::result::Result<Self::Value, E> {
+        Ok(ArenaValue::Short(v))
+    }
+
+    fn visit_i32<E: serde::de::Error>(self, v: i32) -> std::result::Result<Self::Value, E> {
+        Ok(ArenaValue::Int(v))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> std::result::Result<Self::Value, E> {
+        Ok(ArenaValue::Long(v))
+    }
+
+    fn visit_f32<E: serde::de::Error>(self, v: f32) -> std::result::Result<Self::Value, E> {
+        Ok(ArenaValue::Float(v))
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> std::result::Result<Self::Value, E> {
+        Ok(ArenaValue::Double(v))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
+        Ok(ArenaValue::String(self.bump.alloc_str(v)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut list = BumpVec::with_capacity_in(seq.size_hint().unwrap_or(0), self.bump);
+        while let Some(el) = seq.next_element_seed(ValueSeed { bump: self.bump })? {
+            list.push(el);
+        }
+        Ok(ArenaValue::List(list.into_bump_slice()))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let bump = self.bump;
+        let mut compound = BumpVec::new_in(bump);
+
+        while let Some(key) = map.next_key_seed(KeySeed { bump })? {
+            let key = match key {
+                Key::Compound(key) => key,
+                Key::ByteArray => {
+                    let seed = ArraySeed::new(bump, |[b]: [u8; 1]| b as i8);
+                    return map.next_value_seed(seed).map(ArenaValue::ByteArray);
+                }
+                Key::IntArray => {
+                    let seed = ArraySeed::new(bump, i32::from_be_bytes);
+                    return map.next_value_seed(seed).map(ArenaValue::IntArray);
+                }
+                Key::LongArray => {
+                    let seed = ArraySeed::new(bump, i64::from_be_bytes);
+                    return map.next_value_seed(seed).map(ArenaValue::LongArray);
+                }
+            };
+
+            compound.push((key, map.next_value_seed(ValueSeed { bump })?));
+        }
+
+        Ok(ArenaValue::Compound(compound.into_bump_slice()))
+    }
+}
+
+enum Key<'bump> {
+    Compound(&'bump str),
+    ByteArray,
+    IntArray,
+    LongArray,
+}
+
+struct KeySeed<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'de, 'bump> DeserializeSeed<'de> for KeySeed<'bump> {
+    type Value = Key<'bump>;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, 'bump> Visitor<'de> for KeySeed<'bump> {
+    type Value = Key<'bump>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an nbt field string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> std::result::Result<Self::Value, E> {
+        match s {
+            crate::BYTE_ARRAY_TOKEN => Ok(Key::ByteArray),
+            crate::INT_ARRAY_TOKEN => Ok(Key::IntArray),
+            crate::LONG_ARRAY_TOKEN => Ok(Key::LongArray),
+            _ => Ok(Key::Compound(self.bump.alloc_str(s))),
+        }
+    }
+}
+
+/// The elements of an array, converted from the big endian bytes given by the
+/// deserializer straight into the arena.
+struct ArraySeed<'bump, T, const N: usize> {
+    bump: &'bump Bump,
+    from_be_bytes: fn([u8; N]) -> T,
+}
+
+impl<'bump, T, const N: usize> ArraySeed<'bump, T, N> {
+    fn new(bump: &'bump Bump, from_be_bytes: fn([u8; N]) -> T) -> Self {
+        Self {
+            bump,
+            from_be_bytes,
+        }
+    }
+}
+
+impl<'de, 'bump, T: 'bump, const N: usize> DeserializeSeed<'de> for ArraySeed<'bump, T, N> {
+    type Value = &'bump [T];
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de, 'bump, T: 'bump, const N: usize> Visitor<'de> for ArraySeed<'bump, T, N> {
+    type Value = &'bump [T];
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("nbt array bytes")
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
+        if !v.len().is_multiple_of(N) {
+            return Err(E::custom(
+                "array length is not a multiple of its element size",
+            ));
+        }
+        let from_be_bytes = self.from_be_bytes;
+        Ok(self.bump.alloc_slice_fill_iter(
+            v.chunks_exact(N)
+                .map(|c| from_be_bytes(c.try_into().unwrap())),
+        ))
+    }
+}
@@ -11,8 +11,13 @@ pub(crate) const LONG_ARRAY_TOKEN: &str = "__fastnbt_long_array";
 /// NBT ByteArray that owns its data. This type preserves the exact NBT type
 /// when (de)serializing. This dereferences into a i8 slice, so should be usable
 /// basically anywhere a slice should be.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "arbitrary1", derive(arbitrary::Arbitrary))]
+///
+/// Byte arrays are often large blobs of data, so the `Debug` output shows the
+/// length and the first 32 bytes in hex rather than every element, eg
+/// `ByteArray(len: 4096, 0001ff…)`. Use [`to_hex`][`ByteArray::to_hex`] for
+/// all of it.
+#[derive(Clone, PartialEq, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ByteArray {
     data: Vec<i8>,
 }
@@ -78,6 +83,35 @@ impl ByteArray {
         self.data
     }
 
+    /// Create an empty ByteArray with space for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// The number of elements the array can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Append an element to the end of the array.
+    pub fn push(&mut self, value: i8) {
+        self.data.push(value);
+    }
+
+    /// Remove all elements from the array.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// View the data of this ByteArray as unsigned bytes. This does not copy
+    /// the data, so is useful for hashing or writing the array elsewhere.
+    pub fn as_bytes(&self) -> &[u8] {
+        // Safe to treat [i8] as [u8].
+        unsafe { &*(self.data.as_slice() as *const [i8] as *const [u8]) }
+    }
+
     /// Produce a ByteArray from raw data.
     pub(crate) fn from_bytes(data: &[u8]) -> Self {
         // Safe to treat [u8] as [i8].
@@ -89,18 +123,64 @@ impl ByteArray {
 
     /// Produce a ByteArray from raw data.
     pub(crate) fn from_buf(data: Vec<u8>) -> Self {
-        // TODO: Remove copy.
-        let data = data.as_slice();
+        // Safe to treat Vec<u8> as Vec<i8>, they have the same layout. We avoid
+        // Vec::into_raw_parts as it is unstable.
+        let mut data = std::mem::ManuallyDrop::new(data);
+        let (p, len, cap) = (data.as_mut_ptr(), data.len(), data.capacity());
 
-        // Safe to treat [u8] as [i8].
-        let data = unsafe { &*(data as *const [u8] as *const [i8]) };
         ByteArray {
-            data: data.to_owned(),
+            data: unsafe { Vec::from_raw_parts(p as *mut i8, len, cap) },
         }
     }
 
     pub(crate) fn to_bytes(&self) -> Vec<u8> {
-        self.data.iter().flat_map(|i| i.to_be_bytes()).collect()
+        self.as_bytes().to_vec()
+    }
+
+    /// The data of this ByteArray as lowercase hex, two digits per byte.
+    ///
+    /// ```
+    /// # use fastnbt::ByteArray;
+    /// assert_eq!(ByteArray::new(vec![1, -1, 16]).to_hex(), "01ff10");
+    /// ```
+    pub fn to_hex(&self) -> String {
+        let mut hex = String::with_capacity(self.len() * 2);
+        write_hex(&mut hex, self.as_bytes()).expect("writing to a string cannot fail");
+        hex
+    }
+}
+
+/// The number of bytes shown by the `Debug` implementation of [`ByteArray`].
+const DEBUG_BYTES: usize = 32;
+
+impl std::fmt::Debug for ByteArray {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bytes = self.as_bytes();
+        write!(f, "ByteArray(len: {}, ", bytes.len())?;
+        write_hex(f, &bytes[..bytes.len().min(DEBUG_BYTES)])?;
+        if bytes.len() > DEBUG_BYTES {
+            f.write_str("…")?;
+        }
+        f.write_str(")")
+    }
+}
+
+fn write_hex(w: &mut impl std::fmt::Write, bytes: &[u8]) -> std::fmt::Result {
+    for b in bytes {
+        write!(w, "{:02x}", b)?;
+    }
+    Ok(())
+}
+
+impl From<Vec<u8>> for ByteArray {
+    fn from(data: Vec<u8>) -> Self {
+        ByteArray::from_buf(data)
+    }
+}
+
+impl From<&[u8]> for ByteArray {
+    fn from(data: &[u8]) -> Self {
+        ByteArray::from_bytes(data)
     }
 }
 
@@ -118,11 +198,47 @@ impl DerefMut for ByteArray {
     }
 }
 
+impl AsRef<[i8]> for ByteArray {
+    fn as_ref(&self) -> &[i8] {
+        &self.data
+    }
+}
+
+impl IntoIterator for ByteArray {
+    type Item = i8;
+    type IntoIter = std::vec::IntoIter<i8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ByteArray {
+    type Item = &'a i8;
+    type IntoIter = std::slice::Iter<'a, i8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl Extend<i8> for ByteArray {
+    fn extend<I: IntoIterator<Item = i8>>(&mut self, iter: I) {
+        self.data.extend(iter);
+    }
+}
+
+impl<'a> Extend<&'a i8> for ByteArray {
+    fn extend<I: IntoIterator<Item = &'a i8>>(&mut self, iter: I) {
+        self.data.extend(iter);
+    }
+}
+
 /// NBT IntArray that owns its data. This type preserves the exact NBT type
 /// when (de)serializing. This dereferences into a i32 slice, so should be usable
 /// basically anywhere a slice should be.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "arbitrary1", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct IntArray {
     data: Vec<i32>,
 }
@@ -138,6 +254,28 @@ impl IntArray {
         self.data
     }
 
+    /// Create an empty IntArray with space for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// The number of elements the array can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Append an element to the end of the array.
+    pub fn push(&mut self, value: i32) {
+        self.data.push(value);
+    }
+
+    /// Remove all elements from the array.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
     /// Produce a IntArray from raw data. This data should be big endian!
     pub(crate) fn from_bytes(data: &[u8]) -> std::io::Result<Self> {
         let data = data
@@ -217,11 +355,47 @@ impl DerefMut for IntArray {
     }
 }
 
+impl AsRef<[i32]> for IntArray {
+    fn as_ref(&self) -> &[i32] {
+        &self.data
+    }
+}
+
+impl IntoIterator for IntArray {
+    type Item = i32;
+    type IntoIter = std::vec::IntoIter<i32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a IntArray {
+    type Item = &'a i32;
+    type IntoIter = std::slice::Iter<'a, i32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl Extend<i32> for IntArray {
+    fn extend<I: IntoIterator<Item = i32>>(&mut self, iter: I) {
+        self.data.extend(iter);
+    }
+}
+
+impl<'a> Extend<&'a i32> for IntArray {
+    fn extend<I: IntoIterator<Item = &'a i32>>(&mut self, iter: I) {
+        self.data.extend(iter);
+    }
+}
+
 /// NBT LongArray that owns its data. This type preserves the exact NBT type
 /// when (de)serializing. This dereferences into a i64 slice, so should be usable
 /// basically anywhere a slice should be.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "arbitrary1", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct LongArray {
     data: Vec<i64>,
 }
@@ -237,6 +411,61 @@ impl LongArray {
         self.data
     }
 
+    /// Create an empty LongArray with space for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// The number of elements the array can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Append an element to the end of the array.
+    pub fn push(&mut self, value: i64) {
+        self.data.push(value);
+    }
+
+    /// Remove all elements from the array.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// The runs of repeated values in the array, as each value and the number
+    /// of times it repeats, in order.
+    ///
+    /// ```
+    /// # use fastnbt::LongArray;
+    /// let arr = LongArray::new(vec![0, 0, 0, 7, 0]);
+    /// assert_eq!(arr.run_length_summary(), [(0, 3), (7, 1), (0, 1)]);
+    /// ```
+    pub fn run_length_summary(&self) -> Vec<(i64, usize)> {
+        let mut runs: Vec<(i64, usize)> = vec![];
+        for &v in &self.data {
+            match runs.last_mut() {
+                Some((last, n)) if *last == v => *n += 1,
+                _ => runs.push((v, 1)),
+            }
+        }
+        runs
+    }
+
+    /// The value every element of the array has, if they are all the same.
+    /// This is `None` for an empty array. A section of block states that is
+    /// all air, for example, is uniform.
+    ///
+    /// ```
+    /// # use fastnbt::LongArray;
+    /// assert_eq!(LongArray::new(vec![0; 256]).is_uniform(), Some(0));
+    /// assert_eq!(LongArray::new(vec![0, 1]).is_uniform(), None);
+    /// ```
+    pub fn is_uniform(&self) -> Option<i64> {
+        let (first, rest) = self.data.split_first()?;
+        rest.iter().all(|v| v == first).then_some(*first)
+    }
+
     pub(crate) fn from_bytes(data: &[u8]) -> std::io::Result<Self> {
         let data = data
             .chunks_exact(8)
@@ -314,3 +543,89 @@ impl DerefMut for LongArray {
         &mut self.data
     }
 }
+
+impl AsRef<[i64]> for LongArray {
+    fn as_ref(&self) -> &[i64] {
+        &self.data
+    }
+}
+
+impl IntoIterator for LongArray {
+    type Item = i64;
+    type IntoIter = std::vec::IntoIter<i64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a LongArray {
+    type Item = &'a i64;
+    type IntoIter = std::slice::Iter<'a, i64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl Extend<i64> for LongArray {
+    fn extend<I: IntoIterator<Item = i64>>(&mut self, iter: I) {
+        self.data.extend(iter);
+    }
+}
+
+impl<'a> Extend<&'a i64> for LongArray {
+    fn extend<I: IntoIterator<Item = &'a i64>>(&mut self, iter: I) {
+        self.data.extend(iter);
+    }
+}
+
+// Compare the elements with slices and vecs of them, eg
+// `assert_eq!(array, [1, 2, 3])` in tests.
+macro_rules! partialeq_slice {
+    ($($array:ident [$el:ty])*) => {
+        $(
+            impl PartialEq<[$el]> for $array {
+                fn eq(&self, other: &[$el]) -> bool {
+                    self.data[..] == *other
+                }
+            }
+
+            impl<'a> PartialEq<&'a [$el]> for $array {
+                fn eq(&self, other: &&'a [$el]) -> bool {
+                    self.data[..] == **other
+                }
+            }
+
+            impl<const N: usize> PartialEq<[$el; N]> for $array {
+                fn eq(&self, other: &[$el; N]) -> bool {
+                    self.data[..] == other[..]
+                }
+            }
+
+            impl PartialEq<Vec<$el>> for $array {
+                fn eq(&self, other: &Vec<$el>) -> bool {
+                    self.data == *other
+                }
+            }
+
+            impl PartialEq<$array> for [$el] {
+                fn eq(&self, other: &$array) -> bool {
+                    *self == other.data[..]
+                }
+            }
+
+            impl PartialEq<$array> for Vec<$el> {
+                fn eq(&self, other: &$array) -> bool {
+                    *self == other.data
+                }
+            }
+        )*
+    };
+}
+
+partialeq_slice! {
+    ByteArray[i8]
+    IntArray[i32]
+    LongArray[i64]
+}
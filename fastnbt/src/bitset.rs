@@ -0,0 +1,93 @@
+//! This module allows a [`LongArray`] to be (de)serialized as a `Vec<bool>`,
+//! which is how Java's `BitSet` is typically stored in NBT. Bit 0 of the first
+//! long is index 0, bit 63 of the first long is index 63, bit 0 of the second
+//! long is index 64, and so forth.
+//!
+//! Use it with serde's `with` attribute:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Section {
+//!     #[serde(with = "fastnbt::bitset")]
+//!     mask: Vec<bool>,
+//! }
+//! ```
+//!
+//! Deserializing gives every bit of the array, so the length is always a
+//! multiple of 64. If the data has a known number of bits, use
+//! [`deserialize_len`] to drop the trailing unused bits:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Section {
+//!     #[serde(
+//!         serialize_with = "fastnbt::bitset::serialize",
+//!         deserialize_with = "fastnbt::bitset::deserialize_len::<_, 24>"
+//!     )]
+//!     present: Vec<bool>,
+//! }
+//! ```
+//!
+//! When serializing, any trailing bits in the final long are zero.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::LongArray;
+
+/// Expand longs into bits. `len` is the number of bits wanted, the result is
+/// shorter if `longs` does not contain that many bits.
+pub fn from_longs(longs: &[i64], len: usize) -> Vec<bool> {
+    (0..len.min(longs.len() * 64))
+        .map(|i| (longs[i / 64] as u64 >> (i % 64)) & 1 == 1)
+        .collect()
+}
+
+/// Pack bits into longs. The final long is padded with zero bits.
+pub fn to_longs(bits: &[bool]) -> Vec<i64> {
+    bits.chunks(64)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, bit)| acc | ((*bit as u64) << i)) as i64
+        })
+        .collect()
+}
+
+/// Serialize bits as a [`LongArray`].
+pub fn serialize<S>(bits: &[bool], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    LongArray::new(to_longs(bits)).serialize(serializer)
+}
+
+/// Deserialize every bit of a [`LongArray`].
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let longs = LongArray::deserialize(deserializer)?;
+    Ok(from_longs(&longs, longs.len() * 64))
+}
+
+/// Deserialize the first `LEN` bits of a [`LongArray`]. It is an error for the
+/// array to have fewer than `LEN` bits.
+pub fn deserialize_len<'de, D, const LEN: usize>(deserializer: D) -> Result<Vec<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let longs = LongArray::deserialize(deserializer)?;
+    if longs.len() * 64 < LEN {
+        return Err(serde::de::Error::custom(format!(
+            "bitset: expected at least {} bits, found {}",
+            LEN,
+            longs.len() * 64
+        )));
+    }
+    Ok(from_longs(&longs, LEN))
+}
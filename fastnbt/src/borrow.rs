@@ -14,7 +14,17 @@
 //! [`IntArray`][`crate::IntArray`], [`LongArray`][`crate::LongArray`]`}`.
 //!
 //! The `iter()` methods return an iterator to the values read on demand from an
-//! internal reference to the input data.
+//! internal reference to the input data. Similarly `get(i)` decodes only
+//! element `i`, so sparse access into a large array is cheap.
+//!
+//! Unlike the owned arrays, these do not dereference to slices. The input
+//! stores ints and longs big endian, and borrowing it means never decoding it
+//! into a native slice. Use the owned versions for slice methods such as
+//! `windows` and `chunks`.
+//!
+//! To keep the data past the lifetime of the input, convert to the owned
+//! versions with `into_owned()` or [`From`]. This copies the data into a new
+//! allocation.
 //!
 //! # Example
 //!
@@ -70,6 +80,33 @@ impl<'a> ByteArray<'a> {
     pub(crate) fn from_bytes(data: &'a [u8]) -> Self {
         Self { data }
     }
+
+    /// Get the byte at index `i`, or `None` if it is out of bounds.
+    pub fn get(&self, i: usize) -> Option<i8> {
+        self.data.get(i).map(|b| *b as i8)
+    }
+
+    /// The number of bytes in the array.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the array has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Copy the data into an owned [`ByteArray`][`crate::ByteArray`]. This
+    /// allocates.
+    pub fn into_owned(self) -> crate::ByteArray {
+        crate::ByteArray::from_bytes(self.data)
+    }
+}
+
+impl<'a> From<ByteArray<'a>> for crate::ByteArray {
+    fn from(array: ByteArray<'a>) -> Self {
+        array.into_owned()
+    }
 }
 
 impl<'a, 'de: 'a> Deserialize<'de> for ByteArray<'a> {
@@ -146,6 +183,28 @@ enum ArrayRef<'a, T> {
     BigEndian(&'a [u8]),
 }
 
+impl<'a, T: Copy> ArrayRef<'a, T> {
+    fn len(&self) -> usize {
+        match self {
+            ArrayRef::NativeEndian(data) => data.len(),
+            ArrayRef::BigEndian(data) => data.len() / std::mem::size_of::<T>(),
+        }
+    }
+
+    /// Decode element `i`, using `from_be` to decode it from its big endian
+    /// bytes if needed. Only element `i` is read.
+    fn get<const N: usize>(&self, i: usize, from_be: fn([u8; N]) -> T) -> Option<T> {
+        match self {
+            ArrayRef::NativeEndian(data) => data.get(i).copied(),
+            ArrayRef::BigEndian(data) => {
+                let start = i.checked_mul(N)?;
+                let bytes = data.get(start..start.checked_add(N)?)?;
+                Some(from_be(bytes.try_into().ok()?))
+            }
+        }
+    }
+}
+
 impl<'a> IntArray<'a> {
     /// Create an iterator over the i32s
     pub fn iter(&self) -> IntIter<'a> {
@@ -163,6 +222,34 @@ impl<'a> IntArray<'a> {
             data: ArrayRef::BigEndian(data),
         }
     }
+
+    /// Get the i32 at index `i`, or `None` if it is out of bounds. Only this
+    /// element is decoded, so this is cheap even for large arrays.
+    pub fn get(&self, i: usize) -> Option<i32> {
+        self.data.get(i, i32::from_be_bytes)
+    }
+
+    /// The number of i32s in the array.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the array has no i32s.
+    pub fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
+    /// Copy the data into an owned [`IntArray`][`crate::IntArray`]. This
+    /// allocates.
+    pub fn into_owned(self) -> crate::IntArray {
+        crate::IntArray::new(self.iter().collect())
+    }
+}
+
+impl<'a> From<IntArray<'a>> for crate::IntArray {
+    fn from(array: IntArray<'a>) -> Self {
+        array.into_owned()
+    }
 }
 
 impl<'a, 'de: 'a> Deserialize<'de> for IntArray<'a> {
@@ -265,6 +352,34 @@ impl<'a> LongArray<'a> {
             data: ArrayRef::BigEndian(data),
         }
     }
+
+    /// Get the i64 at index `i`, or `None` if it is out of bounds. Only this
+    /// element is decoded, so this is cheap even for large arrays.
+    pub fn get(&self, i: usize) -> Option<i64> {
+        self.data.get(i, i64::from_be_bytes)
+    }
+
+    /// The number of i64s in the array.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the array has no i64s.
+    pub fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
+    /// Copy the data into an owned [`LongArray`][`crate::LongArray`]. This
+    /// allocates.
+    pub fn into_owned(self) -> crate::LongArray {
+        crate::LongArray::new(self.iter().collect())
+    }
+}
+
+impl<'a> From<LongArray<'a>> for crate::LongArray {
+    fn from(array: LongArray<'a>) -> Self {
+        array.into_owned()
+    }
 }
 
 impl<'a, 'de: 'a> Deserialize<'de> for LongArray<'a> {
@@ -397,3 +512,42 @@ where
     let wrapper = CowStr::deserialize(deserializer)?;
     Ok(wrapper.0)
 }
+
+// Compare the decoded elements with slices and vecs of them, eg
+// `assert_eq!(array, [1, 2, 3])` in tests. The data may be big endian bytes, so
+// each element is decoded rather than comparing the bytes.
+macro_rules! partialeq_slice {
+    ($($array:ident [$el:ty])*) => {
+        $(
+            impl<'a> PartialEq<[$el]> for $array<'a> {
+                fn eq(&self, other: &[$el]) -> bool {
+                    self.len() == other.len() && self.iter().eq(other.iter().copied())
+                }
+            }
+
+            impl<'a, 'b> PartialEq<&'b [$el]> for $array<'a> {
+                fn eq(&self, other: &&'b [$el]) -> bool {
+                    *self == **other
+                }
+            }
+
+            impl<'a, const N: usize> PartialEq<[$el; N]> for $array<'a> {
+                fn eq(&self, other: &[$el; N]) -> bool {
+                    *self == other[..]
+                }
+            }
+
+            impl<'a> PartialEq<Vec<$el>> for $array<'a> {
+                fn eq(&self, other: &Vec<$el>) -> bool {
+                    *self == other[..]
+                }
+            }
+        )*
+    };
+}
+
+partialeq_slice! {
+    ByteArray[i8]
+    IntArray[i32]
+    LongArray[i64]
+}
@@ -0,0 +1,51 @@
+//! Serialize and deserialize a [`DateTime<Utc>`] as a Long of milliseconds
+//! since the Unix epoch, for use with `#[serde(with = "fastnbt::chrono_millis")]`.
+//! This requires the `chrono` feature.
+//!
+//! Minecraft stores times such as `LastPlayed` in `level.dat` this way. The
+//! milliseconds count from midnight on 1 January 1970 UTC, so the times are
+//! always UTC, convert them to a local time zone for display if needed.
+//!
+//! ```
+//! use chrono::{DateTime, TimeZone, Utc};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct Data {
+//!     #[serde(with = "fastnbt::chrono_millis")]
+//!     last_played: DateTime<Utc>,
+//! }
+//!
+//! let nbt = fastnbt::nbt!({"LastPlayed": 1_700_000_000_000_i64});
+//! let data: Data = fastnbt::from_value(&nbt).unwrap();
+//! assert_eq!(data.last_played, Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap());
+//!
+//! // Serializing gives the Long back.
+//! assert_eq!(fastnbt::to_value(&data).unwrap(), nbt);
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Serialize `time` as a Long of milliseconds since the Unix epoch. Any
+/// precision finer than a millisecond is dropped.
+pub fn serialize<S>(time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(time.timestamp_millis())
+}
+
+/// Deserialize a time from milliseconds since the Unix epoch. This accepts
+/// any integral NBT value, not only Longs. It is an error if the time cannot
+/// be represented by [`DateTime`].
+pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let millis = i64::deserialize(deserializer)?;
+    DateTime::from_timestamp_millis(millis).ok_or_else(|| {
+        serde::de::Error::custom(format!("timestamp out of range: {} milliseconds", millis))
+    })
+}
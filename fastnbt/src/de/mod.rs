@@ -0,0 +1,1631 @@
+//! This module contains a serde deserializer. It can do most of the things you
+//! would expect of a typical serde deserializer, such as deserializing into:
+//! * Rust structs.
+//! * containers like `HashMap` and `Vec`. Compounds go through serde's map
+//!   path, so any map works, eg `BTreeMap` to have the keys sorted.
+//! * an arbitrary [`Value`][`crate::Value`].
+//! * enums. For NBT typically you want either internally or untagged enums.
+//!
+//! This deserializer supports [`from_bytes`][`crate::from_bytes`] for zero-copy
+//! deserialization for types like `&[u8]` and
+//! [`borrow::LongArray`][`crate::borrow::LongArray`]. There is also
+//! [`from_reader`][`crate::from_reader`] for deserializing from types
+//! implementing [`Read`][`std::io::Read`].
+//!
+//! For reading single payloads by hand, eg for formats that embed NBT values
+//! in a layout of their own, see [`primitives`].
+//!
+//! # Avoiding allocations
+//!
+//! When using [`from_bytes`][`crate::from_bytes`], we can avoid allocations for
+//! things like strings and vectors, instead deserializing into a reference to
+//! the input data.
+//!
+//! The following table summarises what types you likely want to store NBT data
+//! in for owned or borrowed types:
+//!
+//! | NBT type | Owned type | Borrowed type |
+//! | -------- | ---------- | ------------- |
+//! | Byte | `u8` or `i8` | use owned |
+//! | Short | `u16` or `i16` | use owned |
+//! | Int | `i32` or `u32` | use owned |
+//! | Long | `i64` or `u64` | use owned |
+//! | Float | `f32` | use owned |
+//! | Double | `f64` | use owned |
+//! | String | `String` | [`Cow<'a, str>`][`std::borrow::Cow`] or `&[u8]` (see below) |
+//! | List | `Vec<T>`, `Box<[T]>` or `Arc<[T]>` (see below) | use owned |
+//! | Byte Array | [`ByteArray`][`crate::ByteArray`] or `serde_bytes::ByteBuf` | [`borrow::ByteArray`][`crate::borrow::ByteArray`], `&[u8]` or `&serde_bytes::Bytes` |
+//! | Int Array | [`IntArray`][`crate::IntArray`] | [`borrow::IntArray`][`crate::borrow::IntArray`] |
+//! | Long Array | [`LongArray`][`crate::LongArray`] | [`borrow::LongArray`][`crate::borrow::LongArray`] |
+//!
+//! ## Lists
+//!
+//! NBT lists deserialize into anything serde treats as a sequence. This
+//! includes `Box<[T]>`, which avoids the spare capacity of a `Vec`, and
+//! `Rc<[T]>`/`Arc<[T]>` for sharing data such as palettes between threads. The
+//! latter two require serde's `rc` feature to be enabled in your `Cargo.toml`.
+//! To store an NBT array as a boxed slice, deserialize it as eg
+//! [`IntArray`][`crate::IntArray`] and use `into_inner().into_boxed_slice()`.
+//!
+//! Many lists are tiny, such as the 3 doubles of an entity position. With the
+//! `smallvec` feature enabled these can be deserialized into a
+//! [`SmallVec`](https://docs.rs/smallvec) like `SmallVec<[f64; 3]>`, which
+//! stores short lists inline rather than on the heap.
+//!
+//! ## Primitives
+//!
+//! Borrowing for primitive types like the integers and floats is generally not
+//! possible due to alignment requirements of those types. It likely wouldn't be
+//! faster/smaller anyway.
+//!
+//! ## Strings
+//!
+//! For strings, we cannot know ahead of time whether the data can be borrowed
+//! as `&str`. This is because Minecraft uses Java's encoding of Unicode, not
+//! UTF-8. If the string contains Unicode characters outside of the Basic
+//! Multilingual Plane then we need to convert it to UTF-8, requiring us to own
+//! the string data.
+//!
+//! Using [`Cow<'a, str>`][`std::borrow::Cow`] lets us borrow when possible, but
+//! produce an owned value when the representation is different.
+//!
+//! Strings can also be deserialized to `&[u8]` which will always succeed. These
+//! bytes will be Java's CESU-8 format. You can use [`cesu8::from_java_cesu8`]
+//! to decode this.
+//!
+//! ## Memory-mapped files
+//!
+//! [`from_bytes`][`crate::from_bytes`] only needs a `&[u8]`, so it works just
+//! as well over a memory-mapped file, eg with the
+//! [`memmap2`](https://docs.rs/memmap2) crate. The borrowed types above then
+//! point directly into the mapped file, and deserializing them allocates
+//! nothing. The file must be uncompressed NBT, and must not be modified while
+//! it is mapped. See `examples/mmap.rs`.
+//!
+//! # Reading from a `Read`
+//!
+//! [`from_reader`][`crate::from_reader`] parses straight from the reader as it
+//! goes, without reading the whole document into memory first. So for
+//! compressed data, passing the decompressing reader such as
+//! `flate2::read::GzDecoder` means the decompressed data is never held all at
+//! once. Memory use is that of the value being deserialized into, plus a
+//! buffer the size of the largest string or array it keeps. Values that are
+//! ignored, such as unknown keys of a struct, are skipped through a small
+//! buffer. Limits such as [`DeOpts::max_alloc`][`crate::DeOpts::max_alloc`]
+//! apply as they do for slices.
+//!
+//! Compared to [`from_bytes`][`crate::from_bytes`]:
+//! * Nothing can be borrowed, so the borrowed types in the table above cannot
+//!   be used, and every string and array kept is copied.
+//! * Each value is read with its own small read. Wrap readers that make a
+//!   system call per read, such as `File`, in a
+//!   [`BufReader`][`std::io::BufReader`]. Decompressing readers are fine as
+//!   they are.
+//! * It is usually slower than decompressing into a buffer and using
+//!   [`from_bytes`][`crate::from_bytes`], so prefer that when the
+//!   decompressed data comfortably fits in memory.
+//!
+//! # Untrusted input
+//!
+//! Malformed NBT gives an error rather than a panic, from both the
+//! deserializer and the [`stream`][`crate::stream`] parser. Lengths in the
+//! data are checked before use, so a negative or overflowing length is an
+//! error, and reading from a `Read` only allocates as the data actually
+//! arrives. What the input can make the deserializer allocate is still
+//! proportional to its size, so limit that or use
+//! [`DeOpts::max_alloc`][`crate::DeOpts::max_alloc`] and
+//! [`DeOpts::max_seq_len`][`crate::DeOpts::max_seq_len`].
+//!
+//! This is checked by fuzzing, and the inputs in `src/test/resources/fuzz` are
+//! tested on every run. They make a good starting corpus for `cargo fuzz`.
+//!
+//! # Representation of NBT arrays
+//!
+//! In order for [`Value`][`crate::Value`] to preserve all NBT information, the
+//! deserializer "[maps into serde's data
+//! model](https://serde.rs/data-model.html#mapping-into-the-data-model)". As a
+//! consequence of this, NBT array types must be serialized using the types
+//! provided in this crate, eg [LongArray][`crate::LongArray`]. Sequence
+//! containers like `Vec` will serialize to NBT Lists.
+//!
+//! When deserializing, sequences are lenient: a `Vec<i32>` accepts either an
+//! Int Array or a List of Ints, and likewise for bytes and longs. This helps
+//! with tools that write a List where vanilla Minecraft uses an Array. The
+//! array types are strict, so use [IntArray][`crate::IntArray`] to require
+//! exactly an Int Array. Tuples only deserialize from lists.
+//!
+//! # 128 bit integers and UUIDs
+//!
+//! UUIDs tend to be stored in NBT using 4-long IntArrays. When deserializing
+//! `i128` or `u128`, IntArray with length 4 are accepted. This is parsed as big
+//! endian i.e. the most significant bit (and int) is first.
+//!
+//! # Other quirks
+//!
+//! Some other quirks which may not be obvious:
+//! * When deserializing to unsigned types such as u32, it will be an error if a
+//!   value is negative to avoid unexpected behaviour with wrap-around. This
+//!   does not apply to deserializing lists of integrals to `u8` slice or
+//!   vectors. Likewise `NonZeroI32` and the other `NonZero*` types deserialize
+//!   from any integral value, erroring if it is zero.
+//! * Any integral value from NBT can be deserialized to bool. Any non-zero
+//!   value becomes `true`. Bear in mind serializing the same type will change
+//!   the NBT structure, likely unintended. Fields of internally tagged enums
+//!   need [`deserialize_bool`] for this.
+//! * Following from this, a `Vec<bool>` can be deserialized from a list of
+//!   bytes, as some data stores flags one per byte. Each byte that is not `0`
+//!   becomes `true`, not just `1`. Serializing it writes a list of bytes of
+//!   `0` and `1`.
+//! * Lists of lists, such as `Vec<Vec<i32>>`, can be jagged. Each inner list
+//!   has its own length and element tag, so an empty inner list may be a list
+//!   of `End` even if the others are lists of `Int`.
+//! * You can deserialize a field to the unit type `()` or unit struct. This
+//!   ignores the value but ensures that it existed.
+//! * NBT has no null, so an `Option` field is `None` only when its key is
+//!   missing. A key that is present is always `Some`, even if it holds an
+//!   empty compound or list, so eg `tag: Option<Value>` tells whether the
+//!   game wrote a `tag` at all.
+//! * Newtype structs such as `struct SlotId(i32)` deserialize as the type
+//!   they wrap, including as compound keys and as the root compound.
+//! * Other self-describing types such as `serde_json::Value` can be
+//!   deserialized from NBT, but reject NBT arrays unless
+//!   [`DeOpts::arrays_as_lists`][`crate::DeOpts::arrays_as_lists`] is set.
+//!   The types of numbers and arrays are lost, so use [`Value`][`crate::Value`]
+//!   when you need them.
+//! * Tuples and tuple structs deserialize from NBT lists. The list must have
+//!   exactly as many elements as the tuple, eg `(u8, u8, u8)` for an RGB
+//!   colour stored as a list of 3 bytes.
+//! * You cannot deserialize into anything other than a `struct` or similar
+//!   container eg `HashMap`. This is due to a misalignment between the NBT
+//!   format and Rust's types. Attempting to will give an error about no root
+//!   compound. This means you can never do `let s: String = from_bytes(...)`.
+//!   Serialization of a struct assumes an empty-named compound.
+//! * Some tools write a lone end tag, a single zero byte, as a placeholder for
+//!   empty NBT. Deserializing this gives an error for which
+//!   [`Error::is_empty_document`][`crate::error::Error::is_empty_document`] is
+//!   true, so it can be told apart from corrupt data. An end tag followed by
+//!   more data is not a placeholder, and gives an ordinary error. Input starting with any
+//!   other tag than a compound gives an error for which
+//!   [`Error::root_not_compound`][`crate::error::Error::root_not_compound`]
+//!   gives the tag found.
+//!
+//! # Example Minecraft types
+//!
+//! This section demonstrates writing types for a few real Minecraft structures.
+//!
+//! ## Extracting entities as an enum
+//!
+//! This demonstrates the type that you would need to write in order to extract
+//! some subset of entities. This uses a tagged enum in serde, meaning that it
+//! will look for a certain field in the structure to tell it what enum variant
+//! to deserialize into. We use serde's `other` attribute to not error when an
+//! unknown entity type is found.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug)]
+//! #[serde(tag = "id")]
+//! enum Entity {
+//!    #[serde(rename = "minecraft:bat")]
+//!    Bat {
+//!        #[serde(rename = "BatFlags")]
+//!        bat_flags: i8,
+//!    },
+//!
+//!    #[serde(rename = "minecraft:creeper")]
+//!    Creeper {
+//!        #[serde(deserialize_with = "fastnbt::de::deserialize_bool")]
+//!        ignited: bool,
+//!    },
+//!
+//!    // Entities we haven't coded end up as just 'unknown'.
+//!    #[serde(other)]
+//!    Unknown,
+//! }
+//! ```
+//!
+//! To find the tag, serde reads the whole compound into a buffer before
+//! deserializing the variant from it. This works for any field type with one
+//! exception: NBT has no booleans, and the buffered byte can no longer be
+//! turned into a `bool` the way it normally is. Use
+//! [`deserialize_bool`] for `bool` fields of internally tagged enums as above.
+//!
+//! ## Capture unknown entities
+//!
+//! If you need to capture all entity types, but do not wish to manually type
+//! all of them, you can wrap the above entity type in an untagged enum.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use fastnbt::Value;
+//!
+//! #[derive(Deserialize, Debug)]
+//! #[serde(untagged)]
+//! enum Entity {
+//!     Known(KnownEntity),
+//!     Unknown(Value),
+//! }
+
+//! #[derive(Deserialize, Debug)]
+//! #[serde(tag = "id")]
+//! enum KnownEntity {
+//!     #[serde(rename = "minecraft:bat")]
+//!     Bat {
+//!         #[serde(rename = "BatFlags")]
+//!         bat_flags: i8,
+//!     },
+
+//!     #[serde(rename = "minecraft:creeper")]
+//!     Creeper { ignited: i8 },
+//! }
+//! ```
+//!
+//! ## Avoiding allocations in a Chunk
+//!
+//! This example shows how to avoid some allocations. The `Section` type below
+//! contains the block states which stores the state of part of the Minecraft
+//! world. In NBT this is bit-packed data stored as an array of
+//! longs (i64). We avoid allocating a vector for this by storing it as a
+//! [`borrow::LongArray`][`crate::borrow::LongArray`] instead, which stores it
+//! as `&[u8]` under the hood. We can't safely store it as `&[i64]` due to memory
+//! alignment constraints. The `fastanvil` crate has a `PackedBits` type that can
+//! handle the unpacking of these block states.
+//!
+//! ```rust
+//! # use serde::Deserialize;
+//! use fastnbt::borrow::LongArray;
+//!
+//! #[derive(Deserialize)]
+//! struct Chunk<'a> {
+//!     #[serde(rename = "Level")]
+//!     #[serde(borrow)]
+//!     level: Level<'a>,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Level<'a> {
+//!     #[serde(rename = "Sections")]
+//!     #[serde(borrow)]
+//!     pub sections: Option<Vec<Section<'a>>>,
+//! }
+//!
+//! #[derive(Deserialize, Debug)]
+//! #[serde(rename_all = "PascalCase")]
+//! pub struct Section<'a> {
+//!     #[serde(borrow)]
+//!     pub block_states: Option<LongArray<'a>>,
+//! }
+//! ```
+//!
+//! ## Borrowing strings and byte arrays together
+//!
+//! A struct can borrow both its strings and its byte arrays from the input.
+//! Here a custom byte array stored on each item, like the `tag` of items in a
+//! player's inventory, is kept as a `&[u8]` view into the data rather than
+//! copied into a [`ByteArray`][`crate::ByteArray`]. Int and long arrays can
+//! also be borrowed this way, giving their raw big endian bytes.
+//!
+//! ```no_run
+//! use std::borrow::Cow;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct PlayerDat<'a> {
+//!     #[serde(borrow)]
+//!     inventory: Vec<InventorySlot<'a>>,
+//! }
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct InventorySlot<'a> {
+//!     #[serde(borrow)]
+//!     id: Cow<'a, str>,
+//!
+//!     // A ByteArray tag added by a mod or plugin.
+//!     #[serde(borrow, rename = "CustomData")]
+//!     custom_data: Option<&'a [u8]>,
+//! }
+//!
+//! # fn main() {
+//! let data: Vec<u8> = unimplemented!("get uncompressed player dat data");
+//! let player: PlayerDat = fastnbt::from_bytes(&data).unwrap();
+//! # }
+//! ```
+//!
+//! ## Unit variant enum from status of chunk
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Chunk {
+//!     #[serde(rename = "Level")]
+//!     level: Level,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Level {
+//!     #[serde(rename = "Status")]
+//!     status: Status,
+//! }
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "snake_case")]
+//! enum Status {
+//!     Empty,
+//!     StructureStarts,
+//!     StructureReferences,
+//!     Biomes,
+//!     Noise,
+//!     Surface,
+//!     Carvers,
+//!     LiquidCarvers,
+//!     Features,
+//!     Light,
+//!     Spawn,
+//!     Heightmaps,
+//!     Full,
+//! }
+//! ```
+use std::{borrow::Cow, io::Read};
+
+use serde::{
+    de::{
+        self,
+        value::{BorrowedBytesDeserializer, BorrowedStrDeserializer, BytesDeserializer},
+    },
+    forward_to_deserialize_any,
+};
+
+use crate::{
+    error::{prefix_index, prefix_key, Error, Result},
+    input,
+    raw::{RawAccess, RAW_TOKEN},
+    value::VALUE_TOKEN,
+    DeOpts, Endian, Tag, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN,
+};
+
+use crate::input::{Input, Reference};
+
+pub mod primitives;
+
+/// Deserialize a `bool` from any integral value, where any non-zero value is
+/// `true`. The [`Deserializer`] does this for `bool` fields already, but
+/// serde loses track of the field type in some cases, such as the fields of
+/// internally tagged enums. Use this with `#[serde(deserialize_with)]` there.
+///
+/// ```
+/// # use serde::Deserialize;
+/// #[derive(Deserialize)]
+/// #[serde(tag = "id")]
+/// enum Entity {
+///     #[serde(rename = "minecraft:zombie")]
+///     Zombie {
+///         #[serde(rename = "IsBaby", deserialize_with = "fastnbt::de::deserialize_bool")]
+///         is_baby: bool,
+///     },
+/// }
+///
+/// let nbt = fastnbt::nbt!({"id": "minecraft:zombie", "IsBaby": 1_i8});
+/// let entity: Entity = fastnbt::from_bytes(&fastnbt::to_bytes(&nbt).unwrap()).unwrap();
+/// assert!(matches!(entity, Entity::Zombie { is_baby: true }));
+/// ```
+pub fn deserialize_bool<'de, D>(deserializer: D) -> std::result::Result<bool, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    struct BoolVisitor;
+
+    impl<'de> de::Visitor<'de> for BoolVisitor {
+        type Value = bool;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("an integral value or bool")
+        }
+
+        fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<bool, E> {
+            Ok(v)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<bool, E> {
+            Ok(v != 0)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<bool, E> {
+            Ok(v != 0)
+        }
+    }
+
+    // Buffered values reject integers in deserialize_bool before the visitor
+    // sees them, so ask for whatever is there instead.
+    deserializer.deserialize_any(BoolVisitor)
+}
+
+/// Deserializer for NBT data. See the [`de`] module for more information.
+///
+/// [`de`]: ./index.html
+pub struct Deserializer<In> {
+    input: In,
+    scratch: Vec<u8>,
+    seen_root: bool,
+    opts: DeOpts,
+    budget: Budget,
+    coerced: Vec<String>,
+}
+
+/// Tracks the bytes of data deserialized so far against
+/// [`DeOpts::max_alloc`].
+struct Budget {
+    used: usize,
+    max: Option<usize>,
+}
+
+impl Budget {
+    fn charge(&mut self, size: usize) -> Result<()> {
+        let used = self.used.saturating_add(size);
+        if let Some(max) = self.max {
+            if used > max {
+                return Err(Error::bespoke(format!(
+                    "deserializing {} bytes would exceed allocation budget ({} of {} bytes used)",
+                    size, self.used, max
+                )));
+            }
+        }
+        self.used = used;
+        Ok(())
+    }
+}
+
+impl<'de, In> Deserializer<In>
+where
+    In: Input<'de>,
+{
+    pub fn new(input: In, opts: DeOpts) -> Self {
+        Self {
+            input,
+            scratch: Vec::new(),
+            seen_root: false,
+            budget: Budget {
+                used: 0,
+                max: opts.max_alloc,
+            },
+            opts,
+            coerced: Vec::new(),
+        }
+    }
+
+    /// The paths of the values that were replaced by defaults because they
+    /// were the wrong type, in the order they were read. This is always empty
+    /// unless [`DeOpts::tolerant`] is set.
+    pub fn coerced_paths(&self) -> &[String] {
+        &self.coerced
+    }
+}
+
+impl<'a> Deserializer<input::Slice<'a>> {
+    /// Create Deserializer for a `T` from some NBT data slice. See the [`de`] module
+    /// for more information.
+    ///
+    /// [`de`]: ./index.html
+    pub fn from_bytes(bytes: &'a [u8], opts: DeOpts) -> Self {
+        let input = input::Slice {
+            data: bytes,
+            endian: opts.endian,
+        };
+        Deserializer::new(input, opts)
+    }
+}
+
+impl<R: Read> Deserializer<input::Reader<R>> {
+    /// Create Deserializer for a `T` from some NBT data. See the [`de`] module
+    /// for more information.
+    ///
+    /// [`de`]: ./index.html
+    pub fn from_reader(reader: R, opts: DeOpts) -> Self {
+        let input = input::Reader {
+            reader,
+            endian: opts.endian,
+        };
+        Deserializer::new(input, opts)
+    }
+}
+
+/// Read the tag and name of the root of some NBT data, without reading the
+/// rest. This returns the tag, the name and the number of bytes read, so that
+/// the data can be inspected, eg to route it to the right type, before
+/// deserializing it all. The deserializer reads the root the same way.
+///
+/// A lone end tag has no name, so gives an empty name after reading one byte.
+/// This expects the big endian data of Java Edition, where the root has a
+/// name.
+///
+/// ```
+/// # use fastnbt::{de::read_root_header, Tag};
+/// # let data = fastnbt::to_bytes_with_opts(&fastnbt::nbt!({}), fastnbt::SerOpts::new().root_name("Data")).unwrap();
+/// let (tag, name, len) = read_root_header(&data)?;
+/// assert_eq!((tag, name.as_str(), len), (Tag::Compound, "Data", 7));
+/// # Ok::<(), fastnbt::error::Error>(())
+/// ```
+pub fn read_root_header(data: &[u8]) -> Result<(Tag, String, usize)> {
+    let mut input = input::Slice {
+        data,
+        endian: Endian::Big,
+    };
+
+    let mut scratch = Vec::new();
+    let (tag, name) = consume_root_header(&mut input, true, &mut scratch)?;
+    let name = name.as_ref().to_owned();
+    Ok((tag, name, data.len() - input.data.len()))
+}
+
+// Read the tag of the root, and its name if `named`. A lone end tag has no
+// name, so gives an empty one. The deserializer reads its root with this too.
+fn consume_root_header<'de, 's, In: Input<'de>>(
+    input: &'s mut In,
+    named: bool,
+    scratch: &'s mut Vec<u8>,
+) -> Result<(Tag, Reference<'de, 's, str>)> {
+    let tag = input.consume_tag()?;
+    if tag == Tag::End || !named {
+        return Ok((tag, Reference::Borrowed("")));
+    }
+
+    Ok((tag, input.consume_str(scratch)?))
+}
+
+/// Deserialize into a `T` from some NBT data, also giving the name of the root
+/// compound. Most data has an empty root name, but some formats such as
+/// schematics name it. This is the reverse of
+/// [`to_bytes_named`][`crate::ser::to_bytes_named`].
+///
+/// ```
+/// # use fastnbt::{de::from_bytes_with_name, Value};
+/// # let data = fastnbt::ser::to_bytes_named("Data", &fastnbt::nbt!({})).unwrap();
+/// let (name, _): (String, Value) = from_bytes_with_name(&data)?;
+/// assert_eq!(name, "Data");
+/// # Ok::<(), fastnbt::error::Error>(())
+/// ```
+pub fn from_bytes_with_name<'a, T>(input: &'a [u8]) -> Result<(String, T)>
+where
+    T: de::Deserialize<'a>,
+{
+    let (_, name, _) = read_root_header(input)?;
+    let value = crate::from_bytes(input)?;
+    Ok((name, value))
+}
+
+impl<'de, In> de::Deserializer<'de> for &mut Deserializer<In>
+where
+    In: Input<'de>,
+{
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit unit_struct seq tuple tuple_struct
+        identifier ignored_any bytes enum byte_buf option
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.consume_root()?;
+        visitor.visit_map(MapAccess::new(self))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.consume_root()?;
+        if name == RAW_TOKEN {
+            return self.visit_raw(Tag::Compound, visitor);
+        }
+        let fields = self.struct_fields(fields);
+        visitor.visit_map(MapAccess::new(self).with_fields(fields))
+    }
+}
+
+impl<'de, In: Input<'de>> Deserializer<In> {
+    // The fields of a struct, if the options mean keys need checking against
+    // them.
+    fn struct_fields(&self, fields: &'static [&'static str]) -> Option<&'static [&'static str]> {
+        (self.opts.deny_unknown_fields || self.opts.case_insensitive_fields).then_some(fields)
+    }
+
+    // Give the payload of a value to `RawNbt` as it is in the input.
+    fn visit_raw<V>(&mut self, tag: Tag, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let mut data = vec![u8::from(tag)];
+        self.input.consume_raw(tag, &mut data)?;
+        self.budget.charge(data.len())?;
+        visitor.visit_map(RawAccess::new(data))
+    }
+
+    fn consume_root(&mut self) -> Result<()> {
+        if !self.seen_root {
+            let named = self.opts.expect_coumpound_names;
+            let (tag, _) = consume_root_header(&mut self.input, named, &mut self.scratch)?;
+
+            match tag {
+                Tag::Compound => {}
+                // Only a lone end tag is a placeholder, anything after it
+                // means the data is something else.
+                Tag::End if self.input.is_at_end()? => return Err(Error::empty_document()),
+                Tag::End => return Err(Error::end_tag_before_data()),
+                tag => return Err(Error::root_tag_not_compound(tag)),
+            }
+
+            self.seen_root = true;
+        }
+        Ok(())
+    }
+}
+
+struct MapAccess<'a, 'de, In: 'a> {
+    de: &'a mut Deserializer<In>,
+    tag: Tag, // current tag
+    // Current key, to give the path of errors. This borrows from the input
+    // when it can so that tracking it costs no allocations.
+    key: Cow<'de, str>,
+    // The fields of the struct being deserialized, if unknown keys should be
+    // rejected.
+    fields: Option<&'static [&'static str]>,
+}
+
+impl<'a, 'de, In: 'a> MapAccess<'a, 'de, In> {
+    pub fn new(de: &'a mut Deserializer<In>) -> Self {
+        Self {
+            de,
+            tag: Tag::End,
+            key: Cow::Borrowed(""),
+            fields: None,
+        }
+    }
+
+    pub fn with_fields(mut self, fields: Option<&'static [&'static str]>) -> Self {
+        self.fields = fields;
+        self
+    }
+}
+
+impl<'de, 'a, In: Input<'de> + 'a> de::MapAccess<'de> for MapAccess<'a, 'de, In> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        self.tag = self.de.input.consume_tag()?;
+        if self.tag == Tag::End {
+            return Ok(None);
+        }
+
+        let key = seed.deserialize(MapKey {
+            de: &mut *self.de,
+            key: &mut self.key,
+            fields: self.fields,
+        })?;
+
+        if let Some(fields) = self.fields {
+            let ignore_case = self.de.opts.case_insensitive_fields;
+            if self.de.opts.deny_unknown_fields
+                && find_field(fields, &self.key, ignore_case).is_none()
+            {
+                let err: Error = de::Error::unknown_field(&self.key, fields);
+                return Err(err.at_key(&self.key));
+            }
+        }
+
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let coerced = self.de.coerced.len();
+        let value = seed
+            .deserialize(AnonymousValue {
+                tag: self.tag,
+                de: &mut *self.de,
+                last_hint: Hint::None,
+            })
+            .map_err(|e| e.at_key(&self.key))?;
+
+        for path in &mut self.de.coerced[coerced..] {
+            prefix_key(path, &self.key);
+        }
+        Ok(value)
+    }
+}
+
+struct MapKey<'a, 'de, In> {
+    de: &'a mut Deserializer<In>,
+    key: &'a mut Cow<'de, str>,
+    fields: Option<&'static [&'static str]>,
+}
+
+// Find the struct field a key is for. An exact match is preferred over one
+// that only matches ignoring case.
+fn find_field(
+    fields: &'static [&'static str],
+    key: &str,
+    ignore_case: bool,
+) -> Option<&'static str> {
+    let exact = fields.iter().find(|f| **f == key);
+    let any_case = || fields.iter().find(|f| f.eq_ignore_ascii_case(key));
+    exact
+        .or_else(|| ignore_case.then(any_case).flatten())
+        .copied()
+}
+
+fn arr_check(key: &str) -> Result<&str> {
+    if key.starts_with("__")
+        && (key == BYTE_ARRAY_TOKEN || key == INT_ARRAY_TOKEN || key == LONG_ARRAY_TOKEN)
+    {
+        Err(Error::bespoke(
+            "compound using special fastnbt array tokens".to_string(),
+        ))
+    } else {
+        Ok(key)
+    }
+}
+
+impl<'de, 'a, R> MapKey<'a, 'de, R>
+where
+    R: Input<'de>,
+{
+    // Read the key into `self.key`.
+    fn read(&mut self) -> Result<()> {
+        let key = self.de.input.consume_str(&mut self.de.scratch)?;
+        self.de.budget.charge(key.as_ref().len())?;
+        match key {
+            Reference::Borrowed(s) => *self.key = Cow::Borrowed(s),
+            Reference::Copied(s) => match &mut *self.key {
+                Cow::Owned(buf) => {
+                    buf.clear();
+                    buf.push_str(s);
+                }
+                key => *key = Cow::Owned(s.to_owned()),
+            },
+        }
+        arr_check(self.key)?;
+        Ok(())
+    }
+}
+
+// Keys for maps with integer keys, such as `HashMap<i32, T>`, are parsed from
+// the compound key's string. Keys that do not parse are given to the visitor
+// as a string, which it will likely reject.
+macro_rules! deserialize_integer_key {
+    ($method:ident => $visit:ident) => {
+        fn $method<V>(mut self, visitor: V) -> Result<V::Value>
+        where
+            V: de::Visitor<'de>,
+        {
+            self.read()?;
+            match (self.key.parse(), &*self.key) {
+                (Ok(integer), _) => visitor.$visit(integer),
+                (Err(_), Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
+                (Err(_), Cow::Owned(s)) => visitor.visit_str(s),
+            }
+            .map_err(|e: Error| e.at_key(self.key))
+        }
+    };
+}
+
+impl<'de, 'a, R> de::Deserializer<'de> for MapKey<'a, 'de, R>
+where
+    R: Input<'de>,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.read()?;
+
+        // Give the struct the name of the field it expects, so that it matches.
+        if let Some(fields) = self.fields {
+            if self.de.opts.case_insensitive_fields {
+                if let Some(field) = find_field(fields, self.key, true) {
+                    return visitor.visit_borrowed_str(field);
+                }
+            }
+        }
+
+        match &*self.key {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_str(s),
+        }
+    }
+
+    deserialize_integer_key!(deserialize_i8 => visit_i8);
+    deserialize_integer_key!(deserialize_i16 => visit_i16);
+    deserialize_integer_key!(deserialize_i32 => visit_i32);
+    deserialize_integer_key!(deserialize_i64 => visit_i64);
+    deserialize_integer_key!(deserialize_u8 => visit_u8);
+    deserialize_integer_key!(deserialize_u16 => visit_u16);
+    deserialize_integer_key!(deserialize_u32 => visit_u32);
+    deserialize_integer_key!(deserialize_u64 => visit_u64);
+    deserialize_integer_key!(deserialize_i128 => visit_i128);
+    deserialize_integer_key!(deserialize_u128 => visit_u128);
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any bytes enum byte_buf option
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+}
+
+impl<'de, In: Input<'de>> Deserializer<In> {
+    // Skip a value of the wrong type in tolerant mode, recording that it was
+    // replaced. Its path is filled in by the compounds and lists it is in.
+    fn coerce(&mut self, tag: Tag) -> Result<()> {
+        self.input.ignore_value(tag)?;
+        self.coerced.push(String::new());
+        Ok(())
+    }
+
+    // Read an integer for tolerant mode, skipping the value if it is not an
+    // integer.
+    fn tolerant_integer(&mut self, tag: Tag) -> Result<Option<i64>> {
+        Ok(Some(match tag {
+            Tag::Byte => self.input.consume_byte()? as i8 as i64,
+            Tag::Short => self.input.consume_i16()? as i64,
+            Tag::Int => self.input.consume_i32()? as i64,
+            Tag::Long => self.input.consume_i64()?,
+            _ => {
+                self.input.ignore_value(tag)?;
+                return Ok(None);
+            }
+        }))
+    }
+}
+
+fn is_number(tag: Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Byte | Tag::Short | Tag::Int | Tag::Long | Tag::Float | Tag::Double
+    )
+}
+
+// In tolerant mode, integers that are not integers or are out of range for
+// the type deserialize as zero. Otherwise these go to `deserialize_any` as
+// usual, leaving the visitor to reject what it does not accept.
+macro_rules! deserialize_tolerant_integer {
+    ($method:ident => $ty:ty, $visit:ident) => {
+        fn $method<V>(self, visitor: V) -> Result<V::Value>
+        where
+            V: de::Visitor<'de>,
+        {
+            if !self.de.opts.tolerant {
+                return self.deserialize_any(visitor);
+            }
+            match self.de.tolerant_integer(self.tag)? {
+                Some(v) if <$ty>::try_from(v).is_ok() => visitor.$visit(v as $ty),
+                _ => {
+                    self.de.coerced.push(String::new());
+                    visitor.$visit(0)
+                }
+            }
+        }
+    };
+}
+
+enum Hint {
+    None,
+    Seq,
+    Tuple(usize),
+    Map,
+}
+
+/// Deserializer for an anonymous value, ie one with no tag or name before it.
+/// This occurs in lists, but is also used to deserialize the value part of compounds.
+///
+/// This is the 'core' of the deserializer if there can be said to be one.
+struct AnonymousValue<'a, In> {
+    tag: Tag,
+    last_hint: Hint,
+    de: &'a mut Deserializer<In>,
+}
+
+impl<'de, 'a, In> de::Deserializer<'de> for AnonymousValue<'a, In>
+where
+    In: Input<'de>,
+{
+    type Error = Error;
+
+    forward_to_deserialize_any!(identifier char);
+
+    deserialize_tolerant_integer!(deserialize_i8 => i8, visit_i8);
+    deserialize_tolerant_integer!(deserialize_i16 => i16, visit_i16);
+    deserialize_tolerant_integer!(deserialize_i32 => i32, visit_i32);
+    deserialize_tolerant_integer!(deserialize_i64 => i64, visit_i64);
+    deserialize_tolerant_integer!(deserialize_u8 => u8, visit_u8);
+    deserialize_tolerant_integer!(deserialize_u16 => u16, visit_u16);
+    deserialize_tolerant_integer!(deserialize_u32 => u32, visit_u32);
+    deserialize_tolerant_integer!(deserialize_u64 => u64, visit_u64);
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.de.opts.tolerant && !is_number(self.tag) {
+            self.de.coerce(self.tag)?;
+            return visitor.visit_f32(0.0);
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.de.opts.tolerant && !is_number(self.tag) {
+            self.de.coerce(self.tag)?;
+            return visitor.visit_f64(0.0);
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.de.opts.tolerant && self.tag != Tag::String {
+            self.de.coerce(self.tag)?;
+            return visitor.visit_borrowed_str("");
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        v: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if name == RAW_TOKEN {
+            return self.de.visit_raw(self.tag, v);
+        }
+        match self.tag {
+            Tag::Compound => {
+                let fields = self.de.struct_fields(fields);
+                v.visit_map(MapAccess::new(self.de).with_fields(fields))
+            }
+            _ => self.deserialize_any(v),
+        }
+    }
+
+    fn deserialize_any<V>(mut self, v: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let last_hint = self.last_hint;
+        self.last_hint = Hint::None;
+
+        let array_as_seq = match last_hint {
+            Hint::Seq => true,
+            Hint::Map | Hint::Tuple(_) => false,
+            Hint::None => self.de.opts.arrays_as_lists,
+        };
+
+        match self.tag {
+            Tag::End => Err(Error::bespoke("expected value, found end tag".into())),
+            Tag::Byte => v.visit_i8(self.de.input.consume_byte()? as i8),
+            Tag::Short => v.visit_i16(self.de.input.consume_i16()?),
+            Tag::Int => v.visit_i32(self.de.input.consume_i32()?),
+            Tag::Long => v.visit_i64(self.de.input.consume_i64()?),
+            Tag::Float => v.visit_f32(self.de.input.consume_f32()?),
+            Tag::Double => v.visit_f64(self.de.input.consume_f64()?),
+            Tag::String => {
+                let s = self.de.input.consume_str(&mut self.de.scratch)?;
+                self.de.budget.charge(s.as_ref().len())?;
+                match s {
+                    Reference::Borrowed(s) => v.visit_borrowed_str(s),
+                    Reference::Copied(s) => v.visit_str(s),
+                }
+            }
+            Tag::List => {
+                let tag = self.de.input.consume_tag()?;
+                let remaining = self.de.input.consume_len()?;
+
+                // End values have no payload. An end tag on it's own is the payload
+                // of an empty compound. A logical interpretation is that this could
+                // be a list of zero-sized units, but this mean an easy short
+                // malicious payload of a massive list taking up lots of memory (as
+                // the Value type's unit variant would not be zero sized.
+                //
+                // Some old chunks store empty lists as as 'list of end', so if the
+                // size is zero we let it slide.
+                if tag == Tag::End && remaining != 0 {
+                    return Err(Error::list_of_end());
+                }
+
+                // A tuple visitor stops asking for elements once it has the
+                // number it needs, so a longer list would leave elements
+                // unconsumed in the input.
+                if let Hint::Tuple(len) = last_hint {
+                    if remaining != len {
+                        return Err(de::Error::invalid_length(remaining, &v));
+                    }
+                }
+
+                if remaining > self.de.opts.max_seq_len {
+                    return Err(Error::bespoke(format!(
+                        "size ({}) greater than max sequence length ({})",
+                        remaining, self.de.opts.max_seq_len,
+                    )));
+                }
+                self.de
+                    .budget
+                    .charge(remaining.saturating_mul(tag.payload_size().unwrap_or(1)))?;
+
+                v.visit_seq(ListAccess {
+                    de: self.de,
+                    tag,
+                    remaining,
+                    index: 0,
+                })
+            }
+            Tag::Compound => v.visit_map(MapAccess::new(self.de)),
+            Tag::ByteArray => {
+                if let Hint::Tuple(_) = last_hint {
+                    return Err(Error::array_as_seq());
+                }
+                let len = self.de.input.consume_len()?;
+                if array_as_seq {
+                    return visit_array_as_seq(self.de, Tag::Byte, len, v);
+                }
+                v.visit_map(ArrayWrapperAccess::bytes(self.de, len)?)
+            }
+            Tag::IntArray => {
+                if let Hint::Tuple(_) = last_hint {
+                    return Err(Error::array_as_seq());
+                }
+                let len = self.de.input.consume_len()?;
+                if array_as_seq {
+                    return visit_array_as_seq(self.de, Tag::Int, len, v);
+                }
+                v.visit_map(ArrayWrapperAccess::ints(self.de, len)?)
+            }
+            Tag::LongArray => {
+                if let Hint::Tuple(_) = last_hint {
+                    return Err(Error::array_as_seq());
+                }
+                let len = self.de.input.consume_len()?;
+                if array_as_seq {
+                    return visit_array_as_seq(self.de, Tag::Long, len, v);
+                }
+                v.visit_map(ArrayWrapperAccess::longs(self.de, len)?)
+            }
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let consume_visit = |de: &mut Deserializer<In>, len: usize, el_size: usize| {
+            let size = len
+                .checked_mul(el_size)
+                .ok_or_else(|| Error::bespoke("overflow deserializing bytes".to_string()))?;
+            de.budget.charge(size)?;
+
+            if el_size > 1 && de.opts.endian != Endian::Big {
+                return visitor.visit_byte_buf(consume_as_big_endian(&mut de.input, len, el_size)?);
+            }
+
+            match de.input.consume_bytes(size, &mut de.scratch)? {
+                Reference::Borrowed(bs) => visitor.visit_borrowed_bytes(bs),
+                Reference::Copied(bs) => visitor.visit_bytes(bs),
+            }
+        };
+
+        match self.tag {
+            Tag::String => {
+                let len = self.de.input.consume_str_len()?;
+                consume_visit(self.de, len, 1)
+            }
+            Tag::List => {
+                let tag = self.de.input.consume_tag()?;
+                let remaining = self.de.input.consume_len()?;
+
+                match tag {
+                    Tag::Byte => consume_visit(self.de, remaining, std::mem::size_of::<i8>()),
+                    Tag::Short => consume_visit(self.de, remaining, std::mem::size_of::<i16>()),
+                    Tag::Int => consume_visit(self.de, remaining, std::mem::size_of::<i32>()),
+                    Tag::Long => consume_visit(self.de, remaining, std::mem::size_of::<i64>()),
+                    _ => Err(Error::bespoke(format!(
+                        "cannot convert list of {} to bytes",
+                        tag
+                    ))),
+                }
+            }
+            Tag::ByteArray => {
+                let remaining = self.de.input.consume_len()?;
+                consume_visit(self.de, remaining, std::mem::size_of::<i8>())
+            }
+            Tag::IntArray => {
+                let remaining = self.de.input.consume_len()?;
+                consume_visit(self.de, remaining, std::mem::size_of::<i32>())
+            }
+            Tag::LongArray => {
+                let remaining = self.de.input.consume_len()?;
+                consume_visit(self.de, remaining, std::mem::size_of::<i64>())
+            }
+            _ => Err(Error::bespoke(format!(
+                "cannot convert {} to bytes",
+                self.tag
+            ))),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // fastnbt quirk: if your type contains a unit, we allow any valid NBT
+        // value to 'fill' that hole in your type. This means a unit type can be
+        // used to ensure the presense of a value in the NBT without actually
+        // caring or deserializing its contents.
+        self.de.input.ignore_value(self.tag)?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.last_hint = Hint::Tuple(len);
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_enum(UnitVariantAccess {
+            de: AnonymousValue {
+                tag: self.tag,
+                de: self.de,
+                last_hint: Hint::None,
+            },
+        })
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.de.input.ignore_value(self.tag)?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_seq<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.last_hint = Hint::Seq;
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_map<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // The array types deserialize this way, so arrays must stay maps
+        // even when other visitors are given them as lists.
+        self.last_hint = Hint::Map;
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // We specifically allow any intergral type to be deserialized into a
+        // bool.
+        match self.tag {
+            Tag::Byte => visitor.visit_bool(self.de.input.consume_byte()? != 0),
+            Tag::Short => visitor.visit_bool(self.de.input.consume_i16()? != 0),
+            Tag::Int => visitor.visit_bool(self.de.input.consume_i32()? != 0),
+            Tag::Long => visitor.visit_bool(self.de.input.consume_i64()? != 0),
+            tag if self.de.opts.tolerant => {
+                self.de.coerce(tag)?;
+                visitor.visit_bool(false)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(mut self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // The value types tell arrays apart from lists, so are given arrays
+        // as maps like the array types are.
+        if name == VALUE_TOKEN {
+            self.last_hint = Hint::Map;
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_i128<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i128(get_i128_value(&mut self)?)
+    }
+
+    #[inline]
+    fn deserialize_u128<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u128(get_i128_value(&mut self)? as u128)
+    }
+}
+
+/// Visit the elements of an NBT array as if it was a list of `element_tag`, for
+/// sequence types like `Vec`.
+fn visit_array_as_seq<'de, In, V>(
+    de: &mut Deserializer<In>,
+    element_tag: Tag,
+    len: usize,
+    v: V,
+) -> Result<V::Value>
+where
+    In: Input<'de>,
+    V: de::Visitor<'de>,
+{
+    de.budget
+        .charge(len.saturating_mul(element_tag.payload_size().unwrap_or(1)))?;
+
+    let mut access = ListAccess {
+        de,
+        tag: element_tag,
+        remaining: len,
+        index: 0,
+    };
+    let value = v.visit_seq(&mut access)?;
+
+    if access.remaining != 0 {
+        return Err(Error::bespoke(format!(
+            "{} elements of array left unread",
+            access.remaining
+        )));
+    }
+    Ok(value)
+}
+
+struct ListAccess<'a, In: 'a> {
+    de: &'a mut Deserializer<In>,
+    tag: Tag, // current tag
+    remaining: usize,
+    index: usize, // index of the next element
+}
+
+impl<'de, 'a, In: Input<'de> + 'a> de::SeqAccess<'de> for ListAccess<'a, In> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.index += 1;
+            let coerced = self.de.coerced.len();
+            let value = seed
+                .deserialize(AnonymousValue {
+                    de: &mut *self.de,
+                    last_hint: Hint::None,
+                    tag: self.tag,
+                })
+                .map_err(|e| e.at_index(self.index - 1))?;
+
+            for path in &mut self.de.coerced[coerced..] {
+                prefix_index(path, self.index - 1);
+            }
+            Ok(Some(value))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        // Some visitors preallocate whatever we hint, and the length in the
+        // data might be a lie, so cap it.
+        Some(self.remaining.min(self.de.opts.prealloc_limit))
+    }
+}
+
+struct UnitVariantAccess<'a, In: 'a> {
+    de: AnonymousValue<'a, In>,
+}
+
+impl<'de, 'a, In: Input<'de> + 'a> de::EnumAccess<'de> for UnitVariantAccess<'a, In> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(AnonymousValue {
+            de: &mut *self.de.de,
+            last_hint: Hint::None,
+            tag: self.de.tag,
+        })?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a, In: Input<'de> + 'a> de::VariantAccess<'de> for UnitVariantAccess<'a, In> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"newtype variant",
+        ))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::TupleVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::StructVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+enum State {
+    Unread,
+    Read,
+}
+
+pub(crate) struct ArrayWrapperAccess<'a, In: 'a> {
+    de: &'a mut Deserializer<In>,
+    token: &'static str,
+    len: usize,
+    el_size: usize,
+    state: State,
+}
+
+impl<'a, In: 'a> ArrayWrapperAccess<'a, In> {
+    fn new(
+        de: &'a mut Deserializer<In>,
+        token: &'static str,
+        len: usize,
+        el_size: usize,
+    ) -> Result<Self> {
+        let size = len
+            .checked_mul(el_size)
+            .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?;
+        de.budget.charge(size)?;
+
+        Ok(Self {
+            de,
+            token,
+            len,
+            el_size,
+            state: State::Unread,
+        })
+    }
+
+    pub(crate) fn bytes(de: &'a mut Deserializer<In>, size: usize) -> Result<Self> {
+        Self::new(de, BYTE_ARRAY_TOKEN, size, 1)
+    }
+
+    pub(crate) fn ints(de: &'a mut Deserializer<In>, size: usize) -> Result<Self> {
+        Self::new(de, INT_ARRAY_TOKEN, size, 4)
+    }
+
+    pub(crate) fn longs(de: &'a mut Deserializer<In>, size: usize) -> Result<Self> {
+        Self::new(de, LONG_ARRAY_TOKEN, size, 8)
+    }
+}
+
+impl<'de, 'a, In: Input<'de> + 'a> de::MapAccess<'de> for ArrayWrapperAccess<'a, In> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if let State::Unread = self.state {
+            self.state = State::Read;
+            seed.deserialize(BorrowedStrDeserializer::new(self.token))
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let name = match self.token {
+            INT_ARRAY_TOKEN => "int array",
+            LONG_ARRAY_TOKEN => "long array",
+            _ => "byte array",
+        };
+
+        if self.el_size > 1 && self.de.opts.endian != Endian::Big {
+            let data = consume_as_big_endian(&mut self.de.input, self.len, self.el_size)?;
+            return seed
+                .deserialize(BytesDeserializer::new(&data))
+                .map_err(|e: Error| e.for_array(name));
+        }
+
+        let data = self
+            .de
+            .input
+            .consume_bytes(self.len * self.el_size, &mut self.de.scratch)?;
+
+        match data {
+            Reference::Borrowed(bs) => seed.deserialize(BorrowedBytesDeserializer::new(bs)),
+            Reference::Copied(bs) => seed.deserialize(BytesDeserializer::new(bs)),
+        }
+        .map_err(|e: Error| e.for_array(name))
+    }
+}
+
+/// Read `len` numbers of `el_size` bytes each and return their big endian
+/// bytes, whatever the byte order of the input. The array types all store
+/// their data big endian.
+fn consume_as_big_endian<'de, In>(input: &mut In, len: usize, el_size: usize) -> Result<Vec<u8>>
+where
+    In: Input<'de>,
+{
+    let mut data = Vec::new();
+    for _ in 0..len {
+        match el_size {
+            2 => data.extend(input.consume_i16()?.to_be_bytes()),
+            4 => data.extend(input.consume_i32()?.to_be_bytes()),
+            8 => data.extend(input.consume_i64()?.to_be_bytes()),
+            _ => data.push(input.consume_byte()?),
+        }
+    }
+    Ok(data)
+}
+
+fn get_i128_value<'de, In>(de: &mut AnonymousValue<In>) -> Result<i128>
+where
+    In: Input<'de>,
+{
+    let tag = de.tag;
+
+    match tag {
+        Tag::IntArray => {
+            let len = de.de.input.consume_len()?;
+
+            let size = len
+                .checked_mul(4)
+                .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?;
+            de.de.budget.charge(size)?;
+
+            let converted;
+            let bs = if de.de.opts.endian == Endian::Big {
+                de.de.input.consume_bytes(size, &mut de.de.scratch)?
+            } else {
+                converted = consume_as_big_endian(&mut de.de.input, len, 4)?;
+                Reference::Copied(converted.as_slice())
+            };
+            let bs = bs.as_ref();
+
+            match bs.try_into() {
+                Ok(bs) => Ok(i128::from_be_bytes(bs)),
+                Err(_) => Err(Error::bespoke(format!(
+                    "deserialize i128: expected IntArray of length 4 with 16 bytes, found {} bytes",
+                    bs.len()
+                ))),
+            }
+        }
+        _ => Err(Error::bespoke(
+            "deserialize i128: expected IntArray value".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,119 @@
+//! This module contains functions for reading single NBT payloads from a byte
+//! slice, for parsing formats that embed NBT values in a layout of their own.
+//!
+//! Each function reads the payload of one tag from the start of `data`, ie
+//! without the tag or name that come before it in a compound, and returns it
+//! along with the number of bytes read. These read the big endian data of Java
+//! Edition, with strings in Java's modified UTF-8, and error rather than panic
+//! on truncated data or negative lengths.
+//!
+//! ```
+//! use fastnbt::de::primitives::{read_int_array, read_string, read_tag};
+//! use fastnbt::Tag;
+//!
+//! # fn main() -> fastnbt::error::Result<()> {
+//! // A made up format: a tag id, then a string, then an int array.
+//! let data = [11, 0, 2, b'i', b'd', 0, 0, 0, 1, 0, 0, 0, 7];
+//!
+//! let (tag, n) = read_tag(&data)?;
+//! assert_eq!(tag, Tag::IntArray);
+//! let (name, m) = read_string(&data[n..])?;
+//! assert_eq!(name, "id");
+//! let (arr, _) = read_int_array(&data[n + m..])?;
+//! assert_eq!(*arr, [7]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::mem::size_of;
+
+use crate::{
+    error::Result,
+    input::{self, try_size, Input},
+    ByteArray, Endian, IntArray, LongArray, Tag,
+};
+
+/// Read a tag id.
+pub fn read_tag(data: &[u8]) -> Result<(Tag, usize)> {
+    read(data, |input| input.consume_tag())
+}
+
+/// Read the payload of a byte tag.
+pub fn read_byte(data: &[u8]) -> Result<(i8, usize)> {
+    read(data, |input| Ok(input.consume_byte()? as i8))
+}
+
+/// Read the payload of a short tag.
+pub fn read_short(data: &[u8]) -> Result<(i16, usize)> {
+    read(data, |input| input.consume_i16())
+}
+
+/// Read the payload of an int tag.
+pub fn read_int(data: &[u8]) -> Result<(i32, usize)> {
+    read(data, |input| input.consume_i32())
+}
+
+/// Read the payload of a long tag.
+pub fn read_long(data: &[u8]) -> Result<(i64, usize)> {
+    read(data, |input| input.consume_i64())
+}
+
+/// Read the payload of a float tag.
+pub fn read_float(data: &[u8]) -> Result<(f32, usize)> {
+    read(data, |input| input.consume_f32())
+}
+
+/// Read the payload of a double tag.
+pub fn read_double(data: &[u8]) -> Result<(f64, usize)> {
+    read(data, |input| input.consume_f64())
+}
+
+/// Read the payload of a string tag, a length followed by that many bytes of
+/// modified UTF-8. This is also how the names of compound entries are stored.
+pub fn read_string(data: &[u8]) -> Result<(String, usize)> {
+    read(data, |input| {
+        Ok(input.consume_str(&mut Vec::new())?.as_ref().to_owned())
+    })
+}
+
+/// Read the payload of a byte array tag.
+pub fn read_byte_array(data: &[u8]) -> Result<(ByteArray, usize)> {
+    read(data, |input| {
+        let bytes = read_array_bytes(input, size_of::<i8>())?;
+        Ok(ByteArray::from_bytes(bytes))
+    })
+}
+
+/// Read the payload of an int array tag.
+pub fn read_int_array(data: &[u8]) -> Result<(IntArray, usize)> {
+    read(data, |input| {
+        let bytes = read_array_bytes(input, size_of::<i32>())?;
+        Ok(IntArray::from_bytes(bytes)?)
+    })
+}
+
+/// Read the payload of a long array tag.
+pub fn read_long_array(data: &[u8]) -> Result<(LongArray, usize)> {
+    read(data, |input| {
+        let bytes = read_array_bytes(input, size_of::<i64>())?;
+        Ok(LongArray::from_bytes(bytes)?)
+    })
+}
+
+fn read<'a, T>(
+    data: &'a [u8],
+    f: impl FnOnce(&mut input::Slice<'a>) -> Result<T>,
+) -> Result<(T, usize)> {
+    let mut input = input::Slice {
+        data,
+        endian: Endian::Big,
+    };
+    let value = f(&mut input)?;
+    Ok((value, data.len() - input.data.len()))
+}
+
+// Read the length of an array then the bytes of its elements.
+fn read_array_bytes<'a>(input: &mut input::Slice<'a>, el_size: usize) -> Result<&'a [u8]> {
+    let size = try_size(input.consume_i32()?, el_size)?;
+    input.consume(0..size)
+}
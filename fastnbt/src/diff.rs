@@ -0,0 +1,155 @@
+//! This module contains [`diff`][`crate::diff()`], for finding what changed
+//! between two [`Value`]s. This is useful when debugging version migrations,
+//! or seeing what the game changed in a chunk between two saves.
+//!
+//! ```
+//! use fastnbt::{diff::Change, nbt};
+//!
+//! let before = nbt!({"Pos": [1.0, 64.0, 1.0], "Health": 20.0_f32});
+//! let after = nbt!({"Pos": [1.0, 63.5, 1.0], "Fire": -20_i16});
+//!
+//! let diffs = fastnbt::diff(&before, &after);
+//! assert_eq!(diffs.len(), 3);
+//! assert_eq!(diffs[0].path, "Fire");
+//! assert_eq!(diffs[0].change, Change::Added(nbt!(-20_i16)));
+//!
+//! let report: Vec<_> = diffs.iter().map(ToString::to_string).collect();
+//! assert_eq!(report, [
+//!     "+ Fire: -20s",
+//!     "- Health: 20.0f",
+//!     "~ Pos[1]: 64.0d -> 63.5d",
+//! ]);
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+
+use crate::{
+    error::{prefix_index, prefix_key},
+    snbt::SnbtWriter,
+    Value,
+};
+
+/// A single difference between two values, found by
+/// [`diff`][`crate::diff()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    /// Path to the value that changed, eg `Level.Sections[2].Y`. This is empty
+    /// if the top level values differ.
+    pub path: String,
+    /// How the value changed.
+    pub change: Change,
+}
+
+/// How a value changed between the two sides of a [`diff`][`crate::diff()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// The value only exists in the second value.
+    Added(Value),
+    /// The value only exists in the first value.
+    Removed(Value),
+    /// The value exists in both, but is different. Compounds and lists are
+    /// compared element by element, so this is only for other values, or values
+    /// whose type changed.
+    Changed { before: Value, after: Value },
+}
+
+impl Display for Diff {
+    /// Formats the diff as a line of a report, eg `~ Pos[1]: 64.0d -> 63.5d`.
+    /// Values are written in SNBT.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (sign, value) = match &self.change {
+            Change::Added(v) => ('+', v),
+            Change::Removed(v) => ('-', v),
+            Change::Changed { before, .. } => ('~', before),
+        };
+
+        write!(f, "{} ", sign)?;
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write_snbt(f, value)?;
+        if let Change::Changed { after, .. } = &self.change {
+            f.write_str(" -> ")?;
+            write_snbt(f, after)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two values, giving every added, removed and changed value along
+/// with its path. Compounds are compared key by key and lists index by index,
+/// so a value inserted at the start of a list shows as every element changing.
+///
+/// Floats and doubles are compared by their bits, so a NaN is equal to the same
+/// NaN, but `0.0` and `-0.0` differ.
+///
+/// Diffs within a compound are ordered by key, so the result is the same every
+/// time. Two equal values give no diffs. See the [`diff`][mod@crate::diff]
+/// module for an example.
+pub fn diff(a: &Value, b: &Value) -> Vec<Diff> {
+    let mut diffs = vec![];
+    diff_into(a, b, &mut diffs);
+    diffs
+}
+
+// Push the diffs between `a` and `b` with paths relative to them.
+fn diff_into(a: &Value, b: &Value, diffs: &mut Vec<Diff>) {
+    match (a, b) {
+        (Value::Compound(a), Value::Compound(b)) => {
+            let keys: BTreeSet<_> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let start = diffs.len();
+                diff_entry(a.get(key), b.get(key), diffs);
+                for diff in &mut diffs[start..] {
+                    prefix_key(&mut diff.path, key);
+                }
+            }
+        }
+        (Value::List(a), Value::List(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let start = diffs.len();
+                diff_entry(a.get(i), b.get(i), diffs);
+                for diff in &mut diffs[start..] {
+                    prefix_index(&mut diff.path, i);
+                }
+            }
+        }
+        (a, b) if !same(a, b) => diffs.push(Diff {
+            path: String::new(),
+            change: Change::Changed {
+                before: a.clone(),
+                after: b.clone(),
+            },
+        }),
+        _ => {}
+    }
+}
+
+// Whether two values other than compounds and lists are the same, comparing
+// floats by their bits.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+        (Value::Double(a), Value::Double(b)) => a.to_bits() == b.to_bits(),
+        (a, b) => a == b,
+    }
+}
+
+// Diff an entry of a compound or list that may be missing from either side.
+fn diff_entry(a: Option<&Value>, b: Option<&Value>, diffs: &mut Vec<Diff>) {
+    let change = match (a, b) {
+        (Some(a), Some(b)) => return diff_into(a, b, diffs),
+        (Some(a), None) => Change::Removed(a.clone()),
+        (None, Some(b)) => Change::Added(b.clone()),
+        (None, None) => unreachable!("entry came from one of the sides"),
+    };
+    diffs.push(Diff {
+        path: String::new(),
+        change,
+    });
+}
+
+fn write_snbt(f: &mut fmt::Formatter<'_>, value: &Value) -> fmt::Result {
+    SnbtWriter::compact(f).write_value(value)
+}
@@ -1,24 +1,76 @@
 //! Contains the Error and Result type used by the deserializer.
-use std::fmt::Display;
+use std::{fmt::Display, sync::Arc};
+
+use crate::Tag;
 
 /// Various errors that can occur during deserialization.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Error(String);
+///
+/// Errors deserializing a value inside a compound or list are prefixed with the
+/// path to that value, eg `Level.Sections[2].Y: invalid type: ...`.
+///
+/// Errors caused by the reader or writer failing keep the [`std::io::Error`],
+/// which is given by [`source`][`std::error::Error::source`]:
+///
+/// ```
+/// # use fastnbt::Value;
+/// use std::error::Error;
+///
+/// let err = fastnbt::from_reader::<_, Value>(&[10, 0][..]).unwrap_err();
+/// let io = err.source().and_then(|e| e.downcast_ref::<std::io::Error>());
+/// assert_eq!(io.map(|e| e.kind()), Some(std::io::ErrorKind::UnexpectedEof));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Error {
+    msg: String,
+    path: String,
+    kind: Kind,
+    // Shared so that the error can still be cloned.
+    source: Option<Arc<std::io::Error>>,
+}
+
+// The source is left out, as IO errors cannot be compared. Its message is
+// part of `msg` anyway.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.msg == other.msg && self.path == other.path && self.kind == other.kind
+    }
+}
+
+impl Eq for Error {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Other,
+    EmptyDocument,
+    RootNotCompound(Tag),
+    #[cfg(feature = "snbt")]
+    Snbt(usize),
+}
 
 /// Convenience type for Result.
 pub type Result<T> = std::result::Result<T, Error>;
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        if self.path.is_empty() {
+            f.write_str(&self.msg)
+        } else {
+            write!(f, "{}: {}", self.path, self.msg)
+        }
     }
 }
 
 impl serde::de::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
-        Error(msg.to_string())
+        Error::new(msg.to_string())
     }
 }
 
@@ -28,45 +80,196 @@ impl serde::ser::Error for Error {
     where
         T: Display,
     {
-        Error(msg.to_string())
+        Error::new(msg.to_string())
     }
 }
 
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
-        Error(format!("io error: {}", e))
+        let msg = format!("io error: {}", e);
+        Error {
+            source: Some(Arc::new(e)),
+            ..Error::new(msg)
+        }
     }
 }
 
 impl Error {
+    fn new(msg: String) -> Error {
+        Error {
+            msg,
+            path: String::new(),
+            kind: Kind::Other,
+            source: None,
+        }
+    }
+
+    /// Whether the input was an empty document, a lone end tag rather than a
+    /// root compound. An end tag followed by anything else is not counted. Some tools write this single zero byte as a placeholder
+    /// for empty NBT, so it may not indicate corrupt data.
+    pub fn is_empty_document(&self) -> bool {
+        self.kind == Kind::EmptyDocument
+    }
+
+    /// The tag the input started with, if it was not a compound. NBT documents
+    /// always start with a compound, named or not, so another tag usually means
+    /// the input is corrupt or was read from the wrong offset. An empty
+    /// document is not counted, see
+    /// [`is_empty_document`][`Error::is_empty_document`].
+    ///
+    /// ```
+    /// # use fastnbt::{Tag, Value};
+    /// let err = fastnbt::from_bytes::<Value>(&[8, 0, 0, 0, 0]).unwrap_err();
+    /// assert_eq!(err.root_not_compound(), Some(Tag::String));
+    /// ```
+    pub fn root_not_compound(&self) -> Option<Tag> {
+        match self.kind {
+            Kind::RootNotCompound(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// The byte offset into the string where parsing stopped, if this error
+    /// came from parsing SNBT into a [`Value`][`crate::Value`], which needs the
+    /// `snbt` feature.
+    pub fn snbt_position(&self) -> Option<usize> {
+        match self.kind {
+            #[cfg(feature = "snbt")]
+            Kind::Snbt(pos) => Some(pos),
+            _ => None,
+        }
+    }
+
+    /// The path to the value that failed to deserialize, eg
+    /// `Level.Sections[2].Y`. This is empty if the error did not occur inside
+    /// a compound or list.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Add the key of the compound entry the error occurred in to the front of
+    /// the path.
+    pub(crate) fn at_key(mut self, key: &str) -> Error {
+        prefix_key(&mut self.path, key);
+        self
+    }
+
+    /// Add the index of the list element the error occurred in to the front of
+    /// the path.
+    pub(crate) fn at_index(mut self, index: usize) -> Error {
+        prefix_index(&mut self.path, index);
+        self
+    }
+
     pub(crate) fn invalid_tag(tag: u8) -> Error {
-        Error(format!("invalid nbt tag value: {}", tag))
+        Error::new(format!("invalid nbt tag value: {}", tag))
     }
 
     pub(crate) fn no_root_compound() -> Error {
-        Error("invalid nbt: no root compound".to_owned())
+        Error::new("invalid nbt: no root compound".to_owned())
+    }
+
+    pub(crate) fn root_tag_not_compound(tag: Tag) -> Error {
+        Error {
+            kind: Kind::RootNotCompound(tag),
+            ..Error::new(format!("invalid nbt: root is {}, not a compound", tag))
+        }
+    }
+
+    pub(crate) fn heterogeneous_list(index: usize, expected: Tag, found: Tag) -> Error {
+        Error::new(format!(
+            "heterogeneous list: element {} is {}, but the list is of {}",
+            index, found, expected
+        ))
+    }
+
+    pub(crate) fn cannot_coerce(from: Tag, to: Tag) -> Error {
+        Error::new(format!("cannot coerce {} to {}", from, to))
+    }
+
+    pub(crate) fn cannot_coerce_value(value: impl Display, from: Tag, to: Tag, why: &str) -> Error {
+        Error::new(format!(
+            "cannot coerce {} {} to {}: {}",
+            from, value, to, why
+        ))
+    }
+
+    #[cfg(feature = "snbt")]
+    pub(crate) fn snbt(msg: impl Display, pos: usize) -> Error {
+        Error {
+            kind: Kind::Snbt(pos),
+            ..Error::new(format!("invalid snbt at {}: {}", pos, msg))
+        }
+    }
+
+    pub(crate) fn too_deep(max_depth: usize) -> Error {
+        Error::new(format!(
+            "value is nested deeper than the maximum depth of {}",
+            max_depth
+        ))
+    }
+
+    pub(crate) fn list_of_end() -> Error {
+        Error::new("unexpected list of type 'end', which is not supported".to_owned())
+    }
+
+    pub(crate) fn empty_document() -> Error {
+        Error {
+            kind: Kind::EmptyDocument,
+            ..Error::new("empty nbt document: found end tag instead of root compound".to_owned())
+        }
+    }
+
+    pub(crate) fn end_tag_before_data() -> Error {
+        Error::new("invalid nbt: end tag followed by more data instead of root compound".into())
     }
 
     pub(crate) fn nonunicode_string(data: &[u8]) -> Error {
-        Error(format!(
+        Error::new(format!(
             "invalid nbt string: nonunicode: {}",
             String::from_utf8_lossy(data)
         ))
     }
 
     pub(crate) fn unexpected_eof() -> Error {
-        Error("eof: unexpectedly ran out of input".to_owned())
+        Error::new("eof: unexpectedly ran out of input".to_owned())
     }
 
     pub(crate) fn array_as_seq() -> Error {
-        Error("expected NBT Array, found seq: use ByteArray, IntArray or LongArray types".into())
+        Error::new("expected NBT Array, found seq: use ByteArray, IntArray or LongArray types".into())
+    }
+
+    /// Name the array in an error from a visitor that did not accept the
+    /// array's data. Every array's data is given as bytes, so serde calls it a
+    /// byte array.
+    pub(crate) fn for_array(mut self, name: &str) -> Error {
+        if let Some(rest) = self.msg.strip_prefix("invalid type: byte array") {
+            self.msg = format!("invalid type: {}{}", name, rest);
+        }
+        self
     }
 
     pub(crate) fn array_as_other() -> Error {
-        Error("expected NBT Array: use ByteArray, IntArray or LongArray types".into())
+        Error::new("expected NBT Array: use ByteArray, IntArray or LongArray types".into())
     }
 
     pub(crate) fn bespoke(msg: String) -> Error {
-        Error(msg)
+        Error::new(msg)
+    }
+}
+
+/// Add a compound key to the front of a path like `Level.Sections[2].Y`.
+pub(crate) fn prefix_key(path: &mut String, key: &str) {
+    if !path.is_empty() && !path.starts_with('[') {
+        path.insert(0, '.');
+    }
+    path.insert_str(0, key);
+}
+
+/// Add a list index to the front of a path like `Level.Sections[2].Y`.
+pub(crate) fn prefix_index(path: &mut String, index: usize) {
+    if !path.is_empty() && !path.starts_with('[') {
+        path.insert(0, '.');
     }
+    path.insert_str(0, &format!("[{}]", index));
 }
@@ -1,19 +1,29 @@
 use std::{borrow::Cow, io::Read, ops::Range};
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 
 use crate::{
     error::{Error, Result},
-    Tag,
+    Endian, Tag,
 };
 
+// Read a value from `$src` with the byte order given by `$endian`.
+macro_rules! read_endian {
+    ($src:expr, $method:ident, $endian:expr) => {
+        match $endian {
+            Endian::Big => $src.$method::<BigEndian>(),
+            Endian::Little | Endian::NetworkLittle => $src.$method::<LittleEndian>(),
+        }
+    };
+}
+
 mod private {
     // Only this crate can implement this trait. Other traits can inherit from
     // Sealed in order to prevent other crates from creating implementations.
     pub trait Sealed {}
 }
 
-fn try_size(size: i32, multiplier: usize) -> Result<usize> {
+pub(crate) fn try_size(size: i32, multiplier: usize) -> Result<usize> {
     let size: usize = size
         .try_into()
         .map_err(|_| Error::bespoke("size was negative".to_string()))?;
@@ -21,6 +31,16 @@ fn try_size(size: i32, multiplier: usize) -> Result<usize> {
     size.checked_mul(multiplier)
         .ok_or_else(|| Error::bespoke("size too large".to_string()))
 }
+
+fn zigzag_i32(v: u64) -> i32 {
+    let v = v as u32;
+    (v >> 1) as i32 ^ -((v & 1) as i32)
+}
+
+fn zigzag_i64(v: u64) -> i64 {
+    (v >> 1) as i64 ^ -((v & 1) as i64)
+}
+
 pub enum Reference<'b, 'c, T>
 where
     T: ?Sized + 'static,
@@ -38,10 +58,50 @@ impl<'b, 'c> AsRef<[u8]> for Reference<'b, 'c, [u8]> {
     }
 }
 
+impl<'b, 'c> AsRef<str> for Reference<'b, 'c, str> {
+    fn as_ref(&self) -> &str {
+        match self {
+            Reference::Borrowed(s) => s,
+            Reference::Copied(s) => s,
+        }
+    }
+}
+
 pub trait Input<'de>: private::Sealed {
+    #[doc(hidden)]
+    fn endian(&self) -> Endian;
+
     #[doc(hidden)]
     fn consume_byte(&mut self) -> Result<u8>;
 
+    /// Consume an unsigned LEB128 varint of at most `max_bytes` bytes.
+    #[doc(hidden)]
+    fn consume_varint(&mut self, max_bytes: u32) -> Result<u64> {
+        let mut value = 0;
+        for i in 0..max_bytes {
+            let b = self.consume_byte()?;
+            value |= ((b & 0x7f) as u64) << (7 * i);
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::bespoke("varint too long".to_string()))
+    }
+
+    #[doc(hidden)]
+    fn consume_str_len(&mut self) -> Result<usize> {
+        match self.endian() {
+            Endian::NetworkLittle => Ok(self.consume_varint(5)? as u32 as usize),
+            _ => Ok(self.consume_i16()? as u16 as usize),
+        }
+    }
+
+    /// Read the length of an array or list, which cannot be negative.
+    #[doc(hidden)]
+    fn consume_len(&mut self) -> Result<usize> {
+        try_size(self.consume_i32()?, 1)
+    }
+
     // #[doc(hidden)]
     // fn discard(&mut self);
 
@@ -51,6 +111,11 @@ pub trait Input<'de>: private::Sealed {
     #[doc(hidden)]
     fn ignore_bytes(&mut self, size: usize) -> Result<()>;
 
+    /// Whether there is no input left. A reader may have a byte consumed to
+    /// find out, so this is only for when reading is finished either way.
+    #[doc(hidden)]
+    fn is_at_end(&mut self) -> Result<bool>;
+
     fn consume_tag(&mut self) -> Result<Tag> {
         let tag = self.consume_byte()?;
         Tag::try_from(tag).map_err(|_| Error::invalid_tag(tag))
@@ -70,6 +135,11 @@ pub trait Input<'de>: private::Sealed {
     fn consume_f32(&mut self) -> Result<f32>;
     fn consume_f64(&mut self) -> Result<f64>;
 
+    /// Consume the payload of a value, appending its bytes to `out` exactly as
+    /// they were in the input.
+    #[doc(hidden)]
+    fn consume_raw(&mut self, tag: Tag, out: &mut Vec<u8>) -> Result<()>;
+
     fn ignore_value(&mut self, tag: Tag) -> Result<()> {
         match tag {
             Tag::Byte => {
@@ -94,9 +164,20 @@ pub trait Input<'de>: private::Sealed {
                 self.ignore_str()?;
             }
             Tag::ByteArray => {
-                let size = self.consume_i32()? as usize;
+                let size = self.consume_len()?;
                 self.ignore_bytes(size)?;
             }
+            Tag::IntArray if self.endian() == Endian::NetworkLittle => {
+                // Each element is a varint, so they have to be read one by one.
+                for _ in 0..self.consume_len()? {
+                    self.consume_i32()?;
+                }
+            }
+            Tag::LongArray if self.endian() == Endian::NetworkLittle => {
+                for _ in 0..self.consume_len()? {
+                    self.consume_i64()?;
+                }
+            }
             Tag::IntArray => {
                 let size = self.consume_i32()?;
                 self.ignore_bytes(try_size(size, std::mem::size_of::<i32>())?)?;
@@ -122,20 +203,16 @@ pub trait Input<'de>: private::Sealed {
             }
             Tag::List => {
                 let element_tag = self.consume_tag()?;
-                let size = self.consume_i32()?;
+                let size = self.consume_len()?;
                 for _ in 0..size {
                     self.ignore_value(element_tag)?;
                 }
             }
             Tag::End => {
-                // If we are trying to ignore a list of empty compounds, that
-                // list might be indicated by a series of End tags. If this
-                // occurs then we should end the Compound branch of this match
-                // statement, where the end tag will be consumed. So we should
-                // never reach here.
-                //
-                // TODO: Write an explicit test for ignored list of compound.
-                unreachable!()
+                // End tags of compounds are consumed by the compound above, so
+                // this is the element of a non-empty list of end, which is
+                // rejected like it is when not ignored.
+                return Err(Error::list_of_end());
             }
         }
 
@@ -145,11 +222,12 @@ pub trait Input<'de>: private::Sealed {
 
 pub struct Slice<'de> {
     pub(crate) data: &'de [u8],
+    pub(crate) endian: Endian,
 }
 
 impl<'de> private::Sealed for Slice<'de> {}
 impl<'de> Slice<'de> {
-    fn consume(&mut self, r: Range<usize>) -> Result<&'de [u8]> {
+    pub(crate) fn consume(&mut self, r: Range<usize>) -> Result<&'de [u8]> {
         if r.end <= self.data.len() {
             let ret = &self.data[r.start..r.end];
             self.data = &self.data[r.end..];
@@ -161,17 +239,25 @@ impl<'de> Slice<'de> {
 }
 
 impl<'de> Input<'de> for Slice<'de> {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
     fn consume_byte(&mut self) -> Result<u8> {
         Ok(self.consume(0..1)?[0])
     }
 
     fn ignore_str(&mut self) -> Result<()> {
-        let len = self.consume(0..2)?.read_u16::<BigEndian>()? as usize;
+        let len = self.consume_str_len()?;
         self.consume(0..len).map(|_| ())
     }
 
+    fn is_at_end(&mut self) -> Result<bool> {
+        Ok(self.data.is_empty())
+    }
+
     fn consume_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's, str>> {
-        let len = self.consume(0..2)?.read_u16::<BigEndian>()? as usize;
+        let len = self.consume_str_len()?;
         let str = self.consume(0..len)?;
         let str = cesu8::from_java_cesu8(str).map_err(|_| Error::nonunicode_string(str))?;
 
@@ -197,54 +283,82 @@ impl<'de> Input<'de> for Slice<'de> {
 
     fn consume_i16(&mut self) -> Result<i16> {
         let mut bs = self.consume(0..std::mem::size_of::<i16>())?;
-        Ok(bs.read_i16::<BigEndian>()?)
+        Ok(read_endian!(bs, read_i16, self.endian)?)
     }
 
     fn consume_i32(&mut self) -> Result<i32> {
+        if self.endian == Endian::NetworkLittle {
+            return Ok(zigzag_i32(self.consume_varint(5)?));
+        }
         let mut bs = self.consume(0..std::mem::size_of::<i32>())?;
-        Ok(bs.read_i32::<BigEndian>()?)
+        Ok(read_endian!(bs, read_i32, self.endian)?)
     }
 
     fn consume_i64(&mut self) -> Result<i64> {
+        if self.endian == Endian::NetworkLittle {
+            return Ok(zigzag_i64(self.consume_varint(10)?));
+        }
         let mut bs = self.consume(0..std::mem::size_of::<i64>())?;
-        Ok(bs.read_i64::<BigEndian>()?)
+        Ok(read_endian!(bs, read_i64, self.endian)?)
     }
 
     fn consume_f32(&mut self) -> Result<f32> {
         let mut bs = self.consume(0..std::mem::size_of::<f32>())?;
-        Ok(bs.read_f32::<BigEndian>()?)
+        Ok(read_endian!(bs, read_f32, self.endian)?)
     }
 
     fn consume_f64(&mut self) -> Result<f64> {
         let mut bs = self.consume(0..std::mem::size_of::<f64>())?;
-        Ok(bs.read_f64::<BigEndian>()?)
+        Ok(read_endian!(bs, read_f64, self.endian)?)
     }
 
     fn ignore_bytes(&mut self, size: usize) -> Result<()> {
         self.consume(0..size)?;
         Ok(())
     }
+
+    fn consume_raw(&mut self, tag: Tag, out: &mut Vec<u8>) -> Result<()> {
+        let start = self.data;
+        self.ignore_value(tag)?;
+        out.extend_from_slice(&start[..start.len() - self.data.len()]);
+        Ok(())
+    }
 }
 
 pub struct Reader<R: Read> {
     pub(crate) reader: R,
+    pub(crate) endian: Endian,
 }
 
 impl<R: Read> private::Sealed for Reader<R> {}
 
 impl<'de, R: Read> Input<'de> for Reader<R> {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
     fn consume_byte(&mut self) -> Result<u8> {
         Ok(self.reader.read_u8()?)
     }
 
     fn ignore_str(&mut self) -> Result<()> {
-        let len = self.reader.read_u16::<BigEndian>()? as usize;
-        let mut buf = vec![0; len]; // TODO: try a scratch space to reduce allocs?
-        Ok(self.reader.read_exact(&mut buf)?)
+        let len = self.consume_str_len()?;
+        self.ignore_bytes(len)
+    }
+
+    fn is_at_end(&mut self) -> Result<bool> {
+        let mut buf = [0];
+        loop {
+            match self.reader.read(&mut buf) {
+                Ok(n) => return Ok(n == 0),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
     }
 
     fn consume_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's, str>> {
-        let len = self.reader.read_u16::<BigEndian>()? as usize;
+        let len = self.consume_str_len()?;
         scratch.clear();
         scratch.resize(len, 0);
         self.reader.read_exact(scratch)?;
@@ -267,36 +381,78 @@ impl<'de, R: Read> Input<'de> for Reader<R> {
         n: usize,
         scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'de, 's, [u8]>> {
+        // The length comes from the input, so grow the buffer as the data
+        // arrives rather than trusting it up front.
         scratch.clear();
-        scratch.resize(n, 0);
-        self.reader.read_exact(scratch.as_mut_slice())?;
+        let read = (&mut self.reader).take(n as u64).read_to_end(scratch)?;
+        if read < n {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
 
         Ok(Reference::Copied(scratch.as_slice()))
     }
 
     fn consume_i16(&mut self) -> Result<i16> {
-        Ok(self.reader.read_i16::<BigEndian>()?)
+        Ok(read_endian!(self.reader, read_i16, self.endian)?)
     }
 
     fn consume_i32(&mut self) -> Result<i32> {
-        Ok(self.reader.read_i32::<BigEndian>()?)
+        if self.endian == Endian::NetworkLittle {
+            return Ok(zigzag_i32(self.consume_varint(5)?));
+        }
+        Ok(read_endian!(self.reader, read_i32, self.endian)?)
     }
 
     fn consume_i64(&mut self) -> Result<i64> {
-        Ok(self.reader.read_i64::<BigEndian>()?)
+        if self.endian == Endian::NetworkLittle {
+            return Ok(zigzag_i64(self.consume_varint(10)?));
+        }
+        Ok(read_endian!(self.reader, read_i64, self.endian)?)
     }
 
     fn consume_f32(&mut self) -> Result<f32> {
-        Ok(self.reader.read_f32::<BigEndian>()?)
+        Ok(read_endian!(self.reader, read_f32, self.endian)?)
     }
 
     fn consume_f64(&mut self) -> Result<f64> {
-        Ok(self.reader.read_f64::<BigEndian>()?)
+        Ok(read_endian!(self.reader, read_f64, self.endian)?)
     }
 
     fn ignore_bytes(&mut self, size: usize) -> Result<()> {
-        let mut buf = vec![0; size]; // TODO: try a scratch space to reduce allocs?
-        self.reader.read_exact(&mut buf)?;
+        // Copy through a small buffer rather than reading it all at once, so
+        // that skipping a large value does not allocate for all of it.
+        let size = size as u64;
+        let read = std::io::copy(&mut (&mut self.reader).take(size), &mut std::io::sink())?;
+        if read < size {
+            // Report it like a short `read_exact` would, as an IO error.
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
         Ok(())
     }
+
+    fn consume_raw(&mut self, tag: Tag, out: &mut Vec<u8>) -> Result<()> {
+        // Skip the value as usual, keeping a copy of every byte read.
+        let mut tee = Reader {
+            reader: Tee {
+                reader: &mut self.reader,
+                out,
+            },
+            endian: self.endian,
+        };
+        tee.ignore_value(tag)
+    }
+}
+
+/// A reader that copies everything read through it to `out`.
+struct Tee<'a, R> {
+    reader: &'a mut R,
+    out: &'a mut Vec<u8>,
+}
+
+impl<'a, R: Read> Read for Tee<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.out.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
 }
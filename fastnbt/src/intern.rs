@@ -0,0 +1,332 @@
+//! This module contains [`InternedValue`], a value type like [`Value`] whose
+//! strings and compound keys are shared through a [`StringPool`].
+//!
+//! The same strings appear over and over in world data: every chunk has
+//! compounds with the same keys, and palettes name the same blocks, such as
+//! millions of `minecraft:stone`. Parsing into [`Value`] allocates each of
+//! these separately. Parsing into an [`InternedValue`] with
+//! [`from_bytes_interned`] looks each string up in the pool instead, so every
+//! distinct string is allocated once and shared as an [`Arc<str>`] by every
+//! value parsed with the same pool.
+//!
+//! # Tradeoffs
+//!
+//! Every string is hashed and looked up in the pool, which makes parsing
+//! slower than parsing into [`Value`]. In return each repeat of a string costs
+//! only a pointer rather than an allocation, which saves a lot of memory when
+//! holding many values with the same keys and names, such as a cache of
+//! chunks. For values that are parsed, used, and dropped straight away,
+//! [`Value`] is the better choice.
+//!
+//! The pool holds on to every string given to it, even after the values using
+//! it are dropped. Use [`StringPool::remove_unused`] to free those every so
+//! often.
+//!
+//! ```
+//! use fastnbt::intern::{from_bytes_interned, InternedValue, StringPool};
+//! use fastnbt::nbt;
+//! use std::sync::Arc;
+//!
+//! # fn main() -> fastnbt::error::Result<()> {
+//! let data = fastnbt::to_bytes(&nbt!({"Name": "minecraft:stone"}))?;
+//!
+//! let mut pool = StringPool::new();
+//! let a = from_bytes_interned(&data, &mut pool)?;
+//! let b = from_bytes_interned(&data, &mut pool)?;
+//!
+//! let name = |v: &InternedValue| match v.get("Name") {
+//!     Some(InternedValue::String(s)) => s.clone(),
+//!     _ => unreachable!(),
+//! };
+//! assert!(Arc::ptr_eq(&name(&a), &name(&b)));
+//! assert_eq!(pool.len(), 2); // "Name" and "minecraft:stone".
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_bytes::ByteBuf;
+
+use crate::{
+    de::Deserializer, error::Result, value::VALUE_TOKEN, ByteArray, DeOpts, IntArray, LongArray,
+    Tag, Value,
+};
+
+/// A set of strings shared between values. See the [module
+/// documentation][`crate::intern`].
+#[derive(Debug, Default, Clone)]
+pub struct StringPool {
+    strings: HashSet<Arc<str>>,
+}
+
+impl StringPool {
+    /// Create an empty pool.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the pool's copy of `s`, adding it to the pool if it is not there
+    /// yet.
+    pub fn intern(&mut self, s: &str) -> Arc<str> {
+        match self.strings.get(s) {
+            Some(s) => s.clone(),
+            None => {
+                let s: Arc<str> = Arc::from(s);
+                self.strings.insert(s.clone());
+                s
+            }
+        }
+    }
+
+    /// The number of distinct strings in the pool.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether the pool has no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Drop the strings that only the pool still holds, ie that are not used
+    /// by any value.
+    pub fn remove_unused(&mut self) {
+        self.strings.retain(|s| Arc::strong_count(s) > 1);
+    }
+}
+
+/// A value like [`Value`] whose strings and compound keys are shared through
+/// a [`StringPool`]. See the [module documentation][`crate::intern`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum InternedValue {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(Arc<str>),
+    ByteArray(ByteArray),
+    IntArray(IntArray),
+    LongArray(LongArray),
+    List(Vec<InternedValue>),
+    Compound(HashMap<Arc<str>, InternedValue>),
+}
+
+impl InternedValue {
+    /// The tag of this value.
+    pub fn tag(&self) -> Tag {
+        match self {
+            InternedValue::Byte(_) => Tag::Byte,
+            InternedValue::Short(_) => Tag::Short,
+            InternedValue::Int(_) => Tag::Int,
+            InternedValue::Long(_) => Tag::Long,
+            InternedValue::Float(_) => Tag::Float,
+            InternedValue::Double(_) => Tag::Double,
+            InternedValue::String(_) => Tag::String,
+            InternedValue::ByteArray(_) => Tag::ByteArray,
+            InternedValue::IntArray(_) => Tag::IntArray,
+            InternedValue::LongArray(_) => Tag::LongArray,
+            InternedValue::List(_) => Tag::List,
+            InternedValue::Compound(_) => Tag::Compound,
+        }
+    }
+
+    /// Get the value of `key` if this is a compound containing it.
+    pub fn get(&self, key: &str) -> Option<&InternedValue> {
+        match self {
+            InternedValue::Compound(v) => v.get(key),
+            _ => None,
+        }
+    }
+
+    /// Copy this value into a [`Value`], which does not share its strings.
+    pub fn to_value(&self) -> Value {
+        match self {
+            InternedValue::Byte(v) => Value::Byte(*v),
+            InternedValue::Short(v) => Value::Short(*v),
+            InternedValue::Int(v) => Value::Int(*v),
+            InternedValue::Long(v) => Value::Long(*v),
+            InternedValue::Float(v) => Value::Float(*v),
+            InternedValue::Double(v) => Value::Double(*v),
+            InternedValue::String(v) => Value::String(v.to_string()),
+            InternedValue::ByteArray(v) => Value::ByteArray(v.clone()),
+            InternedValue::IntArray(v) => Value::IntArray(v.clone()),
+            InternedValue::LongArray(v) => Value::LongArray(v.clone()),
+            InternedValue::List(v) => Value::List(v.iter().map(InternedValue::to_value).collect()),
+            InternedValue::Compound(v) => Value::Compound(
+                v.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Parse NBT data into an [`InternedValue`], sharing its strings through
+/// `pool`.
+pub fn from_bytes_interned(input: &[u8], pool: &mut StringPool) -> Result<InternedValue> {
+    from_bytes_interned_with_opts(input, pool, Default::default())
+}
+
+/// Similar to [`from_bytes_interned`] but with options.
+pub fn from_bytes_interned_with_opts(
+    input: &[u8],
+    pool: &mut StringPool,
+    opts: DeOpts,
+) -> Result<InternedValue> {
+    let mut des = Deserializer::from_bytes(input, opts);
+    ValueSeed { pool }.deserialize(&mut des)
+}
+
+struct ValueSeed<'a> {
+    pool: &'a mut StringPool,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for ValueSeed<'a> {
+    type Value = InternedValue;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ValueSeed<'a> {
+    type Value = InternedValue;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("valid NBT")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_i8<E: serde::de::Error>(self, v: i8) -> std::result::Result<Self::Value, E> {
+        Ok(InternedValue::Byte(v))
+    }
+
+    fn visit_i16<E: serde::de::Error>(self, v: i16) -> std::result::Result<Self::Value, E> {
+        Ok(InternedValue::Short(v))
+    }
+
+    fn visit_i32<E: serde::de::Error>(self, v: i32) -> std::result::Result<Self::Value, E> {
+        Ok(InternedValue::Int(v))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> std::result::Result<Self::Value, E> {
+        Ok(InternedValue::Long(v))
+    }
+
+    fn visit_f32<E: serde::de::Error>(self, v: f32) -> std::result::Result<Self::Value, E> {
+        Ok(InternedValue::Float(v))
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> std::result::Result<Self::Value, E> {
+        Ok(InternedValue::Double(v))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
+        Ok(InternedValue::String(self.pool.intern(v)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(el) = seq.next_element_seed(ValueSeed {
+            pool: &mut *self.pool,
+        })? {
+            list.push(el);
+        }
+        Ok(InternedValue::List(list))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let pool = self.pool;
+        let mut compound = HashMap::new();
+
+        while let Some(key) = map.next_key_seed(KeySeed { pool: &mut *pool })? {
+            let key = match key {
+                Key::Compound(key) => key,
+                Key::ByteArray => {
+                    let data = map.next_value::<ByteBuf>()?;
+                    return Ok(InternedValue::ByteArray(ByteArray::from_buf(
+                        data.into_vec(),
+                    )));
+                }
+                Key::IntArray => {
+                    let data = map.next_value::<ByteBuf>()?;
+                    return IntArray::from_bytes(&data)
+                        .map(InternedValue::IntArray)
+                        .map_err(|_| serde::de::Error::custom("could not read int array"));
+                }
+                Key::LongArray => {
+                    let data = map.next_value::<ByteBuf>()?;
+                    return LongArray::from_bytes(&data)
+                        .map(InternedValue::LongArray)
+                        .map_err(|_| serde::de::Error::custom("could not read long array"));
+                }
+            };
+
+            let value = map.next_value_seed(ValueSeed { pool: &mut *pool })?;
+            compound.insert(key, value);
+        }
+
+        Ok(InternedValue::Compound(compound))
+    }
+}
+
+enum Key {
+    Compound(Arc<str>),
+    ByteArray,
+    IntArray,
+    LongArray,
+}
+
+struct KeySeed<'a> {
+    pool: &'a mut StringPool,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for KeySeed<'a> {
+    type Value = Key;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for KeySeed<'a> {
+    type Value = Key;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an nbt field string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> std::result::Result<Self::Value, E> {
+        match s {
+            crate::BYTE_ARRAY_TOKEN => Ok(Key::ByteArray),
+            crate::INT_ARRAY_TOKEN => Ok(Key::IntArray),
+            crate::LONG_ARRAY_TOKEN => Ok(Key::LongArray),
+            _ => Ok(Key::Compound(self.pool.intern(s))),
+        }
+    }
+}
@@ -8,6 +8,30 @@
 //! * To easily create values, see the [`nbt`] macro.
 //! * For NBT array types see [`ByteArray`], [`IntArray`], and [`LongArray`].
 //! * For zero-copy NBT array types see [`borrow`].
+//! * To treat a `LongArray` as a Java `BitSet`, see [`bitset`].
+//! * To check a `Value` has a particular structure, see [`schema`].
+//! * To share repeated strings between many parsed values, see [`intern`].
+//! * To convert UUIDs to and from their `IntArray` form, see [`uuid`].
+//! * To keep values you do not need to understand as their raw NBT, to write
+//!   them back unchanged, see [`raw`].
+//! * To print NBT as indented SNBT without building a `Value`, see
+//!   [`to_pretty_snbt`].
+//! * For ready-made types for player data, enable the `minecraft` feature and
+//!   see the `minecraft` module.
+//! * To parse values into a `bumpalo` arena, enable the `bumpalo` feature and
+//!   see the `arena` module.
+//! * For timestamps like `LastPlayed` as `chrono` types, enable the `chrono`
+//!   feature and see the `chrono_millis` module.
+//! * To hash a `Value` by its content, enable the `sha2` feature and see
+//!   `Value::content_hash`.
+//! * To parse a `Value` from SNBT with `str::parse`, enable the `snbt`
+//!   feature.
+//! * To serialize straight into a `bytes::BytesMut`, enable the `bytes` feature
+//!   and see `ser::to_bytes_mut`. Deserializing from `bytes::Bytes` works
+//!   without it.
+//! * To generate `Value`s in a fuzzer, enable the `arbitrary` feature for an
+//!   implementation of `arbitrary::Arbitrary`. `arbitrary1` is an older name
+//!   for the same feature.
 //!
 //! Both this and related crates are under one [fastnbt Github
 //! repository](https://github.com/owengage/fastnbt).
@@ -108,7 +132,7 @@
 //!     let mut data = vec![];
 //!     decoder.read_to_end(&mut data).unwrap();
 //!
-//!     let player: Result<PlayerDat> = from_bytes(data.as_slice());
+//!     let player: Result<PlayerDat> = from_bytes(&data);
 //!
 //!     println!("{:#?}", player);
 //!# }
@@ -124,19 +148,34 @@
 use ser::Serializer;
 use serde::{de as serde_de, Deserialize, Serialize};
 
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+pub mod bitset;
 pub mod borrow;
+#[cfg(feature = "chrono")]
+pub mod chrono_millis;
 pub mod de;
+pub mod diff;
 pub mod error;
+pub mod intern;
+#[cfg(feature = "minecraft")]
+pub mod minecraft;
+pub mod raw;
+pub mod schema;
 pub mod ser;
 pub mod stream;
+pub mod uuid;
 pub mod value;
 
 mod arrays;
 mod input;
 #[macro_use]
 mod macros;
+mod snbt;
 
 pub use arrays::*;
+pub use diff::diff;
+pub use stream::to_pretty_snbt;
 pub use value::{from_value, to_value, Value};
 
 #[cfg(test)]
@@ -153,8 +192,30 @@ use std::{
 };
 
 /// An NBT tag. This does not carry the value or the name of the data.
-#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
-#[cfg_attr(feature = "arbitrary1", derive(arbitrary::Arbitrary))]
+///
+/// A `Tag` serializes as its numeric id, a `u8`, the same as in the NBT
+/// binary format. It deserializes from any integer type holding a valid id. So
+/// a `Tag` field in your own struct is a [`Tag::Byte`] in NBT, and a number in
+/// formats such as JSON. Use the [`Display`] implementation for the name of a
+/// tag.
+///
+/// ```
+/// # use fastnbt::{nbt, Tag};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Header {
+///     tag: Tag,
+/// }
+///
+/// let bytes = fastnbt::to_bytes(&Header { tag: Tag::IntArray }).unwrap();
+/// let value: fastnbt::Value = fastnbt::from_bytes(&bytes).unwrap();
+/// assert_eq!(value, nbt!({"tag": 11_i8}));
+///
+/// let header: Header = fastnbt::from_bytes(&bytes).unwrap();
+/// assert_eq!(header.tag, Tag::IntArray);
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum Tag {
     /// Represents the end of a Compound object.
@@ -188,6 +249,10 @@ pub enum Tag {
 // Crates exist to generate this code for us, but would add to our compile
 // times, so we instead write it out manually, the tags will very rarely change
 // so isn't a massive burden, but saves a significant amount of compile time.
+//
+// This compiles to a range check. In benches/tag_decoding.rs it decodes the
+// tags of a chunk faster than looking them up in a table, and either takes
+// well under 1% of the time to parse that chunk.
 impl TryFrom<u8> for Tag {
     type Error = ();
 
@@ -212,6 +277,92 @@ impl TryFrom<u8> for Tag {
     }
 }
 
+impl Tag {
+    /// Every tag, in order of their ids. This can be used in `const` contexts,
+    /// eg to build a table indexed by tag.
+    pub const ALL: [Tag; 13] = [
+        Tag::End,
+        Tag::Byte,
+        Tag::Short,
+        Tag::Int,
+        Tag::Long,
+        Tag::Float,
+        Tag::Double,
+        Tag::ByteArray,
+        Tag::String,
+        Tag::List,
+        Tag::Compound,
+        Tag::IntArray,
+        Tag::LongArray,
+    ];
+
+    /// Iterate over every tag, in order of their ids from [`Tag::End`] to
+    /// [`Tag::LongArray`]. See also [`Tag::ALL`].
+    ///
+    /// ```
+    /// # use fastnbt::Tag;
+    /// assert_eq!(Tag::all().filter(|t| t.is_array()).count(), 3);
+    /// ```
+    pub fn all() -> impl Iterator<Item = Tag> {
+        Self::ALL.into_iter()
+    }
+
+    /// The size in bytes of the payload of a fixed-width tag, such as 4 for
+    /// [`Tag::Int`]. This is `None` for tags with a variable-length payload:
+    /// strings, arrays, lists and compounds. [`Tag::End`] has no payload, so
+    /// its size is zero.
+    ///
+    /// This assumes the default big endian layout. Ints and longs in
+    /// [`Endian::NetworkLittle`] data are variable-length.
+    ///
+    /// ```
+    /// # use fastnbt::Tag;
+    /// assert_eq!(Tag::Long.payload_size(), Some(8));
+    /// assert_eq!(Tag::String.payload_size(), None);
+    /// ```
+    pub fn payload_size(self) -> Option<usize> {
+        match self {
+            Tag::End => Some(0),
+            Tag::Byte => Some(1),
+            Tag::Short => Some(2),
+            Tag::Int => Some(4),
+            Tag::Long => Some(8),
+            Tag::Float => Some(4),
+            Tag::Double => Some(8),
+            Tag::ByteArray
+            | Tag::String
+            | Tag::List
+            | Tag::Compound
+            | Tag::IntArray
+            | Tag::LongArray => None,
+        }
+    }
+
+    /// Whether this is a numeric tag, [`Tag::Byte`] through [`Tag::Double`].
+    ///
+    /// The predicates `is_scalar`, [`is_array`][`Tag::is_array`] and
+    /// [`is_container`][`Tag::is_container`] are all false for [`Tag::End`]
+    /// and [`Tag::String`].
+    pub fn is_scalar(self) -> bool {
+        matches!(
+            self,
+            Tag::Byte | Tag::Short | Tag::Int | Tag::Long | Tag::Float | Tag::Double
+        )
+    }
+
+    /// Whether this is one of the array tags, [`Tag::ByteArray`],
+    /// [`Tag::IntArray`] or [`Tag::LongArray`].
+    pub fn is_array(self) -> bool {
+        matches!(self, Tag::ByteArray | Tag::IntArray | Tag::LongArray)
+    }
+
+    /// Whether this tag contains other tags, ie [`Tag::List`] or
+    /// [`Tag::Compound`].
+    pub fn is_container(self) -> bool {
+        matches!(self, Tag::List | Tag::Compound)
+    }
+}
+
 impl From<Tag> for u8 {
     #[inline(always)]
     fn from(tag: Tag) -> Self {
@@ -219,6 +370,48 @@ impl From<Tag> for u8 {
     }
 }
 
+impl Serialize for Tag {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(u8::from(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Tag {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct TagVisitor;
+
+        impl<'de> serde_de::Visitor<'de> for TagVisitor {
+            type Value = Tag;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an NBT tag id from 0 to 12")
+            }
+
+            fn visit_u64<E: serde_de::Error>(self, v: u64) -> std::result::Result<Tag, E> {
+                u8::try_from(v)
+                    .ok()
+                    .and_then(|v| Tag::try_from(v).ok())
+                    .ok_or_else(|| E::invalid_value(serde_de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E: serde_de::Error>(self, v: i64) -> std::result::Result<Tag, E> {
+                u8::try_from(v)
+                    .ok()
+                    .and_then(|v| Tag::try_from(v).ok())
+                    .ok_or_else(|| E::invalid_value(serde_de::Unexpected::Signed(v), &self))
+            }
+        }
+
+        deserializer.deserialize_u8(TagVisitor)
+    }
+}
+
 impl Display for Tag {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
@@ -252,12 +445,47 @@ pub fn to_writer<T: Serialize, W: Write>(writer: W, v: &T) -> Result<()> {
     to_writer_with_opts(writer, v, Default::default())
 }
 
+/// Byte order of the numbers in NBT data. Java Edition uses big endian, while
+/// Bedrock Edition's files such as `level.dat` use little endian.
+///
+/// ```no_run
+/// # use fastnbt::{DeOpts, Endian, Value};
+/// # use fastnbt::error::Result;
+/// # fn main() -> Result<()> {
+/// let level_dat: Vec<u8> = std::fs::read("level.dat")?;
+///
+/// // Bedrock's level.dat starts with an 8 byte header before the NBT.
+/// let opts = DeOpts::new().endianness(Endian::Little);
+/// let level: Value = fastnbt::from_bytes_with_opts(&level_dat[8..], opts)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The [`stream`] parser only supports big endian data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    #[default]
+    Big,
+    Little,
+    /// Little endian, but with ints, longs and lengths written as varints.
+    /// This is used by Bedrock Edition's network protocol. Ints and longs,
+    /// including elements of int and long arrays, are zigzag encoded. String
+    /// lengths are unsigned.
+    NetworkLittle,
+}
+
 /// Options for customizing serialization.
 #[derive(Clone)]
 pub struct SerOpts {
     root_name: String,
     /// Whether to include the root compound name.
     serialize_root_name: bool,
+    /// Byte order of the output.
+    endian: Endian,
+    /// Whether to write compound entries sorted by key.
+    sort_keys: bool,
+    /// Maximum nesting depth of values written.
+    max_depth: usize,
 }
 
 impl Default for SerOpts {
@@ -265,6 +493,10 @@ impl Default for SerOpts {
         Self {
             root_name: Default::default(),
             serialize_root_name: true,
+            endian: Endian::Big,
+            sort_keys: false,
+            // The depth vanilla refuses to read past.
+            max_depth: 512,
         }
     }
 }
@@ -292,6 +524,48 @@ impl SerOpts {
         self.serialize_root_name = true;
         self
     }
+
+    /// Set the byte order of the output. Defaults to [`Endian::Big`], as used
+    /// by Java Edition.
+    pub fn endianness(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Write the entries of every compound sorted by the bytes of their keys,
+    /// rather than in the order they are serialized. This makes the output
+    /// deterministic for types like `HashMap` and [`Value`], which is useful
+    /// for diffing or hashing the output.
+    ///
+    /// Each compound is buffered in memory before it is written, so this is
+    /// slower than the default.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, SerOpts};
+    /// let opts = SerOpts::new().sort_keys(true);
+    /// let a = fastnbt::to_bytes_with_opts(&nbt!({"b": 1, "a": {"d": 2, "c": 3}}), opts.clone());
+    /// let b = fastnbt::to_bytes_with_opts(&nbt!({"a": {"c": 3, "d": 2}, "b": 1}), opts);
+    /// assert_eq!(a.unwrap(), b.unwrap());
+    /// ```
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Set the maximum nesting depth of the value written, where the root
+    /// compound is at depth 1, as for [`Value::depth`]. A compound or list
+    /// nested deeper is an error. This is 512 by default, the depth the game
+    /// refuses to read NBT past.
+    ///
+    /// Serializing through serde, such as with [`to_bytes`], recurses once
+    /// for every level of nesting, so raising this far can overflow the stack
+    /// on a deep value rather than give an error. [`ser::value_to_writer`]
+    /// and [`ser::value_to_bytes`] do not recurse, so can be given a limit of
+    /// `usize::MAX` to write a [`Value`] of any depth.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
 }
 
 /// Serialize some `T` into NBT data. See the [`ser`] module for more
@@ -303,6 +577,11 @@ pub fn to_bytes_with_opts<T: Serialize>(v: &T, opts: SerOpts) -> Result<Vec<u8>>
         writer: &mut result,
         root_name: opts.root_name,
         serialize_root_name: opts.serialize_root_name,
+        endian: opts.endian,
+        sort_keys: opts.sort_keys,
+        list_tags: Vec::new(),
+        depth: 0,
+        max_depth: opts.max_depth,
     };
     v.serialize(&mut serializer)?;
     Ok(result)
@@ -316,6 +595,11 @@ pub fn to_writer_with_opts<T: Serialize, W: Write>(writer: W, v: &T, opts: SerOp
         writer,
         root_name: opts.root_name,
         serialize_root_name: opts.serialize_root_name,
+        endian: opts.endian,
+        sort_keys: opts.sort_keys,
+        list_tags: Vec::new(),
+        depth: 0,
+        max_depth: opts.max_depth,
     };
     v.serialize(&mut serializer)?;
     Ok(())
@@ -324,6 +608,10 @@ pub fn to_writer_with_opts<T: Serialize, W: Write>(writer: W, v: &T, opts: SerOp
 /// Deserialize into a `T` from some NBT data. See the [`de`] module for more
 /// information.
 ///
+/// The input can be a reference to anything holding bytes, such as a
+/// `&[u8]`, `&Vec<u8>` or `&Box<[u8]>`. It is taken by reference so that `T`
+/// can borrow from it.
+///
 /// ```no_run
 /// # use fastnbt::Value;
 /// # use flate2::read::GzDecoder;
@@ -336,11 +624,11 @@ pub fn to_writer_with_opts<T: Serialize, W: Write>(writer: W, v: &T, opts: SerOp
 /// let mut buf = vec![];
 /// decoder.read_to_end(&mut buf).unwrap();
 ///
-/// let val: Value = fastnbt::from_bytes(buf.as_slice())?;
+/// let val: Value = fastnbt::from_bytes(&buf)?;
 /// # Ok(())
 /// # }
 /// ```
-pub fn from_bytes<'a, T>(input: &'a [u8]) -> Result<T>
+pub fn from_bytes<'a, T>(input: &'a (impl AsRef<[u8]> + ?Sized)) -> Result<T>
 where
     T: serde_de::Deserialize<'a>,
 {
@@ -377,6 +665,11 @@ pub fn from_reader_with_opts<'de, R, T>(reader: R, opts: DeOpts) -> Result<T>
 /// Deserialize into a `T` from some NBT data. See the [`de`] module for more
 /// information.
 ///
+/// This reads the data as it is parsed, so a decompressing reader can be given
+/// directly without holding all the decompressed data in memory. See
+/// [Reading from a `Read`](de#reading-from-a-read) for how this compares to
+/// [`from_bytes`].
+///
 /// ```no_run
 /// # use fastnbt::Value;
 /// # use flate2::read::GzDecoder;
@@ -405,6 +698,20 @@ pub struct DeOpts {
     max_seq_len: usize,
     /// Whether compound tag names are expected to exist or not.
     expect_coumpound_names: bool,
+    /// Byte order of the input.
+    endian: Endian,
+    /// Maximum number of bytes of data a single document can contain.
+    max_alloc: Option<usize>,
+    /// Whether structs error on keys they do not have a field for.
+    deny_unknown_fields: bool,
+    /// Whether struct fields match keys ignoring case.
+    case_insensitive_fields: bool,
+    /// Largest number of list elements hinted to visitors for preallocating.
+    prealloc_limit: usize,
+    /// Whether arrays are given to self-describing visitors as lists.
+    arrays_as_lists: bool,
+    /// Whether scalars of the wrong type deserialize as their default.
+    tolerant: bool,
 }
 
 impl DeOpts {
@@ -432,6 +739,186 @@ impl DeOpts {
         self.expect_coumpound_names = value;
         self
     }
+
+    /// Set the byte order of the input. Defaults to [`Endian::Big`], as used by
+    /// Java Edition.
+    ///
+    /// Borrowed arrays such as [`borrow::IntArray`] can only be deserialized
+    /// from big endian data. Use the owned arrays for little endian data.
+    pub fn endianness(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Set the maximum total size in bytes of the strings, arrays and lists in
+    /// a single document. Deserializing errors as soon as this budget would be
+    /// exceeded, before anything is allocated for the value that exceeds it.
+    /// There is no limit by default.
+    ///
+    /// Strings and arrays count their length in bytes, and lists count the
+    /// payload size of their elements. Data is counted even if it is borrowed
+    /// from the input. Data skipped over by the type being deserialized, such
+    /// as unknown struct fields or [`IgnoredAny`][`serde::de::IgnoredAny`], is
+    /// not counted, as nothing is allocated for it. This gives a simple bound
+    /// on memory use when deserializing untrusted data.
+    ///
+    /// ```
+    /// # use fastnbt::{DeOpts, Value};
+    /// # let nbt = fastnbt::to_bytes(&fastnbt::nbt!({"a": [B; 0, 0, 0, 0]})).unwrap();
+    /// let opts = DeOpts::new().max_alloc(3);
+    /// let err = fastnbt::from_bytes_with_opts::<Value>(&nbt, opts).unwrap_err();
+    /// assert!(err.to_string().contains("4 bytes"));
+    /// ```
+    pub fn max_alloc(mut self, value: usize) -> Self {
+        self.max_alloc = Some(value);
+        self
+    }
+
+    /// Set whether every struct in the document rejects compound keys it has
+    /// no field for, as if they all had `#[serde(deny_unknown_fields)]`. This
+    /// is off by default. The error gives the path to the unknown key.
+    ///
+    /// This applies to anything serde deserializes as a struct. Maps such as
+    /// `HashMap` and [`Value`] accept any key, as do structs using
+    /// `#[serde(flatten)]`, since serde deserializes those as maps.
+    ///
+    /// ```
+    /// # use fastnbt::DeOpts;
+    /// # use serde::Deserialize;
+    /// # let nbt = fastnbt::to_bytes(&fastnbt::nbt!({"pos": {"x": 1, "y": 2}})).unwrap();
+    /// #[derive(Deserialize, Debug)]
+    /// struct Entity {
+    ///     pos: Pos,
+    /// }
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// struct Pos {
+    ///     x: i32,
+    /// }
+    ///
+    /// let opts = DeOpts::new().deny_unknown_fields(true);
+    /// let err = fastnbt::from_bytes_with_opts::<Entity>(&nbt, opts).unwrap_err();
+    /// assert_eq!(err.to_string(), "pos.y: unknown field `y`, expected `x`");
+    /// ```
+    pub fn deny_unknown_fields(mut self, value: bool) -> Self {
+        self.deny_unknown_fields = value;
+        self
+    }
+
+    /// Set whether struct fields match compound keys ignoring ASCII case, so
+    /// that a field renamed to `DataVersion` also matches `dataversion` or
+    /// `dataVersion`. This is off by default, and is for tolerating data from
+    /// writers that are inconsistent about case.
+    ///
+    /// A key matching a field exactly is always used for that field, even if
+    /// it also matches another field ignoring case. Like
+    /// [`deny_unknown_fields`][`DeOpts::deny_unknown_fields`], this applies to
+    /// anything serde deserializes as a struct.
+    ///
+    /// ```
+    /// # use fastnbt::DeOpts;
+    /// # use serde::Deserialize;
+    /// # let nbt = fastnbt::to_bytes(&fastnbt::nbt!({"dataVersion": 3465})).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Chunk {
+    ///     #[serde(rename = "DataVersion")]
+    ///     data_version: i32,
+    /// }
+    ///
+    /// let opts = DeOpts::new().case_insensitive_fields(true);
+    /// let chunk: Chunk = fastnbt::from_bytes_with_opts(&nbt, opts).unwrap();
+    /// assert_eq!(chunk.data_version, 3465);
+    /// ```
+    pub fn case_insensitive_fields(mut self, value: bool) -> Self {
+        self.case_insensitive_fields = value;
+        self
+    }
+
+    /// Set the largest number of elements a list tells serde to preallocate
+    /// space for, such as with `Vec::with_capacity`. Defaults to 4096.
+    ///
+    /// The length of a list is read before its elements, so a list claiming
+    /// to be huge would otherwise allocate for every element it claims to have
+    /// before any are read. Longer lists still deserialize fully, growing as
+    /// needed. A higher limit avoids reallocating for lists you expect to be
+    /// large, a lower one uses less memory for lists that claim to be large
+    /// but are not. This is separate from [`max_seq_len`][`DeOpts::max_seq_len`],
+    /// which rejects long lists entirely.
+    pub fn prealloc_limit(mut self, value: usize) -> Self {
+        self.prealloc_limit = value;
+        self
+    }
+
+    /// Set whether byte, int and long arrays are given as lists of numbers to
+    /// types that accept any value, such as `serde_json::Value`. This is off
+    /// by default.
+    ///
+    /// Types that accept any value cannot otherwise tell NBT arrays apart
+    /// from lists. So by default fastnbt gives them arrays in a special form
+    /// that [`Value`] and the array types recognise, but that other types such
+    /// as `serde_json::Value` reject. Turning this on lets those types
+    /// deserialize any NBT, but loses the difference between arrays and
+    /// lists, as well as the exact number types. [`Value`] and fields of the
+    /// array types such as [`LongArray`] still get arrays, except where serde
+    /// buffers a value before deserializing it, for `#[serde(flatten)]` and
+    /// untagged or internally tagged enums. A [`Value`] from such a buffer
+    /// has lists where the NBT had arrays, and an array type fails.
+    ///
+    /// For a lossless textual form of NBT, deserialize to [`Value`] and use
+    /// SNBT, via the `fastsnbt` crate.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, DeOpts};
+    /// # let nbt = fastnbt::to_bytes(&nbt!({"UUID": [I; 1, 2, 3, 4]})).unwrap();
+    /// let opts = DeOpts::new().arrays_as_lists(true);
+    /// let json: serde_json::Value = fastnbt::from_bytes_with_opts(&nbt, opts).unwrap();
+    ///
+    /// assert_eq!(json.to_string(), r#"{"UUID":[1,2,3,4]}"#);
+    /// ```
+    pub fn arrays_as_lists(mut self, value: bool) -> Self {
+        self.arrays_as_lists = value;
+        self
+    }
+
+    /// Set whether numbers, strings and bools of the wrong type deserialize
+    /// as that type's default, rather than failing. This is off by default,
+    /// and is for salvaging what can be read from corrupt data or data from
+    /// inconsistent writers.
+    ///
+    /// A field expecting an integer gets `0` for a value that is not an
+    /// integer or is out of range for the type. Likewise floats get `0.0`,
+    /// strings get an empty string and bools get `false`. The mismatched value
+    /// is skipped. Other mismatches, such as a list where a struct is
+    /// expected, still fail.
+    ///
+    /// The paths of the values replaced, in the same form as the paths of
+    /// errors, are given by
+    /// [`coerced_paths`][`de::Deserializer::coerced_paths`]. To get them,
+    /// deserialize with a [`Deserializer`][`de::Deserializer`] directly rather
+    /// than with [`from_bytes_with_opts`].
+    ///
+    /// ```
+    /// # use fastnbt::{de::Deserializer, nbt, DeOpts};
+    /// # use serde::Deserialize;
+    /// # let nbt = fastnbt::to_bytes(&nbt!({"Health": "20", "Air": 300, "Name": "Steve"})).unwrap();
+    /// #[derive(Deserialize)]
+    /// #[serde(rename_all = "PascalCase")]
+    /// struct Entity {
+    ///     health: f32,
+    ///     air: i16,
+    ///     name: String,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_bytes(&nbt, DeOpts::new().tolerant(true));
+    /// let entity = Entity::deserialize(&mut de).unwrap();
+    ///
+    /// assert_eq!((entity.health, entity.air, entity.name.as_str()), (0.0, 300, "Steve"));
+    /// assert_eq!(de.coerced_paths(), ["Health"]);
+    /// ```
+    pub fn tolerant(mut self, value: bool) -> Self {
+        self.tolerant = value;
+        self
+    }
 }
 
 impl Default for DeOpts {
@@ -439,16 +926,27 @@ impl Default for DeOpts {
         Self {
             max_seq_len: 10_000_000, // arbitrary high limit.
             expect_coumpound_names: true,
+            endian: Endian::Big,
+            max_alloc: None,
+            deny_unknown_fields: false,
+            case_insensitive_fields: false,
+            prealloc_limit: 4096,
+            arrays_as_lists: false,
+            tolerant: false,
         }
     }
 }
 
 /// Similar to [`from_bytes`] but with options.
-pub fn from_bytes_with_opts<'a, T>(input: &'a [u8], opts: DeOpts) -> Result<T>
+pub fn from_bytes_with_opts<'a, T>(
+    input: &'a (impl AsRef<[u8]> + ?Sized),
+    opts: DeOpts,
+) -> Result<T>
 where
     T: serde_de::Deserialize<'a>,
 {
     const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+    let input = input.as_ref();
 
     // Provide freindly error for the common case of passing GZip data to
     // `from_bytes`. This would be invalid starting data for NBT anyway.
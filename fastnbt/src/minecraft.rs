@@ -0,0 +1,187 @@
+//! This module contains types for data files written by Minecraft, such as
+//! [`PlayerDat`] for the player data found in a world's `playerdata`
+//! directory, and [`EntityChunk`] for the chunks of the region files in its
+//! `entities` directory. This requires the `minecraft` feature.
+//!
+//! These capture the commonly used fields. Everything else is kept in a
+//! catch-all map, so nothing is lost when reading a file from a version with
+//! fields these types do not know about.
+//!
+//! ```no_run
+//! use fastnbt::minecraft::read_player_dat;
+//!
+//! # fn main() -> fastnbt::error::Result<()> {
+//! let file = std::fs::File::open("world/playerdata/some-uuid.dat")?;
+//! let player = read_player_dat(file)?;
+//!
+//! println!("health: {}", player.health);
+//! for item in player.inventory.iter().chain(&player.ender_items) {
+//!     println!("{} x{}", item.id, item.count);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+
+use crate::{error::Result, IntArray, Value};
+
+/// The data of a player, as stored in `playerdata/<uuid>.dat` files, or in
+/// the `Player` compound of single player `level.dat` files. See
+/// [`read_player_dat`] for reading these files.
+///
+/// Fields that come and go between versions are optional. Any other field is
+/// kept in [`other`][`PlayerDat::other`].
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerDat {
+    /// The version of the game that wrote this data. Missing before 1.9.
+    pub data_version: Option<i32>,
+
+    /// Position as x, y and z.
+    pub pos: (f64, f64, f64),
+    /// Velocity as x, y and z.
+    pub motion: (f64, f64, f64),
+    /// Rotation as yaw then pitch, in degrees.
+    pub rotation: (f32, f32),
+    /// The dimension the player is in, eg `minecraft:overworld`. Before 1.16
+    /// this was a number, which is read as `None`.
+    #[serde(default, deserialize_with = "dimension")]
+    pub dimension: Option<String>,
+
+    pub health: f32,
+    #[serde(rename = "foodLevel")]
+    pub food_level: i32,
+    pub xp_level: i32,
+    pub xp_total: i32,
+    /// The game mode, 0 to 3 for survival, creative, adventure and spectator.
+    #[serde(rename = "playerGameType")]
+    pub game_type: i32,
+
+    /// The hotbar slot in hand, 0 to 8.
+    pub selected_item_slot: i32,
+    pub inventory: Vec<Item>,
+    /// The contents of the player's ender chest.
+    #[serde(default)]
+    pub ender_items: Vec<Item>,
+
+    /// Every other field, such as `abilities` and `Attributes`.
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// A stack of items in an inventory.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Item {
+    /// The slot the item is in. The slots of the player's inventory are
+    /// described on the [wiki](https://minecraft.wiki/w/Player.dat_format).
+    #[serde(rename = "Slot")]
+    pub slot: Option<i8>,
+    /// The item's id, eg `minecraft:stone`.
+    pub id: String,
+    /// The number of items in the stack. This is read from `count` as
+    /// written since 1.20.5, or `Count` as written before.
+    #[serde(alias = "Count")]
+    pub count: i32,
+
+    /// Every other field, such as `components` since 1.20.5 or `tag` before.
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// A chunk of the region files in the `entities` directory of a dimension,
+/// where entities have been stored apart from the rest of the chunk since
+/// 1.17. The region files are the same as the ones in the `region`
+/// directory, so are read the same way, eg with `fastanvil::Region`. Only the
+/// NBT of each chunk is different.
+///
+/// ```no_run
+/// # use fastnbt::minecraft::EntityChunk;
+/// # let data: Vec<u8> = todo!(); // a chunk of `entities/r.0.0.mca`
+/// let chunk: EntityChunk = fastnbt::from_bytes(&data).unwrap();
+/// for entity in &chunk.entities {
+///     println!("{} at {:?}", entity.id, entity.pos);
+/// }
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EntityChunk {
+    /// The version of the game that wrote this data.
+    pub data_version: i32,
+    /// The x and z coordinates of the chunk, in chunks rather than blocks.
+    #[serde(deserialize_with = "chunk_position")]
+    pub position: (i32, i32),
+    pub entities: Vec<Entity>,
+}
+
+/// An entity, such as a mob or a dropped item, in an [`EntityChunk`].
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Entity {
+    /// The entity's id, eg `minecraft:zombie`.
+    pub id: String,
+    /// Position as x, y and z.
+    #[serde(rename = "Pos")]
+    pub pos: (f64, f64, f64),
+
+    /// Every other field, such as `UUID`, `Health` or `Passengers`.
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+impl Entity {
+    /// The entity's UUID, if it has one stored as an `IntArray` as written
+    /// since 1.16.
+    pub fn uuid(&self) -> Option<u128> {
+        match self.other.get("UUID") {
+            Some(Value::IntArray(arr)) => crate::uuid::from_int_array(arr),
+            _ => None,
+        }
+    }
+}
+
+/// Read a [`PlayerDat`] from a player data file. The game compresses these
+/// with gzip, but uncompressed NBT is also accepted.
+pub fn read_player_dat<R: Read>(mut reader: R) -> Result<PlayerDat> {
+    const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+
+    if data.starts_with(&GZIP_MAGIC_BYTES) {
+        let mut decompressed = vec![];
+        GzDecoder::new(data.as_slice()).read_to_end(&mut decompressed)?;
+        data = decompressed;
+    }
+
+    crate::from_bytes(&data)
+}
+
+/// Keep the dimension only if it is a name. Versions before 1.16 stored a
+/// number, which is not worth failing the whole file for.
+fn dimension<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => Some(s),
+        _ => None,
+    })
+}
+
+/// Read the `Position` of an [`EntityChunk`], an `IntArray` of x and z.
+fn chunk_position<'de, D>(deserializer: D) -> std::result::Result<(i32, i32), D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match *IntArray::deserialize(deserializer)? {
+        [x, z] => Ok((x, z)),
+        ref other => Err(serde::de::Error::invalid_length(
+            other.len(),
+            &"2 ints for x and z",
+        )),
+    }
+}
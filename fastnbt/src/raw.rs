@@ -0,0 +1,284 @@
+//! Keep values as the raw NBT they were read from, to write them back
+//! unchanged.
+//!
+//! A [`RawNbt`] holds the tag of a value and the bytes of its payload exactly
+//! as they were in the input. This is for the parts of a structure you do not
+//! need to understand but want to preserve, eg editing one field of a chunk
+//! and saving it with everything else byte-identical. Use it as a field type,
+//! or with `#[serde(with = "fastnbt::raw")]`:
+//!
+//! ```
+//! # use fastnbt::{nbt, raw::RawNbt, Value};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Chunk {
+//!     #[serde(rename = "DataVersion")]
+//!     data_version: i32,
+//!     #[serde(with = "fastnbt::raw")]
+//!     sections: RawNbt,
+//! }
+//!
+//! # fn main() -> fastnbt::error::Result<()> {
+//! # let input = fastnbt::to_bytes(&nbt!({
+//! #     "DataVersion": 3465,
+//! #     "sections": [{"Y": -4_i8, "biomes": {"palette": ["minecraft:plains"]}}],
+//! # }))?;
+//! let mut chunk: Chunk = fastnbt::from_bytes(&input)?;
+//! chunk.data_version = 3700;
+//! let output = fastnbt::to_bytes(&chunk)?;
+//!
+//! let mut expected: Value = fastnbt::from_bytes(&input)?;
+//! *expected.get_path_mut(&["DataVersion"]).unwrap() = Value::Int(3700);
+//! assert_eq!(fastnbt::from_bytes::<Value>(&output)?, expected);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The fields of a struct are written in the order they are declared, so to
+//! write a whole compound back byte-identical declare its fields in the order
+//! they appear in the input.
+//!
+//! Only this crate's serializer and deserializer, and [`Value`] through
+//! [`to_value`][`crate::to_value`] and [`from_value`][`crate::from_value`],
+//! know about raw values. Other formats, and serde's buffering of values for
+//! `#[serde(flatten)]` and untagged or internally tagged enums, see a map with
+//! a single private key instead.
+//!
+//! The payload is kept in the byte order it was read in. Writing it with a
+//! serializer in another byte order writes it unchanged, so produces corrupt
+//! NBT.
+
+use std::fmt::Debug;
+
+use serde::{
+    de::{self, value::BorrowedStrDeserializer, Visitor},
+    forward_to_deserialize_any, Deserialize, Serialize,
+};
+use serde_bytes::{ByteBuf, Bytes};
+
+use crate::{
+    error::{Error, Result},
+    SerOpts, Tag, Value,
+};
+
+pub(crate) const RAW_TOKEN: &str = "__fastnbt_raw";
+
+/// The tag and raw payload of an NBT value. See the [`raw`][`crate::raw`]
+/// module.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RawNbt {
+    // The tag, followed by the payload. This is the form it is passed through
+    // serde in, so it needs no copying.
+    data: Vec<u8>,
+}
+
+impl RawNbt {
+    /// Create a raw value from its tag and payload. The payload is not
+    /// checked, so if it is not a valid payload of the tag this is written as
+    /// corrupt NBT.
+    pub fn new(tag: Tag, payload: Vec<u8>) -> Self {
+        let mut data = payload;
+        data.insert(0, u8::from(tag));
+        Self { data }
+    }
+
+    /// Encode a value as raw NBT, in big endian.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, raw::RawNbt, Tag};
+    /// let raw = RawNbt::from_value(&nbt!(1_i16)).unwrap();
+    /// assert_eq!(raw.tag(), Tag::Short);
+    /// assert_eq!(raw.payload(), &[0, 1]);
+    /// ```
+    pub fn from_value(value: &Value) -> Result<Self> {
+        // Written as the only entry of a root compound, so the payload is
+        // between the entry's empty name and the compound's end tag.
+        let root = Value::Compound([(String::new(), value.clone())].into());
+        let nbt = crate::ser::value_to_bytes(&root, SerOpts::new())?;
+
+        let mut data = vec![nbt[3]];
+        data.extend_from_slice(&nbt[6..nbt.len() - 1]);
+        Ok(Self { data })
+    }
+
+    /// Parse the payload into a [`Value`], as big endian.
+    pub fn to_value(&self) -> Result<Value> {
+        let mut nbt = vec![u8::from(Tag::Compound), 0, 0, self.data[0], 0, 0];
+        nbt.extend_from_slice(self.payload());
+        nbt.push(u8::from(Tag::End));
+
+        let mut root: std::collections::HashMap<String, Value> = crate::from_bytes(&nbt)?;
+        root.remove("")
+            .ok_or_else(|| Error::bespoke("invalid raw nbt payload".to_string()))
+    }
+
+    /// The tag of the value.
+    pub fn tag(&self) -> Tag {
+        // Only valid tags are ever stored.
+        Tag::try_from(self.data[0]).unwrap()
+    }
+
+    /// The payload of the value, as it was in the input. This does not include
+    /// the tag, or the name if it was in a compound.
+    pub fn payload(&self) -> &[u8] {
+        &self.data[1..]
+    }
+
+    /// Take the payload of the value.
+    pub fn into_payload(mut self) -> Vec<u8> {
+        self.data.remove(0);
+        self.data
+    }
+
+    pub(crate) fn from_data(data: Vec<u8>) -> Result<Self> {
+        match data.first() {
+            Some(&tag) => match Tag::try_from(tag) {
+                Ok(_) => Ok(Self { data }),
+                Err(_) => Err(Error::invalid_tag(tag)),
+            },
+            None => Err(Error::bespoke("raw nbt has no tag".to_string())),
+        }
+    }
+}
+
+// The payload can be any size, so only show how big it is.
+impl Debug for RawNbt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RawNbt({:?}, {} bytes)",
+            self.tag(),
+            self.payload().len()
+        )
+    }
+}
+
+impl Serialize for RawNbt {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Inner<'a> {
+            __fastnbt_raw: &'a Bytes,
+        }
+
+        Inner {
+            __fastnbt_raw: Bytes::new(&self.data),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawNbt {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct InnerVisitor;
+        impl<'de> Visitor<'de> for InnerVisitor {
+            type Value = RawNbt;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("raw nbt")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let token = map.next_key::<&str>()?.ok_or_else(|| {
+                    de::Error::custom("expected NBT raw token, but got empty map")
+                })?;
+                if token != RAW_TOKEN {
+                    return Err(de::Error::custom("expected NBT raw token"));
+                }
+
+                let data = map.next_value::<ByteBuf>()?;
+                RawNbt::from_data(data.into_vec()).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_struct(RAW_TOKEN, &[RAW_TOKEN], InnerVisitor)
+    }
+}
+
+/// Serialize a [`RawNbt`] field, for `#[serde(with = "fastnbt::raw")]`.
+pub fn serialize<S>(raw: &RawNbt, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    raw.serialize(serializer)
+}
+
+/// Deserialize a [`RawNbt`] field, for `#[serde(with = "fastnbt::raw")]`.
+pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<RawNbt, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    RawNbt::deserialize(deserializer)
+}
+
+/// Hands the tag and payload of a raw value to [`RawNbt`], in the same shape
+/// it serializes as.
+pub(crate) struct RawAccess {
+    data: Option<Vec<u8>>,
+}
+
+impl RawAccess {
+    /// Create the access from the tag followed by the payload.
+    pub(crate) fn new(data: Vec<u8>) -> Self {
+        Self { data: Some(data) }
+    }
+
+    pub(crate) fn from_value(value: &Value) -> Result<Self> {
+        Ok(Self::new(RawNbt::from_value(value)?.data))
+    }
+}
+
+impl<'de> de::MapAccess<'de> for RawAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.data {
+            Some(_) => seed
+                .deserialize(BorrowedStrDeserializer::new(RAW_TOKEN))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let data = self
+            .data
+            .take()
+            .ok_or_else(|| Error::bespoke("next_value called before next_key".to_string()))?;
+        seed.deserialize(ByteBufDeserializer(data))
+    }
+}
+
+/// Gives its bytes to the visitor without copying them.
+struct ByteBufDeserializer(Vec<u8>);
+
+impl<'de> de::Deserializer<'de> for ByteBufDeserializer {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_byte_buf(self.0)
+    }
+}
@@ -0,0 +1,160 @@
+//! This module contains [`Shape`], for checking that a [`Value`] has a given
+//! structure without caring about the exact data inside it. This is useful for
+//! validating data, and for keeping tests concise.
+//!
+//! ```
+//! use fastnbt::{nbt, schema::Shape, Tag};
+//!
+//! // A compound with a `Pos` key that is a list of 3 doubles.
+//! let shape = Shape::compound([("Pos", Shape::list_of_len(3, Shape::tag(Tag::Double)))]);
+//!
+//! assert!(shape.matches(&nbt!({"Pos": [1.0, 64.0, 1.0], "id": "minecraft:pig"})));
+//! assert!(!shape.matches(&nbt!({"Pos": [1.0, 64.0]})));
+//!
+//! let mismatch = shape.check(&nbt!({"Pos": [1.0, 64, 1.0]})).unwrap_err();
+//! assert_eq!(mismatch.path, "Pos[1]");
+//! assert_eq!(mismatch.to_string(), "at Pos[1]: expected double, found int");
+//! ```
+
+use std::fmt::Display;
+
+use crate::{Tag, Value};
+
+/// The expected structure of a [`Value`]. See the [`schema`][`crate::schema`]
+/// module for an example.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// Matches any value.
+    Any,
+    /// Matches any value with the given tag, eg any double.
+    Tag(Tag),
+    /// Matches a list whose elements all match the element shape. If `len` is
+    /// set the list must have exactly that many elements.
+    List {
+        len: Option<usize>,
+        element: Box<Shape>,
+    },
+    /// Matches a compound containing at least the given keys, with values
+    /// matching their shapes. Other keys are allowed.
+    Compound(Vec<(String, Shape)>),
+}
+
+/// The first place a [`Value`] did not match a [`Shape`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Path to the mismatching value, eg `Level.Sections[2].Y`. This is empty
+    /// if the top level value did not match.
+    pub path: String,
+    /// What was wrong with the value.
+    pub reason: String,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.reason)
+        } else {
+            write!(f, "at {}: {}", self.path, self.reason)
+        }
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+impl Shape {
+    /// Matches any value with the given tag.
+    pub fn tag(tag: Tag) -> Self {
+        Shape::Tag(tag)
+    }
+
+    /// Matches a list of any length whose elements all match `element`.
+    pub fn list(element: Shape) -> Self {
+        Shape::List {
+            len: None,
+            element: Box::new(element),
+        }
+    }
+
+    /// Matches a list of exactly `len` elements that all match `element`.
+    pub fn list_of_len(len: usize, element: Shape) -> Self {
+        Shape::List {
+            len: Some(len),
+            element: Box::new(element),
+        }
+    }
+
+    /// Matches a compound containing at least the given keys.
+    pub fn compound<K: Into<String>>(entries: impl IntoIterator<Item = (K, Shape)>) -> Self {
+        Shape::Compound(entries.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Whether `value` matches this shape.
+    pub fn matches(&self, value: &Value) -> bool {
+        self.check(value).is_ok()
+    }
+
+    /// Check that `value` matches this shape, giving the first mismatch if it
+    /// does not.
+    pub fn check(&self, value: &Value) -> Result<(), Mismatch> {
+        let mut path = String::new();
+        self.check_at(value, &mut path)
+            .map_err(|reason| Mismatch { path, reason })
+    }
+
+    // On a mismatch `path` is left pointing at the mismatching value.
+    fn check_at(&self, value: &Value, path: &mut String) -> Result<(), String> {
+        match (self, value) {
+            (Shape::Any, _) => Ok(()),
+            (Shape::Tag(tag), v) if v.tag() == *tag => Ok(()),
+            (Shape::List { len, element }, Value::List(list)) => {
+                if let Some(len) = len {
+                    if list.len() != *len {
+                        return Err(format!(
+                            "expected list of length {}, found length {}",
+                            len,
+                            list.len()
+                        ));
+                    }
+                }
+
+                let parent_len = path.len();
+                for (i, v) in list.iter().enumerate() {
+                    path.push_str(&format!("[{i}]"));
+                    element.check_at(v, path)?;
+                    path.truncate(parent_len);
+                }
+                Ok(())
+            }
+            (Shape::Compound(entries), Value::Compound(map)) => {
+                let parent_len = path.len();
+                for (key, shape) in entries {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+
+                    match map.get(key) {
+                        Some(v) => shape.check_at(v, path)?,
+                        None => return Err("missing key".to_string()),
+                    }
+                    path.truncate(parent_len);
+                }
+                Ok(())
+            }
+            (shape, v) => Err(format!(
+                "expected {}, found {}",
+                shape.expected_tag(),
+                v.tag()
+            )),
+        }
+    }
+
+    fn expected_tag(&self) -> Tag {
+        match self {
+            Shape::Tag(tag) => *tag,
+            Shape::List { .. } => Tag::List,
+            Shape::Compound(_) => Tag::Compound,
+            Shape::Any => unreachable!("any value matches Shape::Any"),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::io::Write;
 
 use serde::ser::Impossible;
 
-use crate::{error::Error, error::Result, Tag};
+use crate::{error::Error, error::Result, Endian, Tag};
 
 use super::{serializer::Serializer, write_nbt::WriteNbt};
 
@@ -50,8 +50,28 @@ impl<'a, W: Write> serde::Serializer for ArraySerializer<'a, W> {
             _ => panic!(),
         };
         let len = v.len() / stride;
-        self.ser.writer.write_len(len)?;
-        self.ser.writer.write_all(v)?;
+        let endian = self.ser.endian;
+        self.ser.writer.write_len(len, endian)?;
+
+        // The array types hold their data big endian, so can be written as-is
+        // unless we want another byte order.
+        if stride == 1 || endian == Endian::Big {
+            self.ser.writer.write_all(v)?;
+            return Ok(());
+        }
+
+        for el in v.chunks_exact(stride) {
+            match *el {
+                [a, b, c, d] => self
+                    .ser
+                    .writer
+                    .write_nbt_int(i32::from_be_bytes([a, b, c, d]), endian)?,
+                _ => self
+                    .ser
+                    .writer
+                    .write_nbt_long(i64::from_be_bytes(el.try_into().unwrap()), endian)?,
+            }
+        }
         Ok(())
     }
 
@@ -7,6 +7,10 @@
 //! result in an error (not a panic). If you find a case where you think there
 //! is a valid way to serialize it, please open an issue.
 //!
+//! For building NBT imperatively without serde, see [`NbtWriter`]. To
+//! serialize a [`Value`] of any depth without recursing, such as one parsed
+//! from untrusted data, see [`value_to_writer`].
+//!
 //! The examples directory contains some examples. The [`de`][`crate::de`]
 //! module contains more information about (de)serialization.
 //!
@@ -16,11 +20,23 @@
 //! `i128` or `u128`, an IntArray of length 4 will be produced. This is stored
 //! as big endian i.e. the most significant bit (and int) is first.
 //!
+//! # Map keys
+//!
+//! NBT compound keys are always strings. Maps with integer keys, such as a
+//! `HashMap<i32, T>` registry keyed by numeric ID, are serialized with each key
+//! converted to its decimal string, as JSON does. Deserializing back into a map
+//! with integer keys parses them again, giving an error for a key that is not
+//! a valid integer of that type. Other key types, such as floats, are an error.
+//!
+//! Like strings, keys can be at most 65535 bytes long once encoded, except in
+//! network little endian NBT. A longer key is an error showing the start of
+//! the key, given before anything of its entry is written.
+//!
 //! # Root compound name
 //!
 //! A valid NBT compound must have a name, including the root compound. For most
 //! Minecraft data this is simply the empty string. If you need to control the
-//! name of this root compound you can use
+//! name of this root compound you can use [`to_bytes_named`],
 //! [`to_bytes_with_opts`][`crate::to_bytes_with_opts`] and
 //! [`to_writer_with_opts`][`crate::to_writer_with_opts`]. For example the
 //! [unofficial schematic
@@ -46,9 +62,100 @@
 //! let structure: Schematic = todo!(); // make schematic
 //! let bytes = fastnbt::to_bytes_with_opts(&structure, SerOpts::new().root_name("Schematic")).unwrap();
 //! ```
+//!
+//! To save part of a larger value as a document of its own, eg a single
+//! section of a chunk, see [`to_bytes_rooted`].
 mod array_serializer;
 mod name_serializer;
+mod nbt_writer;
 mod serializer;
+mod value_writer;
 mod write_nbt;
 
+pub use nbt_writer::NbtWriter;
 pub use serializer::*;
+pub use value_writer::{value_to_bytes, value_to_writer};
+
+use std::collections::HashMap;
+
+use crate::{error::Result, SerOpts, Value};
+
+/// Serialize some `T` into NBT data with the root compound named `name`, as
+/// formats such as schematics expect. This is short for
+/// [`to_bytes_with_opts`][`crate::to_bytes_with_opts`] with
+/// [`SerOpts::root_name`]. Use
+/// [`from_bytes_with_name`][`crate::de::from_bytes_with_name`] to read the
+/// name back.
+///
+/// ```
+/// # use fastnbt::{de::from_bytes_with_name, nbt, Value};
+/// # fn main() -> fastnbt::error::Result<()> {
+/// let bytes = fastnbt::ser::to_bytes_named("Schematic", &nbt!({"Version": 2}))?;
+///
+/// let (name, value): (String, Value) = from_bytes_with_name(&bytes)?;
+/// assert_eq!(name, "Schematic");
+/// assert_eq!(value, nbt!({"Version": 2}));
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_bytes_named<T: serde::Serialize>(name: &str, value: &T) -> Result<Vec<u8>> {
+    crate::to_bytes_with_opts(value, SerOpts::new().root_name(name))
+}
+
+/// Serialize `value` as a standalone NBT document with the root name `name`,
+/// eg to save a subtree of a larger value as its own file.
+///
+/// A document must have a compound at its root. A compound is written as the
+/// root itself, named `name`. Any other value, such as a list or an int, is
+/// wrapped in an unnamed root compound with `name` as its only key.
+///
+/// ```
+/// # use fastnbt::{nbt, Value};
+/// # fn main() -> fastnbt::error::Result<()> {
+/// let chunk = nbt!({"sections": [{"Y": -4}, {"Y": -3}]});
+/// let section = chunk.find_all("sections/1")[0];
+///
+/// let bytes = fastnbt::ser::to_bytes_rooted("section", section)?;
+/// assert_eq!(fastnbt::from_bytes::<Value>(&bytes)?, nbt!({"Y": -3}));
+///
+/// let y = section.find_all("Y")[0];
+/// let bytes = fastnbt::ser::to_bytes_rooted("Y", y)?;
+/// assert_eq!(fastnbt::from_bytes::<Value>(&bytes)?, nbt!({"Y": -3}));
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_bytes_rooted(name: &str, value: &Value) -> Result<Vec<u8>> {
+    match value {
+        Value::Compound(_) => crate::to_bytes_with_opts(value, SerOpts::new().root_name(name)),
+        _ => crate::to_bytes(&HashMap::from([(name, value)])),
+    }
+}
+
+/// Serialize some `T` into NBT data, appending it to `buf`. This avoids the
+/// copy from the `Vec` that [`to_bytes`][`crate::to_bytes`] returns, eg when
+/// building packets in a tokio codec. Requires the `bytes` feature.
+///
+/// The data is left in `buf` if serializing fails partway through, so clear
+/// it or drop it in that case.
+///
+/// Deserializing needs no feature, as [`from_bytes`][`crate::from_bytes`]
+/// takes anything that is `AsRef<[u8]>`, including `Bytes` and `BytesMut`.
+///
+/// ```
+/// # use fastnbt::{nbt, Value};
+/// use bytes::BytesMut;
+///
+/// # fn main() -> fastnbt::error::Result<()> {
+/// let mut buf = BytesMut::new();
+/// fastnbt::ser::to_bytes_mut(&nbt!({"x": 1}), &mut buf)?;
+///
+/// let bytes = buf.freeze();
+/// assert_eq!(fastnbt::from_bytes::<Value>(&bytes)?, nbt!({"x": 1}));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "bytes")]
+pub fn to_bytes_mut<T: serde::Serialize>(v: &T, buf: &mut bytes::BytesMut) -> Result<()> {
+    use bytes::BufMut;
+    crate::to_writer(buf.writer(), v)
+}
@@ -39,6 +39,18 @@ macro_rules! must_be_stringy {
     };
 }
 
+// Integer keys are written as their decimal string, like JSON.
+macro_rules! stringify_int {
+    ($($ser:ident($t:ty)),* $(,)?) => {
+        $(
+            fn $ser(self, v: $t) -> Result<Self::Ok> {
+                write!(self.name, "{}", v)?;
+                Ok(())
+            }
+        )*
+    };
+}
+
 /// NameSerializer is all about serializing the name of a field. It does not
 /// write the length or the tag. We typically need to write this to a different
 /// buffer than the main one we're writing to, because we need to write out the
@@ -72,15 +84,20 @@ impl<W: Write> Serializer for &mut NameSerializer<W> {
         Ok(())
     }
 
+    stringify_int! {
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_i128(i128),
+        serialize_u128(u128),
+    }
+
     must_be_stringy!("bool": serialize_bool(bool));
-    must_be_stringy!("i8": serialize_i8(i8));
-    must_be_stringy!("i16": serialize_i16(i16));
-    must_be_stringy!("i32": serialize_i32(i32));
-    must_be_stringy!("i64": serialize_i64(i64));
-    must_be_stringy!("u8": serialize_u8(u8));
-    must_be_stringy!("u16": serialize_u16(u16));
-    must_be_stringy!("u32": serialize_u32(u32));
-    must_be_stringy!("u64": serialize_u64(u64));
     must_be_stringy!("f32": serialize_f32(f32));
     must_be_stringy!("f64": serialize_f64(f64));
     must_be_stringy!("none": serialize_none());
@@ -0,0 +1,277 @@
+use std::io::Write;
+
+use crate::{
+    error::{Error, Result},
+    Endian, Tag,
+};
+
+use super::write_nbt::{check_name, WriteNbt};
+
+enum Layer {
+    Compound,
+    List { tag: Tag, remaining: usize },
+}
+
+macro_rules! values {
+    ($($field:ident, $element:ident, $payload:ident($t:ty) => $tag:ident;)*) => {
+        $(
+            #[doc = concat!("Write a named ", stringify!($tag), " in the current compound.")]
+            pub fn $field(&mut self, name: &str, v: $t) -> Result<()> {
+                self.begin_field(Tag::$tag, name)?;
+                self.$payload(v)
+            }
+
+            #[doc = concat!("Write a ", stringify!($tag), " element in the current list.")]
+            pub fn $element(&mut self, v: $t) -> Result<()> {
+                self.begin_element(Tag::$tag)?;
+                self.$payload(v)
+            }
+        )*
+    };
+}
+
+/// Writes NBT imperatively, one value at a time. This is the writing
+/// counterpart of the [`stream`][`crate::stream`] parser, for data that does
+/// not map cleanly onto a serde type.
+///
+/// Values in a compound are written with the `field_*` methods, and elements of
+/// a list with the `element_*` methods. Compounds and lists are closed with
+/// [`end`][`NbtWriter::end`]. The structure is checked as it is written, so
+/// for example writing a named field into a list, or too many elements into a
+/// list, is an error.
+///
+/// ```
+/// # use fastnbt::{ser::NbtWriter, Tag, Value};
+/// # fn main() -> fastnbt::error::Result<()> {
+/// let mut writer = NbtWriter::new(Vec::new());
+/// writer.compound("")?;
+/// writer.field_int("DataVersion", 3465)?;
+/// writer.list("sections", Tag::Compound, 2)?;
+/// for y in [-4, -3] {
+///     writer.element_compound()?;
+///     writer.field_byte("Y", y)?;
+///     writer.end()?;
+/// }
+/// writer.end()?; // sections
+/// writer.end()?; // root
+///
+/// let bytes = writer.finish()?;
+/// let chunk: Value = fastnbt::from_bytes(&bytes)?;
+/// assert_eq!(chunk, fastnbt::nbt!({
+///     "DataVersion": 3465,
+///     "sections": [{"Y": -4_i8}, {"Y": -3_i8}],
+/// }));
+/// # Ok(())
+/// # }
+/// ```
+pub struct NbtWriter<W: Write> {
+    writer: W,
+    endian: Endian,
+    stack: Vec<Layer>,
+    started: bool,
+}
+
+impl<W: Write> NbtWriter<W> {
+    /// Create a writer for big endian NBT, as used by Java Edition.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            endian: Endian::Big,
+            stack: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Set the byte order of the output.
+    pub fn endianness(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Start a compound. The first call starts the root compound, after which
+    /// this starts a named compound in the current compound.
+    pub fn compound(&mut self, name: &str) -> Result<()> {
+        if !self.started {
+            let name = cesu8::to_java_cesu8(name);
+            check_name(&name, self.endian)?;
+            self.started = true;
+            self.writer.write_tag(Tag::Compound)?;
+            self.writer.write_size_prefixed_bytes(&name, self.endian)?;
+        } else {
+            self.begin_field(Tag::Compound, name)?;
+        }
+        self.stack.push(Layer::Compound);
+        Ok(())
+    }
+
+    /// Start a compound element in the current list.
+    pub fn element_compound(&mut self) -> Result<()> {
+        self.begin_element(Tag::Compound)?;
+        self.stack.push(Layer::Compound);
+        Ok(())
+    }
+
+    /// Start a named list of `len` elements of `tag` in the current compound.
+    pub fn list(&mut self, name: &str, tag: Tag, len: usize) -> Result<()> {
+        self.begin_field(Tag::List, name)?;
+        self.start_list(tag, len)
+    }
+
+    /// Start a list element of `len` elements of `tag` in the current list.
+    pub fn element_list(&mut self, tag: Tag, len: usize) -> Result<()> {
+        self.begin_element(Tag::List)?;
+        self.start_list(tag, len)
+    }
+
+    values! {
+        field_byte, element_byte, payload_byte(i8) => Byte;
+        field_short, element_short, payload_short(i16) => Short;
+        field_int, element_int, payload_int(i32) => Int;
+        field_long, element_long, payload_long(i64) => Long;
+        field_float, element_float, payload_float(f32) => Float;
+        field_double, element_double, payload_double(f64) => Double;
+        field_string, element_string, payload_string(&str) => String;
+        field_byte_array, element_byte_array, payload_byte_array(&[i8]) => ByteArray;
+        field_int_array, element_int_array, payload_int_array(&[i32]) => IntArray;
+        field_long_array, element_long_array, payload_long_array(&[i64]) => LongArray;
+    }
+
+    /// End the current compound or list. It is an error to end a list before
+    /// all of its elements have been written.
+    pub fn end(&mut self) -> Result<()> {
+        match self.stack.pop() {
+            Some(Layer::Compound) => self.writer.write_tag(Tag::End),
+            Some(Layer::List { remaining: 0, .. }) => Ok(()),
+            Some(Layer::List { remaining, .. }) => Err(Error::bespoke(format!(
+                "cannot end list with {} elements left to write",
+                remaining
+            ))),
+            None => Err(Error::bespoke("no compound or list to end".to_string())),
+        }
+    }
+
+    /// Finish writing, returning the underlying writer. It is an error to
+    /// finish before the root compound has been ended.
+    pub fn finish(self) -> Result<W> {
+        if !self.started || !self.stack.is_empty() {
+            return Err(Error::bespoke(
+                "cannot finish before the root compound is ended".to_string(),
+            ));
+        }
+        Ok(self.writer)
+    }
+
+    fn begin_field(&mut self, tag: Tag, name: &str) -> Result<()> {
+        match self.stack.last() {
+            Some(Layer::Compound) => {
+                let name = cesu8::to_java_cesu8(name);
+                check_name(&name, self.endian)?;
+                self.writer.write_tag(tag)?;
+                self.writer.write_size_prefixed_bytes(&name, self.endian)
+            }
+            Some(Layer::List { .. }) => Err(Error::bespoke(format!(
+                "cannot write named field {} in a list",
+                name
+            ))),
+            None => Err(Error::bespoke(format!(
+                "cannot write field {} outside of a compound",
+                name
+            ))),
+        }
+    }
+
+    fn begin_element(&mut self, tag: Tag) -> Result<()> {
+        match self.stack.last_mut() {
+            Some(Layer::List {
+                tag: list_tag,
+                remaining,
+            }) => {
+                if *list_tag != tag {
+                    return Err(Error::bespoke(format!(
+                        "cannot write {} element in list of {}",
+                        tag, list_tag
+                    )));
+                }
+                if *remaining == 0 {
+                    return Err(Error::bespoke(
+                        "list already has all its elements".to_string(),
+                    ));
+                }
+                *remaining -= 1;
+                Ok(())
+            }
+            _ => Err(Error::bespoke(format!(
+                "cannot write {} element outside of a list",
+                tag
+            ))),
+        }
+    }
+
+    fn start_list(&mut self, tag: Tag, len: usize) -> Result<()> {
+        if tag == Tag::End && len != 0 {
+            return Err(Error::bespoke(
+                "cannot write non-empty list of end".to_string(),
+            ));
+        }
+        self.writer.write_tag(tag)?;
+        self.writer.write_len(len, self.endian)?;
+        self.stack.push(Layer::List {
+            tag,
+            remaining: len,
+        });
+        Ok(())
+    }
+
+    fn payload_byte(&mut self, v: i8) -> Result<()> {
+        self.writer.write_all(&[v as u8])?;
+        Ok(())
+    }
+
+    fn payload_short(&mut self, v: i16) -> Result<()> {
+        self.writer.write_nbt_short(v, self.endian)
+    }
+
+    fn payload_int(&mut self, v: i32) -> Result<()> {
+        self.writer.write_nbt_int(v, self.endian)
+    }
+
+    fn payload_long(&mut self, v: i64) -> Result<()> {
+        self.writer.write_nbt_long(v, self.endian)
+    }
+
+    fn payload_float(&mut self, v: f32) -> Result<()> {
+        self.writer.write_nbt_float(v, self.endian)
+    }
+
+    fn payload_double(&mut self, v: f64) -> Result<()> {
+        self.writer.write_nbt_double(v, self.endian)
+    }
+
+    fn payload_string(&mut self, v: &str) -> Result<()> {
+        self.writer.write_size_prefixed_str(v, self.endian)
+    }
+
+    fn payload_byte_array(&mut self, v: &[i8]) -> Result<()> {
+        self.writer.write_len(v.len(), self.endian)?;
+        // Safe to treat [i8] as [u8].
+        let bytes = unsafe { &*(v as *const [i8] as *const [u8]) };
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn payload_int_array(&mut self, v: &[i32]) -> Result<()> {
+        self.writer.write_len(v.len(), self.endian)?;
+        for i in v {
+            self.payload_int(*i)?;
+        }
+        Ok(())
+    }
+
+    fn payload_long_array(&mut self, v: &[i64]) -> Result<()> {
+        self.writer.write_len(v.len(), self.endian)?;
+        for l in v {
+            self.payload_long(*l)?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use std::{io::Write, mem};
 
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::WriteBytesExt;
 use serde::{
     ser::{self, Impossible, SerializeTuple},
     Serialize,
@@ -8,15 +8,19 @@ use serde::{
 
 use crate::{
     error::{Error, Result},
-    Tag, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN,
+    raw::RAW_TOKEN,
+    Endian, Tag, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN,
 };
 
 use super::{
-    array_serializer::ArraySerializer, name_serializer::NameSerializer, write_nbt::WriteNbt,
+    array_serializer::ArraySerializer,
+    name_serializer::NameSerializer,
+    write_nbt::{check_name, WriteNbt},
 };
 
 enum DelayedHeader {
     List { len: usize }, // header for a list, so element tag and list size.
+    ListElement { index: usize, expected: Tag }, // no header, but the element must match the list.
     MapEntry { outer_name: Vec<u8> }, // header for a compound, so tag, name of compound.
     Root { root_name: Option<String> }, // root compound, special because it isn't allowed to be an array type. Must be compound.
 }
@@ -28,6 +32,31 @@ pub struct Serializer<W: Write> {
     // NOTE: This is `mem:take`en, so is only valid at the start of serialization!
     pub(crate) root_name: String,
     pub(crate) serialize_root_name: bool,
+
+    // Byte order of numbers written.
+    pub(crate) endian: Endian,
+
+    // Whether compound entries are buffered and written sorted by key.
+    pub(crate) sort_keys: bool,
+
+    // Element tags of the lists being serialized, innermost last. Each is
+    // filled in when the header of the list is written with its first element.
+    pub(crate) list_tags: Vec<Option<Tag>>,
+
+    // Compounds and lists that are open, and how many may be.
+    pub(crate) depth: usize,
+    pub(crate) max_depth: usize,
+}
+
+impl<W: Write> Serializer<W> {
+    // Start a compound or list, which is an error if it is too deep.
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > self.max_depth {
+            return Err(Error::too_deep(self.max_depth));
+        }
+        Ok(())
+    }
 }
 
 macro_rules! no_root {
@@ -59,9 +88,9 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
     type SerializeStruct = SerializerMap<'a, W>;
     type SerializeStructVariant = Impossible<(), Error>;
 
-    fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
@@ -72,6 +101,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
         let root_name = mem::take(&mut self.root_name);
         let serialize_root_name = mem::take(&mut self.serialize_root_name);
         Ok(SerializerMap {
+            sorted: self.sort_keys.then(Vec::new),
             ser: self,
             key: None,
             header: Some(DelayedHeader::Root {
@@ -135,9 +165,16 @@ pub struct SerializerMap<'a, W: Write> {
     key: Option<Vec<u8>>,
     header: Option<DelayedHeader>,
     trailer: Option<Tag>,
+    // Serialized entries and their names, if writing sorted keys.
+    sorted: Option<Vec<(Vec<u8>, Vec<u8>)>>,
 }
 
-fn write_header(writer: &mut impl Write, header: DelayedHeader, actual_tag: Tag) -> Result<()> {
+fn write_header<W: Write>(
+    ser: &mut Serializer<W>,
+    header: DelayedHeader,
+    actual_tag: Tag,
+) -> Result<()> {
+    let (writer, endian) = (&mut ser.writer, ser.endian);
     match header {
         DelayedHeader::Root {
             root_name: outer_name,
@@ -146,19 +183,33 @@ fn write_header(writer: &mut impl Write, header: DelayedHeader, actual_tag: Tag)
                 // TODO: Test case for this.
                 return Err(Error::no_root_compound());
             }
-            writer.write_tag(Tag::Compound)?;
-            if let Some(outer_name) = &outer_name {
-                writer.write_size_prefixed_str(&outer_name)?;
+            match &outer_name {
+                Some(outer_name) => {
+                    let outer_name = cesu8::to_java_cesu8(outer_name);
+                    check_name(&outer_name, endian)?;
+                    writer.write_tag(Tag::Compound)?;
+                    writer.write_size_prefixed_bytes(&outer_name, endian)?;
+                }
+                None => writer.write_tag(Tag::Compound)?,
             }
         }
         DelayedHeader::MapEntry { ref outer_name } => {
             writer.write_tag(actual_tag)?;
-            writer.write_u16::<BigEndian>(outer_name.len() as u16)?;
-            writer.write_all(outer_name)?;
+            writer.write_size_prefixed_bytes(outer_name, endian)?;
         }
         DelayedHeader::List { len } => {
             writer.write_tag(actual_tag)?;
-            writer.write_len(len)?;
+            writer.write_len(len, endian)?;
+            // The header is written before anything inside the element, so
+            // this list is still the innermost.
+            if let Some(tag) = ser.list_tags.last_mut() {
+                *tag = Some(actual_tag);
+            }
+        }
+        DelayedHeader::ListElement { index, expected } => {
+            if actual_tag != expected {
+                return Err(Error::heterogeneous_list(index, expected, actual_tag));
+            }
         }
     };
     Ok(())
@@ -169,25 +220,30 @@ impl<'a, W: Write> serde::ser::SerializeMap for SerializerMap<'a, W> {
 
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<()>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         // Get the name ahead of time.
         let mut name = Vec::new();
         key.serialize(&mut NameSerializer { name: &mut name })?;
+        check_name(&name, self.ser.endian)?;
         self.key = Some(name);
         Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         let name = self.key.take().ok_or_else(|| {
             Error::bespoke("serialize_value called before serialize_key".to_string())
         })?;
 
+        if name == RAW_TOKEN.as_bytes() {
+            return self.serialize_raw(value);
+        }
+
         let outer_tag = match std::str::from_utf8(&name) {
             Ok(BYTE_ARRAY_TOKEN) => Tag::ByteArray,
             Ok(INT_ARRAY_TOKEN) => Tag::IntArray,
@@ -196,7 +252,10 @@ impl<'a, W: Write> serde::ser::SerializeMap for SerializerMap<'a, W> {
         };
 
         if let Some(header) = self.header.take() {
-            write_header(&mut self.ser.writer, header, outer_tag)?;
+            write_header(self.ser, header, outer_tag)?;
+            if outer_tag == Tag::Compound {
+                self.ser.enter()?;
+            }
         }
 
         match std::str::from_utf8(&name) {
@@ -221,11 +280,34 @@ impl<'a, W: Write> serde::ser::SerializeMap for SerializerMap<'a, W> {
                     tag: Tag::LongArray,
                 })
             }
-            _ => value.serialize(&mut Delayed {
-                ser: &mut *self.ser,
-                header: Some(DelayedHeader::MapEntry { outer_name: name }),
-                is_list: false,
-            }),
+            _ => match &mut self.sorted {
+                Some(entries) => {
+                    let mut entry = Serializer {
+                        writer: Vec::new(),
+                        root_name: String::new(),
+                        serialize_root_name: false,
+                        endian: self.ser.endian,
+                        sort_keys: true,
+                        list_tags: Vec::new(),
+                        depth: self.ser.depth,
+                        max_depth: self.ser.max_depth,
+                    };
+                    value.serialize(&mut Delayed {
+                        ser: &mut entry,
+                        header: Some(DelayedHeader::MapEntry {
+                            outer_name: name.clone(),
+                        }),
+                        is_list: false,
+                    })?;
+                    entries.push((name, entry.writer));
+                    Ok(())
+                }
+                None => value.serialize(&mut Delayed {
+                    ser: &mut *self.ser,
+                    header: Some(DelayedHeader::MapEntry { outer_name: name }),
+                    is_list: false,
+                }),
+            },
         }
     }
 
@@ -235,10 +317,43 @@ impl<'a, W: Write> serde::ser::SerializeMap for SerializerMap<'a, W> {
                 // if we still have a header, that means that we haven't seen a
                 // single key, so it must be an empty compound, we need to write
                 // the bytes we have delayed then close off the compound.
-                write_header(&mut self.ser.writer, header, Tag::Compound)?;
+                write_header(self.ser, header, Tag::Compound)?;
+                self.ser.enter()?;
+            }
+            if let Some(mut entries) = self.sorted.take() {
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                for (_, entry) in entries {
+                    self.ser.writer.write_all(&entry)?;
+                }
             }
             self.ser.writer.write_tag(tag)?;
+            self.ser.depth -= 1;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, W: Write> SerializerMap<'a, W> {
+    // Write the tag and payload of a `RawNbt` as they are. It serializes as a
+    // struct with its tag and payload as the only field, so this struct takes
+    // the place of the value.
+    fn serialize_raw<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        // The name serializer takes bytes as they are, which is all we need.
+        let mut data = Vec::new();
+        value.serialize(&mut NameSerializer { name: &mut data })?;
+        let (&tag, payload) = data
+            .split_first()
+            .ok_or_else(|| Error::bespoke("raw nbt has no tag".to_string()))?;
+        let tag = Tag::try_from(tag).map_err(|_| Error::invalid_tag(tag))?;
+
+        if let Some(header) = self.header.take() {
+            write_header(self.ser, header, tag)?;
         }
+        self.trailer = None;
+        self.ser.writer.write_all(payload)?;
         Ok(())
     }
 }
@@ -248,9 +363,9 @@ impl<'a, W: Write> serde::ser::SerializeStruct for SerializerMap<'a, W> {
 
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         ser::SerializeMap::serialize_entry(self, key, value)
     }
@@ -263,16 +378,19 @@ impl<'a, W: Write> serde::ser::SerializeStruct for SerializerMap<'a, W> {
 pub struct SerializerTuple<'a, W: Write> {
     pub(crate) ser: &'a mut Serializer<W>,
     pub(crate) len: usize,
-    pub(crate) first: bool,
+    // Index of the next element.
+    pub(crate) index: usize,
+    // Tag of the elements, known once the first has been serialized.
+    pub(crate) tag: Option<Tag>,
 }
 
 impl<'a, W: 'a + Write> serde::ser::SerializeSeq for SerializerTuple<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         <Self as serde::ser::SerializeTuple>::serialize_element(self, value)
     }
@@ -287,20 +405,37 @@ impl<'a, W: 'a + Write> serde::ser::SerializeTuple for SerializerTuple<'a, W> {
 
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        value.serialize(&mut Delayed {
+        let header = match self.tag {
+            Some(expected) => DelayedHeader::ListElement {
+                index: self.index,
+                expected,
+            },
+            None => {
+                self.ser.list_tags.push(None);
+                DelayedHeader::List { len: self.len }
+            }
+        };
+
+        let result = value.serialize(&mut Delayed {
             ser: self.ser,
-            header: self.first.then_some(DelayedHeader::List { len: self.len }),
+            header: Some(header),
             is_list: true,
-        })?;
-        self.first = false;
+        });
+        if self.tag.is_none() {
+            self.tag = self.ser.list_tags.pop().flatten();
+        }
+        result?;
+
+        self.index += 1;
         Ok(())
     }
 
     fn end(self) -> Result<()> {
+        self.ser.depth -= 1;
         Ok(())
     }
 }
@@ -311,12 +446,12 @@ impl<'a, W: 'a + Write> serde::ser::SerializeTupleStruct for SerializerTuple<'a,
     type Error = Error;
 
     fn end(self) -> Result<()> {
-        Ok(())
+        <Self as serde::ser::SerializeTuple>::end(self)
     }
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         self.serialize_element(value)
     }
@@ -328,12 +463,12 @@ impl<'a, W: 'a + Write> serde::ser::SerializeTupleVariant for SerializerTuple<'a
     type Error = Error;
 
     fn end(self) -> Result<()> {
-        Ok(())
+        <Self as serde::ser::SerializeTuple>::end(self)
     }
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         self.serialize_element(value)
     }
@@ -348,7 +483,7 @@ struct Delayed<'a, W: Write + 'a> {
 impl<'a, W: Write + 'a> Delayed<'a, W> {
     fn write_header(&mut self, tag: Tag) -> Result<()> {
         if let Some(header) = self.header.take() {
-            write_header(&mut self.ser.writer, header, tag)?;
+            write_header(self.ser, header, tag)?;
         }
         Ok(())
     }
@@ -379,19 +514,19 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_i16(self, v: i16) -> Result<()> {
         self.write_header(Tag::Short)?;
-        self.ser.writer.write_i16::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_short(v, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_i32(self, v: i32) -> Result<()> {
         self.write_header(Tag::Int)?;
-        self.ser.writer.write_i32::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_int(v, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_i64(self, v: i64) -> Result<()> {
         self.write_header(Tag::Long)?;
-        self.ser.writer.write_i64::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_long(v, self.ser.endian)?;
         Ok(())
     }
 
@@ -401,11 +536,17 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_u128(self, v: u128) -> Result<()> {
         self.write_header(Tag::IntArray)?;
-        self.ser.writer.write_len(4)?;
-        self.ser.writer.write_u32::<BigEndian>((v >> 96) as u32)?;
-        self.ser.writer.write_u32::<BigEndian>((v >> 64) as u32)?;
-        self.ser.writer.write_u32::<BigEndian>((v >> 32) as u32)?;
-        self.ser.writer.write_u32::<BigEndian>(v as u32)?;
+        self.ser.writer.write_len(4, self.ser.endian)?;
+        self.ser
+            .writer
+            .write_nbt_int((v >> 96) as i32, self.ser.endian)?;
+        self.ser
+            .writer
+            .write_nbt_int((v >> 64) as i32, self.ser.endian)?;
+        self.ser
+            .writer
+            .write_nbt_int((v >> 32) as i32, self.ser.endian)?;
+        self.ser.writer.write_nbt_int(v as i32, self.ser.endian)?;
         Ok(())
     }
 
@@ -417,50 +558,52 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_u16(self, v: u16) -> Result<()> {
         self.write_header(Tag::Short)?;
-        self.ser.writer.write_u16::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_short(v as i16, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_u32(self, v: u32) -> Result<()> {
         self.write_header(Tag::Int)?;
-        self.ser.writer.write_u32::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_int(v as i32, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_u64(self, v: u64) -> Result<()> {
         self.write_header(Tag::Long)?;
-        self.ser.writer.write_u64::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_long(v as i64, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
         self.write_header(Tag::Float)?;
-        self.ser.writer.write_f32::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_float(v, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_f64(self, v: f64) -> Result<()> {
         self.write_header(Tag::Double)?;
-        self.ser.writer.write_f64::<BigEndian>(v)?;
+        self.ser.writer.write_nbt_double(v, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_char(self, v: char) -> Result<()> {
         self.write_header(Tag::Int)?;
-        self.ser.writer.write_u32::<BigEndian>(v as u32)?;
+        self.ser.writer.write_nbt_int(v as i32, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_str(self, v: &str) -> Result<()> {
         self.write_header(Tag::String)?;
-        self.ser.writer.write_size_prefixed_str(v)?;
+        self.ser
+            .writer
+            .write_size_prefixed_str(v, self.ser.endian)?;
         Ok(())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
         self.write_header(Tag::List)?;
         self.ser.writer.write_tag(Tag::Byte)?;
-        self.ser.writer.write_len(v.len())?;
+        self.ser.writer.write_len(v.len(), self.ser.endian)?;
         self.ser.writer.write_all(v)?;
         Ok(())
     }
@@ -476,9 +619,9 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
         }
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<()>
+    fn serialize_some<T>(self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
@@ -500,17 +643,19 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
         variant: &'static str,
     ) -> Result<()> {
         self.write_header(Tag::String)?;
-        self.ser.writer.write_size_prefixed_str(variant)
+        self.ser
+            .writer
+            .write_size_prefixed_str(variant, self.ser.endian)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -518,7 +663,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
         _value: &T,
     ) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         Err(Error::bespoke(
             "cannot serialize newtype variant, please open fastnbt issue".to_string(),
@@ -534,6 +679,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
         self.write_header(Tag::List)?;
+        self.ser.enter()?;
         if len == 0 {
             // Weird case, serialize_element will never be called so we won't
             // get chance to write the tag type of the list. Worse still we have
@@ -545,12 +691,13 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
             // A list of end tags seems to be the way to go.
 
             self.ser.writer.write_tag(Tag::End)?;
-            self.ser.writer.write_u32::<BigEndian>(0)?; // ie len
+            self.ser.writer.write_len(0, self.ser.endian)?; // ie len
         }
 
         Ok(SerializerTuple {
             ser: self.ser,
-            first: true,
+            index: 0,
+            tag: None,
             len,
         })
     }
@@ -575,6 +722,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
         Ok(SerializerMap {
+            sorted: self.ser.sort_keys.then(Vec::new),
             ser: self.ser,
             key: None,
             header: self.header.take(),
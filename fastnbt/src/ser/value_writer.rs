@@ -0,0 +1,199 @@
+use std::{collections::hash_map, io::Write};
+
+use crate::{
+    error::{Error, Result},
+    Endian, SerOpts, Tag, Value,
+};
+
+use super::write_nbt::{check_name, WriteNbt};
+
+/// Serialize a [`Value`] into NBT data, without recursing. See
+/// [`value_to_writer`].
+///
+/// ```
+/// # use fastnbt::{nbt, SerOpts, Value};
+/// # fn main() -> fastnbt::error::Result<()> {
+/// let value = nbt!({"a": {"b": [{}]}});
+/// let bytes = fastnbt::ser::value_to_bytes(&value, SerOpts::new())?;
+/// assert_eq!(fastnbt::from_bytes::<Value>(&bytes)?, value);
+///
+/// let err = fastnbt::ser::value_to_bytes(&value, SerOpts::new().max_depth(3)).unwrap_err();
+/// assert_eq!(err.to_string(), "value is nested deeper than the maximum depth of 3");
+/// # Ok(())
+/// # }
+/// ```
+pub fn value_to_bytes(value: &Value, opts: SerOpts) -> Result<Vec<u8>> {
+    let mut result = vec![];
+    value_to_writer(&mut result, value, opts)?;
+    Ok(result)
+}
+
+/// Serialize a [`Value`] into NBT data, without recursing.
+///
+/// This writes the same data as [`to_writer_with_opts`][`crate::to_writer_with_opts`],
+/// but keeps its place in the value on the heap rather than the stack. So
+/// however deeply nested the value is, such as one parsed from untrusted
+/// data, this cannot overflow the stack. Serializing through serde recurses
+/// once for every level of nesting.
+///
+/// Values nested deeper than [`SerOpts::max_depth`], 512 by default, are
+/// refused as they are by the serde serializer. Set it to `usize::MAX` to
+/// write a value of any depth.
+///
+/// Dropping a [`Value`] still recurses once for every level of nesting, so a
+/// value deep enough to overflow the stack when serialized through serde can
+/// overflow it when dropped as well.
+pub fn value_to_writer<W: Write>(mut writer: W, value: &Value, opts: SerOpts) -> Result<()> {
+    let root = match value {
+        Value::Compound(root) => root,
+        _ => return Err(Error::no_root_compound()),
+    };
+
+    let endian = opts.endian;
+    let max_depth = opts.max_depth;
+    if max_depth == 0 {
+        return Err(Error::too_deep(max_depth));
+    }
+
+    if opts.serialize_root_name {
+        let name = cesu8::to_java_cesu8(&opts.root_name);
+        check_name(&name, endian)?;
+        writer.write_tag(Tag::Compound)?;
+        writer.write_size_prefixed_bytes(&name, endian)?;
+    } else {
+        writer.write_tag(Tag::Compound)?;
+    }
+
+    let mut stack = vec![Layer::compound(root, opts.sort_keys)];
+
+    while let Some(layer) = stack.last_mut() {
+        let value = match layer {
+            Layer::Compound(entries) => match entries.next() {
+                Some((name, value)) => {
+                    let name = cesu8::to_java_cesu8(name);
+                    check_name(&name, endian)?;
+                    writer.write_tag(value.tag())?;
+                    writer.write_size_prefixed_bytes(&name, endian)?;
+                    value
+                }
+                None => {
+                    writer.write_tag(Tag::End)?;
+                    stack.pop();
+                    continue;
+                }
+            },
+            Layer::List {
+                elements,
+                tag,
+                index,
+            } => match elements.next() {
+                Some(value) => {
+                    if value.tag() != *tag {
+                        return Err(Error::heterogeneous_list(*index, *tag, value.tag()));
+                    }
+                    *index += 1;
+                    value
+                }
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            },
+        };
+
+        match value {
+            Value::Compound(v) => {
+                if stack.len() == max_depth {
+                    return Err(Error::too_deep(max_depth));
+                }
+                stack.push(Layer::compound(v, opts.sort_keys));
+            }
+            Value::List(v) => {
+                if stack.len() == max_depth {
+                    return Err(Error::too_deep(max_depth));
+                }
+                let tag = v.first().map_or(Tag::End, Value::tag);
+                writer.write_tag(tag)?;
+                writer.write_len(v.len(), endian)?;
+                stack.push(Layer::List {
+                    elements: v.iter(),
+                    tag,
+                    index: 0,
+                });
+            }
+            _ => write_payload(&mut writer, value, endian)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// A compound or list being written, and what is left of it.
+enum Layer<'a> {
+    Compound(Entries<'a>),
+    List {
+        elements: std::slice::Iter<'a, Value>,
+        tag: Tag,
+        index: usize,
+    },
+}
+
+impl<'a> Layer<'a> {
+    fn compound(compound: &'a hash_map::HashMap<String, Value>, sort_keys: bool) -> Self {
+        if !sort_keys {
+            return Layer::Compound(Entries::Unsorted(compound.iter()));
+        }
+
+        // Sorted by their encoded bytes, as the serde serializer does.
+        let mut entries: Vec<_> = compound.iter().collect();
+        entries.sort_by_cached_key(|(k, _)| cesu8::to_java_cesu8(k).into_owned());
+        Layer::Compound(Entries::Sorted(entries.into_iter()))
+    }
+}
+
+enum Entries<'a> {
+    Unsorted(hash_map::Iter<'a, String, Value>),
+    Sorted(std::vec::IntoIter<(&'a String, &'a Value)>),
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = (&'a String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Entries::Unsorted(it) => it.next(),
+            Entries::Sorted(it) => it.next(),
+        }
+    }
+}
+
+/// Write the payload of a value that is not a compound or list.
+fn write_payload<W: Write>(writer: &mut W, value: &Value, endian: Endian) -> Result<()> {
+    match value {
+        Value::Byte(v) => writer.write_all(&[*v as u8])?,
+        Value::Short(v) => writer.write_nbt_short(*v, endian)?,
+        Value::Int(v) => writer.write_nbt_int(*v, endian)?,
+        Value::Long(v) => writer.write_nbt_long(*v, endian)?,
+        Value::Float(v) => writer.write_nbt_float(*v, endian)?,
+        Value::Double(v) => writer.write_nbt_double(*v, endian)?,
+        Value::String(v) => writer.write_size_prefixed_str(v, endian)?,
+        Value::ByteArray(v) => {
+            writer.write_len(v.len(), endian)?;
+            writer.write_all(v.as_bytes())?;
+        }
+        Value::IntArray(v) => {
+            writer.write_len(v.len(), endian)?;
+            for el in v.iter() {
+                writer.write_nbt_int(*el, endian)?;
+            }
+        }
+        Value::LongArray(v) => {
+            writer.write_len(v.len(), endian)?;
+            for el in v.iter() {
+                writer.write_nbt_long(*el, endian)?;
+            }
+        }
+        Value::List(_) | Value::Compound(_) => unreachable!("containers are written by the caller"),
+    }
+    Ok(())
+}
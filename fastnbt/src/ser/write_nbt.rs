@@ -1,10 +1,25 @@
 use std::convert::TryInto;
 use std::io::Write;
 
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
 
 use crate::error::{Error, Result};
-use crate::Tag;
+use crate::{Endian, Tag};
+
+// Write methods for NBT number types that are fixed size in every byte order.
+macro_rules! write_endian {
+    ($($name:ident($t:ty) => $method:ident),* $(,)?) => {
+        $(
+            fn $name(&mut self, v: $t, endian: Endian) -> Result<()> {
+                match endian {
+                    Endian::Big => self.$method::<BigEndian>(v)?,
+                    Endian::Little | Endian::NetworkLittle => self.$method::<LittleEndian>(v)?,
+                }
+                Ok(())
+            }
+        )*
+    };
+}
 
 pub(crate) trait WriteNbt: Write {
     fn write_tag(&mut self, tag: Tag) -> Result<()> {
@@ -12,22 +27,88 @@ pub(crate) trait WriteNbt: Write {
         Ok(())
     }
 
-    fn write_size_prefixed_str(&mut self, key: &str) -> Result<()> {
-        let key = cesu8::to_java_cesu8(key);
-        let len_bytes = key.len() as u16;
-        self.write_u16::<BigEndian>(len_bytes)?;
-        self.write_all(&key)?;
+    write_endian! {
+        write_nbt_short(i16) => write_i16,
+        write_nbt_float(f32) => write_f32,
+        write_nbt_double(f64) => write_f64,
+    }
+
+    fn write_nbt_int(&mut self, v: i32, endian: Endian) -> Result<()> {
+        match endian {
+            Endian::Big => self.write_i32::<BigEndian>(v)?,
+            Endian::Little => self.write_i32::<LittleEndian>(v)?,
+            Endian::NetworkLittle => self.write_varint(((v << 1) ^ (v >> 31)) as u32 as u64)?,
+        }
         Ok(())
     }
 
-    fn write_len(&mut self, len: usize) -> Result<()> {
-        self.write_u32::<BigEndian>(
-            len.try_into()
-                .map_err(|_| Error::bespoke("len too large".to_owned()))?,
-        )?;
+    fn write_nbt_long(&mut self, v: i64, endian: Endian) -> Result<()> {
+        match endian {
+            Endian::Big => self.write_i64::<BigEndian>(v)?,
+            Endian::Little => self.write_i64::<LittleEndian>(v)?,
+            Endian::NetworkLittle => self.write_varint(((v << 1) ^ (v >> 63)) as u64)?,
+        }
+        Ok(())
+    }
 
+    /// Write an unsigned LEB128 varint.
+    fn write_varint(&mut self, mut v: u64) -> Result<()> {
+        while v >= 0x80 {
+            self.write_u8(v as u8 | 0x80)?;
+            v >>= 7;
+        }
+        self.write_u8(v as u8)?;
         Ok(())
     }
+
+    fn write_size_prefixed_str(&mut self, key: &str, endian: Endian) -> Result<()> {
+        let key = cesu8::to_java_cesu8(key);
+        self.write_size_prefixed_bytes(&key, endian)
+    }
+
+    /// Write already encoded string data with its length. Other than in network
+    /// NBT the length is a u16, so longer strings are an error.
+    fn write_size_prefixed_bytes(&mut self, key: &[u8], endian: Endian) -> Result<()> {
+        match endian {
+            Endian::NetworkLittle => self.write_varint(key.len() as u64)?,
+            _ => {
+                let len: u16 = key.len().try_into().map_err(|_| too_long("string", key))?;
+                self.write_nbt_short(len as i16, endian)?
+            }
+        }
+        self.write_all(key)?;
+        Ok(())
+    }
+
+    fn write_len(&mut self, len: usize, endian: Endian) -> Result<()> {
+        let len: u32 = len
+            .try_into()
+            .map_err(|_| Error::bespoke("len too large".to_owned()))?;
+
+        self.write_nbt_int(len as i32, endian)
+    }
 }
 
 impl<T> WriteNbt for T where T: Write {}
+
+/// Check that an encoded compound key or root name fits in NBT, before
+/// anything of its entry is written. Every `str` can be encoded, so only the
+/// length can be wrong.
+pub(crate) fn check_name(name: &[u8], endian: Endian) -> Result<()> {
+    if endian != Endian::NetworkLittle && name.len() > u16::MAX as usize {
+        return Err(too_long("name", name));
+    }
+    Ok(())
+}
+
+/// The error for string data too long to write, showing the start of it.
+fn too_long(what: &str, data: &[u8]) -> Error {
+    let start = String::from_utf8_lossy(&data[..32]);
+    Error::bespoke(format!(
+        "{} of {} bytes is longer than the NBT limit of {} bytes: {:?}...",
+        what,
+        data.len(),
+        u16::MAX,
+        start
+    ))
+}
@@ -0,0 +1,230 @@
+//! Writing SNBT, shared by [`to_pretty_snbt`][`crate::to_pretty_snbt`] and the
+//! [`Display`][`std::fmt::Display`] of [`Diff`][`crate::diff::Diff`], so that
+//! both write the same SNBT, which parses back into the values written.
+
+use std::fmt::{self, Debug, Write};
+
+use crate::{stream::ValueRef, Tag, Value};
+
+/// Arrays with more elements than this are wrapped over several lines by
+/// pretty SNBT, with this many elements on each line.
+const PRETTY_ARRAY_WIDTH: usize = 16;
+
+/// Writes values as SNBT, given in the order a [`Parser`][`crate::stream::Parser`]
+/// gives them.
+pub(crate) struct SnbtWriter<W> {
+    writer: W,
+    // Spaces to indent each level by, or None to write everything on one line.
+    indent: Option<usize>,
+    // The number of values written so far in each open compound and list.
+    written: Vec<usize>,
+}
+
+impl<W: Write> SnbtWriter<W> {
+    /// A writer that puts each value on its own line, indenting each level by
+    /// `indent` spaces.
+    pub(crate) fn pretty(writer: W, indent: usize) -> Self {
+        Self {
+            writer,
+            indent: Some(indent),
+            written: vec![],
+        }
+    }
+
+    /// A writer that puts everything on one line, eg `{"a": [1b, 2b]}`.
+    pub(crate) fn compact(writer: W) -> Self {
+        Self {
+            writer,
+            indent: None,
+            written: vec![],
+        }
+    }
+
+    /// Whether every compound and list started has been ended.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.written.is_empty()
+    }
+
+    /// The writer being written to.
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Write the next value.
+    pub(crate) fn write(&mut self, value: ValueRef) -> fmt::Result {
+        if let ValueRef::CompoundEnd | ValueRef::ListEnd = value {
+            let count = self.written.pop().unwrap_or(0);
+            if count > 0 {
+                self.newline(self.written.len())?;
+            }
+            return self.writer.write_char(match value {
+                ValueRef::CompoundEnd => '}',
+                _ => ']',
+            });
+        }
+
+        if let Some(count) = self.written.last_mut() {
+            let first = *count == 0;
+            *count += 1;
+            if !first {
+                self.writer.write_char(',')?;
+                if self.indent.is_none() {
+                    self.writer.write_char(' ')?;
+                }
+            }
+            self.newline(self.written.len())?;
+
+            if let Some(name) = value.name() {
+                write_str(&mut self.writer, name)?;
+                self.writer.write_str(": ")?;
+            }
+        }
+
+        let depth = self.written.len();
+        match value {
+            ValueRef::Byte(_, v) => write!(self.writer, "{}b", v),
+            ValueRef::Short(_, v) => write!(self.writer, "{}s", v),
+            ValueRef::Int(_, v) => write!(self.writer, "{}", v),
+            ValueRef::Long(_, v) => write!(self.writer, "{}L", v),
+            ValueRef::Float(_, v) => write!(self.writer, "{:?}f", v),
+            ValueRef::Double(_, v) => write!(self.writer, "{:?}d", v),
+            ValueRef::String(_, v) => write_str(&mut self.writer, v),
+            ValueRef::ByteArray(_, v) => self.write_array('B', "b", v, depth),
+            ValueRef::IntArray(_, v) => self.write_array('I', "", v, depth),
+            ValueRef::LongArray(_, v) => self.write_array('L', "L", v, depth),
+            ValueRef::List(..) => {
+                self.written.push(0);
+                self.writer.write_char('[')
+            }
+            ValueRef::Compound(_) => {
+                self.written.push(0);
+                self.writer.write_char('{')
+            }
+            ValueRef::CompoundEnd | ValueRef::ListEnd => unreachable!(),
+        }
+    }
+
+    /// Write a whole value. The keys of compounds are written in order, so
+    /// the same value is always written the same way.
+    pub(crate) fn write_value(&mut self, value: &Value) -> fmt::Result {
+        self.write_named(None, value)
+    }
+
+    fn write_named(&mut self, name: Option<&str>, value: &Value) -> fmt::Result {
+        match value {
+            Value::Byte(v) => self.write(ValueRef::Byte(name, *v)),
+            Value::Short(v) => self.write(ValueRef::Short(name, *v)),
+            Value::Int(v) => self.write(ValueRef::Int(name, *v)),
+            Value::Long(v) => self.write(ValueRef::Long(name, *v)),
+            Value::Float(v) => self.write(ValueRef::Float(name, *v)),
+            Value::Double(v) => self.write(ValueRef::Double(name, *v)),
+            Value::String(v) => self.write(ValueRef::String(name, v)),
+            Value::ByteArray(v) => self.write(ValueRef::ByteArray(name, v)),
+            Value::IntArray(v) => self.write(ValueRef::IntArray(name, v)),
+            Value::LongArray(v) => self.write(ValueRef::LongArray(name, v)),
+            Value::List(v) => {
+                let tag = v.first().map_or(Tag::End, Value::tag);
+                self.write(ValueRef::List(name, tag, v.len() as i32))?;
+                for el in v {
+                    self.write_named(None, el)?;
+                }
+                self.write(ValueRef::ListEnd)
+            }
+            Value::Compound(v) => {
+                let mut entries: Vec<_> = v.iter().collect();
+                entries.sort_by_key(|(k, _)| *k);
+
+                self.write(ValueRef::Compound(name))?;
+                for (k, el) in entries {
+                    self.write_named(Some(k), el)?;
+                }
+                self.write(ValueRef::CompoundEnd)
+            }
+        }
+    }
+
+    // Start a new line at `depth`, if writing pretty SNBT.
+    fn newline(&mut self, depth: usize) -> fmt::Result {
+        match self.indent {
+            Some(indent) => write!(self.writer, "\n{:width$}", "", width = depth * indent),
+            None => Ok(()),
+        }
+    }
+
+    fn write_array<T: Debug>(
+        &mut self,
+        prefix: char,
+        suffix: &str,
+        v: &[T],
+        depth: usize,
+    ) -> fmt::Result {
+        write!(self.writer, "[{};", prefix)?;
+        if self.indent.is_none() || v.len() <= PRETTY_ARRAY_WIDTH {
+            for (i, el) in v.iter().enumerate() {
+                self.writer.write_str(if i == 0 { " " } else { ", " })?;
+                write!(self.writer, "{:?}{}", el, suffix)?;
+            }
+            return self.writer.write_char(']');
+        }
+
+        for (i, line) in v.chunks(PRETTY_ARRAY_WIDTH).enumerate() {
+            if i > 0 {
+                self.writer.write_char(',')?;
+            }
+            self.newline(depth + 1)?;
+            for (j, el) in line.iter().enumerate() {
+                if j > 0 {
+                    self.writer.write_str(", ")?;
+                }
+                write!(self.writer, "{:?}{}", el, suffix)?;
+            }
+        }
+        self.newline(depth)?;
+        self.writer.write_char(']')
+    }
+}
+
+/// Write a string or key quoted, escaping quotes and backslashes.
+fn write_str<W: Write>(writer: &mut W, v: &str) -> fmt::Result {
+    writer.write_char('"')?;
+    for part in v.split_inclusive(['"', '\\']) {
+        match part.strip_suffix(['"', '\\']) {
+            Some(rest) => {
+                writer.write_str(rest)?;
+                writer.write_char('\\')?;
+                writer.write_str(&part[rest.len()..])?;
+            }
+            None => writer.write_str(part)?,
+        }
+    }
+    writer.write_char('"')
+}
+
+/// Lets the [`SnbtWriter`] write to an [`io::Write`][`std::io::Write`],
+/// keeping the error that stopped it.
+pub(crate) struct IoWriter<W> {
+    inner: W,
+    error: Option<std::io::Error>,
+}
+
+impl<W: std::io::Write> IoWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+
+    /// The error to give for a [`fmt::Error`] from writing.
+    pub(crate) fn take_error(&mut self) -> std::io::Error {
+        self.error
+            .take()
+            .unwrap_or_else(|| std::io::Error::other("formatter error"))
+    }
+}
+
+impl<W: std::io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
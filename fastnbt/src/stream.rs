@@ -1,8 +1,16 @@
 //! Allows streaming of NBT data without prior knowledge of the structure.
 
 use super::Tag;
+use crate::snbt::{IoWriter, SnbtWriter};
 use byteorder::{BigEndian, ReadBytesExt};
-use std::{convert::TryFrom, io::Read, str};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::Debug,
+    io::{Read, Write},
+    str,
+    sync::Arc,
+};
 
 /// An optional `String`.
 pub type Name = Option<String>;
@@ -34,10 +42,105 @@ pub enum Value {
     LongArray(Name, Vec<i64>),
 }
 
+impl Value {
+    /// The name of this value, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Value::Byte(name, _)
+            | Value::Short(name, _)
+            | Value::Int(name, _)
+            | Value::Long(name, _)
+            | Value::Float(name, _)
+            | Value::Double(name, _)
+            | Value::ByteArray(name, _)
+            | Value::String(name, _)
+            | Value::List(name, _, _)
+            | Value::Compound(name)
+            | Value::IntArray(name, _)
+            | Value::LongArray(name, _) => name.as_deref(),
+            Value::CompoundEnd | Value::ListEnd => None,
+        }
+    }
+}
+
+/// A shallow NBT value that borrows its names, strings and arrays from the
+/// [`Parser`], returned by [`Parser::next_ref`].
+///
+/// This mirrors [`Value`], but avoids allocating for every value. The data is
+/// only valid until the next call to the parser, so must be copied to keep it,
+/// eg with [`into_value`][`ValueRef::into_value`].
+///
+/// Names and strings are stored as Java's modified UTF-8, which is decoded to
+/// `&str`. Most strings are already valid UTF-8 and are decoded without
+/// allocating. Only strings containing a null character or a character
+/// outside the Basic Multilingual Plane, such as most emoji, are encoded
+/// differently and allocate while being decoded.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ValueRef<'a> {
+    CompoundEnd,
+    Byte(Option<&'a str>, i8),
+    Short(Option<&'a str>, i16),
+    Int(Option<&'a str>, i32),
+    Long(Option<&'a str>, i64),
+    Float(Option<&'a str>, f32),
+    Double(Option<&'a str>, f64),
+    ByteArray(Option<&'a str>, &'a [i8]),
+    String(Option<&'a str>, &'a str),
+    List(Option<&'a str>, Tag, i32),
+    ListEnd,
+    Compound(Option<&'a str>),
+    IntArray(Option<&'a str>, &'a [i32]),
+    LongArray(Option<&'a str>, &'a [i64]),
+}
+
+impl<'a> ValueRef<'a> {
+    /// The name of this value, if it has one.
+    pub fn name(&self) -> Option<&'a str> {
+        match *self {
+            ValueRef::Byte(name, _)
+            | ValueRef::Short(name, _)
+            | ValueRef::Int(name, _)
+            | ValueRef::Long(name, _)
+            | ValueRef::Float(name, _)
+            | ValueRef::Double(name, _)
+            | ValueRef::ByteArray(name, _)
+            | ValueRef::String(name, _)
+            | ValueRef::List(name, _, _)
+            | ValueRef::Compound(name)
+            | ValueRef::IntArray(name, _)
+            | ValueRef::LongArray(name, _) => name,
+            ValueRef::CompoundEnd | ValueRef::ListEnd => None,
+        }
+    }
+
+    /// Copy this into an owned [`Value`]. This allocates.
+    pub fn into_value(self) -> Value {
+        let name = self.name().map(str::to_owned);
+        match self {
+            ValueRef::CompoundEnd => Value::CompoundEnd,
+            ValueRef::Byte(_, v) => Value::Byte(name, v),
+            ValueRef::Short(_, v) => Value::Short(name, v),
+            ValueRef::Int(_, v) => Value::Int(name, v),
+            ValueRef::Long(_, v) => Value::Long(name, v),
+            ValueRef::Float(_, v) => Value::Float(name, v),
+            ValueRef::Double(_, v) => Value::Double(name, v),
+            ValueRef::ByteArray(_, v) => Value::ByteArray(name, v.to_vec()),
+            ValueRef::String(_, v) => Value::String(name, v.to_owned()),
+            ValueRef::List(_, tag, len) => Value::List(name, tag, len),
+            ValueRef::ListEnd => Value::ListEnd,
+            ValueRef::Compound(_) => Value::Compound(name),
+            ValueRef::IntArray(_, v) => Value::IntArray(name, v.to_vec()),
+            ValueRef::LongArray(_, v) => Value::LongArray(name, v.to_vec()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Error {
     msg: String,
     kind: ErrorKind,
+    // Shared so that the error can still be cloned.
+    source: Option<Arc<std::io::Error>>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,6 +179,7 @@ impl Error {
         Self {
             msg: msg.into(),
             kind: ErrorKind::Other,
+            source: None,
         }
     }
 
@@ -83,6 +187,7 @@ impl Error {
         Self {
             msg: format!("invalid tag: {}", t),
             kind: ErrorKind::InvalidTag,
+            source: None,
         }
     }
 
@@ -93,6 +198,7 @@ impl Error {
                 String::from_utf8_lossy(&d),
             ),
             kind: ErrorKind::Nonunicode(d),
+            source: None,
         }
     }
 
@@ -100,11 +206,18 @@ impl Error {
         Self {
             msg: "EOF".into(),
             kind: ErrorKind::Eof,
+            source: None,
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
@@ -114,16 +227,14 @@ impl std::fmt::Display for Error {
 
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
-        match e.kind() {
-            std::io::ErrorKind::UnexpectedEof => Self {
-                msg: e.to_string(),
-                kind: ErrorKind::UnexpectedEof,
-            },
-            // Probably want to include the IO error in future.
-            _ => Self {
-                msg: e.to_string(),
-                kind: ErrorKind::Other,
-            },
+        let kind = match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => ErrorKind::UnexpectedEof,
+            _ => ErrorKind::Other,
+        };
+        Self {
+            msg: e.to_string(),
+            kind,
+            source: Some(Arc::new(e)),
         }
     }
 }
@@ -208,9 +319,79 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// }
 /// # }
 /// ```
+///
+/// ## Scanning without allocating
+/// [`next`][`Parser::next`] allocates for every name, string and array.
+/// [`next_ref`][`Parser::next_ref`] instead returns a [`ValueRef`] borrowing
+/// them from buffers inside the parser, which are reused for every value. Here
+/// we count the block entities in a chunk by name, without keeping the names.
+///
+/// ```
+/// use fastnbt::stream::{Parser, ValueRef};
+/// # use fastnbt::nbt;
+/// # let data = fastnbt::to_bytes(&nbt!({"block_entities": [{"id": "chest"}]})).unwrap();
+///
+/// let mut parser = Parser::new(data.as_slice());
+/// let mut chests = 0;
+/// while let Ok(value) = parser.next_ref() {
+///     if let ValueRef::String(Some("id"), "chest") = value {
+///         chests += 1;
+///     }
+/// }
+/// assert_eq!(chests, 1);
+/// ```
 pub struct Parser<R: Read> {
     reader: R,
     layers: Vec<Layer>,
+    track_path: bool,
+    path: Vec<PathElem>,
+    // Whether the last element of the path belongs to the previous value, and
+    // should be removed before parsing the next.
+    pop_path: bool,
+    // Buffers for the data of the last value, reused to avoid allocating.
+    named: bool,
+    name: String,
+    string: String,
+    raw: Vec<u8>,
+    ints: Vec<i32>,
+    longs: Vec<i64>,
+}
+
+// The last value parsed, with its name and data left in the parser's buffers.
+#[derive(Clone, Copy)]
+enum Event {
+    CompoundEnd,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray,
+    String,
+    List(Tag, i32),
+    ListEnd,
+    Compound,
+    IntArray,
+    LongArray,
+}
+
+/// An element of the path to a value, see [`Parser::current_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathElem {
+    /// The name of a value in a compound.
+    Key(String),
+    /// The index of a value in a list.
+    Index(usize),
+}
+
+impl std::fmt::Display for PathElem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathElem::Key(key) => f.write_str(key),
+            PathElem::Index(i) => write!(f, "{}", i),
+        }
+    }
 }
 
 impl<R: Read> Parser<R> {
@@ -219,13 +400,123 @@ impl<R: Read> Parser<R> {
         Self {
             reader,
             layers: Vec::new(),
+            track_path: false,
+            path: Vec::new(),
+            pop_path: false,
+            named: false,
+            name: String::new(),
+            string: String::new(),
+            raw: Vec::new(),
+            ints: Vec::new(),
+            longs: Vec::new(),
         }
     }
 
+    /// Set whether to track the path to the current value, for
+    /// [`current_path`][`Parser::current_path`]. This is off by default to
+    /// avoid the overhead.
+    pub fn with_path_tracking(mut self, track: bool) -> Self {
+        self.track_path = track;
+        self
+    }
+
+    /// The path to the value last returned by [`next`][`Parser::next`], made of
+    /// the compound keys and list indices leading to it. The root compound is
+    /// not part of the path. For a `CompoundEnd` or `ListEnd` this is the path
+    /// of the compound or list that ended.
+    ///
+    /// This is always empty unless path tracking is turned on with
+    /// [`with_path_tracking`][`Parser::with_path_tracking`].
+    ///
+    /// ```
+    /// use fastnbt::stream::{Parser, PathElem, Value};
+    /// # use fastnbt::nbt;
+    /// # let data = fastnbt::to_bytes(&nbt!({"block_entities": [{"id": "chest"}]})).unwrap();
+    ///
+    /// let mut parser = Parser::new(data.as_slice()).with_path_tracking(true);
+    /// while let Ok(_value) = parser.next() {
+    ///     if let [PathElem::Key(key), ..] = parser.current_path() {
+    ///         if key == "block_entities" {
+    ///             // only values inside block_entities end up here.
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn current_path(&self) -> &[PathElem] {
+        &self.path
+    }
+
     /// Parse the next value from the input.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Value> {
-        self.next_inner()
+        self.next_ref().map(ValueRef::into_value)
+    }
+
+    /// Parse the next value from the input, borrowing its name and data rather
+    /// than allocating them. See [`ValueRef`].
+    ///
+    /// The returned value borrows the parser, and is only valid until the next
+    /// call to it. The parser reuses the same buffers for every value, so once
+    /// they have grown to fit the largest names and arrays this does not
+    /// allocate.
+    pub fn next_ref(&mut self) -> Result<ValueRef<'_>> {
+        let event = self.next_tracked()?;
+        let name = self.named.then_some(self.name.as_str());
+
+        Ok(match event {
+            Event::CompoundEnd => ValueRef::CompoundEnd,
+            Event::Byte(v) => ValueRef::Byte(name, v),
+            Event::Short(v) => ValueRef::Short(name, v),
+            Event::Int(v) => ValueRef::Int(name, v),
+            Event::Long(v) => ValueRef::Long(name, v),
+            Event::Float(v) => ValueRef::Float(name, v),
+            Event::Double(v) => ValueRef::Double(name, v),
+            Event::ByteArray => ValueRef::ByteArray(name, slice_u8_as_i8(&self.raw)),
+            Event::String => ValueRef::String(name, &self.string),
+            Event::List(tag, len) => ValueRef::List(name, tag, len),
+            Event::ListEnd => ValueRef::ListEnd,
+            Event::Compound => ValueRef::Compound(name),
+            Event::IntArray => ValueRef::IntArray(name, &self.ints),
+            Event::LongArray => ValueRef::LongArray(name, &self.longs),
+        })
+    }
+
+    fn next_tracked(&mut self) -> Result<Event> {
+        if !self.track_path {
+            return self.next_inner();
+        }
+
+        if self.pop_path {
+            self.path.pop();
+        }
+
+        let depth = self.layers.len();
+        let list_index = match self.layers.last() {
+            Some(Layer::List(_, remaining, len)) if *remaining > 0 => {
+                Some((len - remaining) as usize)
+            }
+            _ => None,
+        };
+
+        let event = self.next_inner()?;
+
+        match event {
+            // Elements of the root do not have a path element.
+            Event::CompoundEnd | Event::ListEnd => self.pop_path = !self.layers.is_empty(),
+            _ if depth == 0 => self.pop_path = false,
+            _ => {
+                let elem = match list_index {
+                    Some(i) => PathElem::Index(i),
+                    None => PathElem::Key(self.name.clone()),
+                };
+                self.path.push(elem);
+
+                // Stay in compounds and lists until their end.
+                self.pop_path = !matches!(event, Event::Compound | Event::List(..));
+            }
+        }
+
+        Ok(event)
     }
 
     /// Gets a reference to the underlying value in this parser.
@@ -245,12 +536,14 @@ impl<R: Read> Parser<R> {
 
     /// Get the next value from the reader. Returns EOF if the stream ended sucessfully, and
     /// IO(err) for any other IO error.
-    fn next_inner(&mut self) -> Result<Value> {
+    fn next_inner(&mut self) -> Result<Event> {
+        self.named = false;
+
         let last_layer = self.layers.last().map(|l| (*l).clone());
         match last_layer {
-            Some(Layer::List(_, 0)) => {
+            Some(Layer::List(_, 0, _)) => {
                 self.layers.pop();
-                return Ok(Value::ListEnd);
+                return Ok(Event::ListEnd);
             }
             Some(_) => {}
             None => {}
@@ -258,7 +551,7 @@ impl<R: Read> Parser<R> {
 
         if let Some(layer) = self.layers.last_mut() {
             match layer {
-                Layer::List(_, remainder) => {
+                Layer::List(_, remainder, _) => {
                     *remainder -= 1;
                 }
                 Layer::Compound => {}
@@ -268,7 +561,7 @@ impl<R: Read> Parser<R> {
         let last_layer = self.layers.last().map(|l| (*l).clone());
         if let Some(layer) = last_layer {
             match layer {
-                Layer::List(tag, _) => return self.read_payload(tag, None),
+                Layer::List(tag, _, _) => return self.read_payload(tag),
                 Layer::Compound => {}
             };
         }
@@ -277,7 +570,12 @@ impl<R: Read> Parser<R> {
         // natural end of stream.
         let tag = match self.reader.read_u8() {
             Ok(t) => t,
-            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Err(Error::eof()),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Err(Error {
+                    source: Some(Arc::new(e)),
+                    ..Error::eof()
+                })
+            }
             e => e?,
         };
 
@@ -289,78 +587,103 @@ impl<R: Read> Parser<R> {
             return match last_layer {
                 Some(Layer::Compound) => {
                     self.layers.pop();
-                    Ok(Value::CompoundEnd)
+                    Ok(Event::CompoundEnd)
                 }
                 Some(_) => Err(Error::bespoke("expected to be in compound")),
                 None => Err(Error::bespoke("expected to be in compound")),
             };
         }
 
-        let name = Some(self.read_size_prefixed_string()?);
+        read_size_prefixed_string(&mut self.reader, &mut self.raw, &mut self.name)?;
+        self.named = true;
 
-        self.read_payload(tag, name)
+        self.read_payload(tag)
     }
 
-    fn read_size_prefixed_string(&mut self) -> Result<String> {
-        let name_len = self.reader.read_u16::<BigEndian>()? as usize;
-
-        let mut buf = vec![0; name_len];
-        self.reader.read_exact(&mut buf[..])?;
-
-        Ok(cesu8::from_java_cesu8(&buf[..])
-            .map_err(|_| Error::nonunicode(Vec::from(&buf[..])))?
-            .into_owned())
-    }
-
-    fn read_payload(&mut self, tag: Tag, name: Name) -> Result<Value> {
+    fn read_payload(&mut self, tag: Tag) -> Result<Event> {
         match tag {
-            Tag::Byte => Ok(Value::Byte(name, self.reader.read_i8()?)),
-            Tag::Short => Ok(Value::Short(name, self.reader.read_i16::<BigEndian>()?)),
-            Tag::Int => Ok(Value::Int(name, self.reader.read_i32::<BigEndian>()?)),
-            Tag::Long => Ok(Value::Long(name, self.reader.read_i64::<BigEndian>()?)),
-            Tag::Float => Ok(Value::Float(name, self.reader.read_f32::<BigEndian>()?)),
-            Tag::Double => Ok(Value::Double(name, self.reader.read_f64::<BigEndian>()?)),
+            Tag::Byte => Ok(Event::Byte(self.reader.read_i8()?)),
+            Tag::Short => Ok(Event::Short(self.reader.read_i16::<BigEndian>()?)),
+            Tag::Int => Ok(Event::Int(self.reader.read_i32::<BigEndian>()?)),
+            Tag::Long => Ok(Event::Long(self.reader.read_i64::<BigEndian>()?)),
+            Tag::Float => Ok(Event::Float(self.reader.read_f32::<BigEndian>()?)),
+            Tag::Double => Ok(Event::Double(self.reader.read_f64::<BigEndian>()?)),
             Tag::Compound => {
                 self.layers.push(Layer::Compound);
-                Ok(Value::Compound(name))
+                Ok(Event::Compound)
             }
-            Tag::End => panic!("end tag should have returned early"),
+            // Named end tags return early, so this is an element of a list of
+            // end, which has nothing to read.
+            Tag::End => Err(Error::bespoke(
+                "unexpected list of type 'end', which is not supported",
+            )),
             Tag::List => {
                 let element_tag = self.reader.read_u8()?;
                 let element_tag = u8_to_tag(element_tag)?;
-                let size = self.reader.read_i32::<BigEndian>()?;
-                self.layers.push(Layer::List(element_tag, size));
-                Ok(Value::List(name, element_tag, size))
+                let size = read_len(&mut self.reader)? as i32;
+                self.layers.push(Layer::List(element_tag, size, size));
+                Ok(Event::List(element_tag, size))
+            }
+            Tag::String => {
+                read_size_prefixed_string(&mut self.reader, &mut self.raw, &mut self.string)?;
+                Ok(Event::String)
             }
-            Tag::String => Ok(Value::String(name, self.read_size_prefixed_string()?)),
             Tag::ByteArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0u8; size as usize];
-                self.reader.read_exact(&mut buf[..])?;
-                Ok(Value::ByteArray(name, vec_u8_into_i8(buf)))
+                let size = read_len(&mut self.reader)?;
+                // Grow as the data arrives rather than trusting the size.
+                self.raw.clear();
+                let read = (&mut self.reader)
+                    .take(size as u64)
+                    .read_to_end(&mut self.raw)?;
+                if read < size {
+                    return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+                }
+                Ok(Event::ByteArray)
             }
             Tag::IntArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0i32; size as usize];
-                for i in 0..size {
-                    buf[i as usize] = self.reader.read_i32::<BigEndian>()?;
+                let size = read_len(&mut self.reader)?;
+                self.ints.clear();
+                for _ in 0..size {
+                    self.ints.push(self.reader.read_i32::<BigEndian>()?);
                 }
-
-                Ok(Value::IntArray(name, buf))
+                Ok(Event::IntArray)
             }
             Tag::LongArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0i64; size as usize];
-                for i in 0..size {
-                    buf[i as usize] = self.reader.read_i64::<BigEndian>()?;
+                let size = read_len(&mut self.reader)?;
+                self.longs.clear();
+                for _ in 0..size {
+                    self.longs.push(self.reader.read_i64::<BigEndian>()?);
                 }
-
-                Ok(Value::LongArray(name, buf))
+                Ok(Event::LongArray)
             }
         }
     }
 }
 
+// Read the length of an array or list, which cannot be negative.
+fn read_len(reader: &mut impl Read) -> Result<usize> {
+    let len = reader.read_i32::<BigEndian>()?;
+    usize::try_from(len).map_err(|_| Error::bespoke(format!("negative length: {}", len)))
+}
+
+// Read a string into `out`, using `raw` for the undecoded data.
+fn read_size_prefixed_string(
+    reader: &mut impl Read,
+    raw: &mut Vec<u8>,
+    out: &mut String,
+) -> Result<()> {
+    let len = reader.read_u16::<BigEndian>()? as usize;
+
+    raw.clear();
+    raw.resize(len, 0);
+    reader.read_exact(&mut raw[..])?;
+
+    let decoded = cesu8::from_java_cesu8(raw).map_err(|_| Error::nonunicode(raw.clone()))?;
+    out.clear();
+    out.push_str(&decoded);
+    Ok(())
+}
+
 /// Parse the input until the compound we are currently inside is complete.
 /// Handles inner compounds by skipping those as well.
 pub fn skip_compound<R: Read>(parser: &mut Parser<R>) -> Result<()> {
@@ -401,22 +724,124 @@ pub fn find_list<R: Read>(parser: &mut Parser<R>, name: Option<&str>) -> Result<
     }
 }
 
-// Thanks to https://stackoverflow.com/a/59707887
-fn vec_u8_into_i8(v: Vec<u8>) -> Vec<i8> {
-    // ideally we'd use Vec::into_raw_parts, but it's unstable,
-    // so we have to do it manually:
+/// Count how many of each tag are in the NBT read from `reader`, without
+/// building any values. Every value is counted, including elements of lists
+/// and the root compound, but not the end of compounds. Reading continues
+/// until the end of the input, so this also counts over several documents one
+/// after the other.
+///
+/// This only allocates the returned counts and the parser's reused buffers.
+///
+/// ```
+/// # use fastnbt::{nbt, stream::count_tags, Tag};
+/// let nbt = fastnbt::to_bytes(&nbt!({"Pos": [1.0, 2.0, 3.0], "id": "pig"})).unwrap();
+/// let counts = count_tags(nbt.as_slice()).unwrap();
+///
+/// assert_eq!(counts[&Tag::Double], 3);
+/// assert_eq!(counts[&Tag::Compound], 1);
+/// ```
+pub fn count_tags<R: Read>(reader: R) -> Result<HashMap<Tag, usize>> {
+    let mut parser = Parser::new(reader);
+    let mut counts = HashMap::new();
+
+    loop {
+        let tag = parser.next_ref().map(|value| match value {
+            ValueRef::CompoundEnd | ValueRef::ListEnd => None,
+            ValueRef::Byte(..) => Some(Tag::Byte),
+            ValueRef::Short(..) => Some(Tag::Short),
+            ValueRef::Int(..) => Some(Tag::Int),
+            ValueRef::Long(..) => Some(Tag::Long),
+            ValueRef::Float(..) => Some(Tag::Float),
+            ValueRef::Double(..) => Some(Tag::Double),
+            ValueRef::ByteArray(..) => Some(Tag::ByteArray),
+            ValueRef::String(..) => Some(Tag::String),
+            ValueRef::List(..) => Some(Tag::List),
+            ValueRef::Compound(..) => Some(Tag::Compound),
+            ValueRef::IntArray(..) => Some(Tag::IntArray),
+            ValueRef::LongArray(..) => Some(Tag::LongArray),
+        });
+
+        let tag = match tag {
+            Ok(Some(tag)) => tag,
+            Ok(None) => continue,
+            // Only the end of the input between documents is a clean end.
+            Err(e) if e.is_eof() && parser.layers.is_empty() => return Ok(counts),
+            Err(e) if e.is_eof() => {
+                return Err(Error {
+                    msg: "EOF part way through a value".into(),
+                    kind: ErrorKind::UnexpectedEof,
+                    source: e.source,
+                })
+            }
+            Err(e) => return Err(e),
+        };
+        *counts.entry(tag).or_insert(0) += 1;
+    }
+}
+
+/// Write the NBT read from `reader` to `writer` as indented SNBT, one value
+/// per line, indenting each level by `indent` spaces. This streams the data
+/// rather than building a [`Value`][`crate::Value`] first, so it can print
+/// files of any size, eg piping a file into a small program built on this:
+///
+/// ```sh
+/// cat level.nbt | nbt2snbt
+/// ```
+///
+/// Reading continues until the end of the input, printing each document
+/// after the other. Arrays of up to 16 elements stay on one line, longer
+/// arrays are wrapped with 16 elements per line. Keys and strings are always
+/// quoted. Compounds keep the order of the input, and the name of the root
+/// compound is not printed, as SNBT has no place for it. Values are written
+/// the same way as in [`Diff`][`crate::diff::Diff`] reports, and parse back
+/// into a [`Value`][`crate::Value`] with its `FromStr`, using the `snbt`
+/// feature.
+///
+/// ```
+/// # use fastnbt::nbt;
+/// let nbt = fastnbt::to_bytes(&nbt!({"Pos": [1.5, 64.0]})).unwrap();
+/// let mut snbt = vec![];
+/// fastnbt::to_pretty_snbt(nbt.as_slice(), &mut snbt, 2).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(snbt).unwrap(),
+///     "{\n  \"Pos\": [\n    1.5d,\n    64.0d\n  ]\n}\n"
+/// );
+/// ```
+pub fn to_pretty_snbt<R: Read, W: Write>(reader: R, writer: W, indent: usize) -> Result<()> {
+    let mut parser = Parser::new(reader);
+    let mut snbt = SnbtWriter::pretty(IoWriter::new(writer), indent);
 
-    // first, make sure v's destructor doesn't free the data
-    // it thinks it owns when it goes out of scope
-    let mut v = std::mem::ManuallyDrop::new(v);
+    loop {
+        let value = match parser.next_ref() {
+            Ok(value) => value,
+            Err(e) if e.is_eof() && snbt.is_complete() => return Ok(()),
+            Err(e) if e.is_eof() => {
+                return Err(Error {
+                    msg: "EOF part way through a value".into(),
+                    kind: ErrorKind::UnexpectedEof,
+                    source: e.source,
+                })
+            }
+            Err(e) => return Err(e),
+        };
 
-    // then, pick apart the existing Vec
-    let p = v.as_mut_ptr();
-    let len = v.len();
-    let cap = v.capacity();
+        let mut written = snbt.write(value);
+        if written.is_ok() && snbt.is_complete() {
+            // Each document ends its line, including ones that are not a
+            // compound, as some tools write.
+            written = std::fmt::Write::write_char(snbt.get_mut(), '\n');
+        }
+        if written.is_err() {
+            return Err(snbt.get_mut().take_error().into());
+        }
+    }
+}
 
-    // finally, adopt the data into a new Vec
-    unsafe { Vec::from_raw_parts(p as *mut i8, len, cap) }
+fn slice_u8_as_i8(v: &[u8]) -> &[i8] {
+    // u8 and i8 have the same size and alignment, and any bit pattern is valid
+    // for both.
+    unsafe { std::slice::from_raw_parts(v.as_ptr() as *const i8, v.len()) }
 }
 
 fn u8_to_tag(tag: u8) -> Result<Tag> {
@@ -425,6 +850,7 @@ fn u8_to_tag(tag: u8) -> Result<Tag> {
 
 #[derive(Clone)]
 enum Layer {
-    List(Tag, i32),
+    // Element tag, elements remaining, and the total length.
+    List(Tag, i32, i32),
     Compound,
 }
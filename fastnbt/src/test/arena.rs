@@ -0,0 +1,50 @@
+use bumpalo::Bump;
+
+use crate::{
+    arena::{from_bytes_in, from_bytes_in_with_opts, ArenaValue},
+    to_bytes, to_bytes_with_opts, DeOpts, Endian, SerOpts, Tag,
+};
+
+#[test]
+fn arena_value_matches_value() {
+    let value = nbt!({
+        "name": "test",
+        "nums": [1_i8, 2_i8],
+        "nested": {"pos": [1.5, 2.5]},
+        "bytes": [B; 1, -2],
+        "ints": [I; 3, 400000],
+        "longs": [L; -5, 1 << 40],
+        "empty": {},
+    });
+    let data = to_bytes(&value).unwrap();
+
+    let bump = Bump::new();
+    let arena = from_bytes_in(&data, &bump).unwrap();
+
+    assert_eq!(arena.tag(), Tag::Compound);
+    assert_eq!(arena.get("name"), Some(&ArenaValue::String("test")));
+    assert_eq!(arena.get("ints"), Some(&ArenaValue::IntArray(&[3, 400000])));
+    assert_eq!(arena.get("missing"), None);
+    assert_eq!(arena.get("name").unwrap().get("name"), None);
+    assert_eq!(arena.to_value(), value);
+}
+
+#[test]
+fn arena_value_little_endian() {
+    let value = nbt!({"ints": [I; 1, -2], "longs": [L; 3], "s": 7_i16});
+    let opts = SerOpts::new().endianness(Endian::Little);
+    let data = to_bytes_with_opts(&value, opts).unwrap();
+
+    let bump = Bump::new();
+    let opts = DeOpts::new().endianness(Endian::Little);
+    let arena = from_bytes_in_with_opts(&data, &bump, opts).unwrap();
+
+    assert_eq!(arena.to_value(), value);
+}
+
+#[test]
+fn arena_value_invalid_input() {
+    let bump = Bump::new();
+    assert!(from_bytes_in(&[], &bump).is_err());
+    assert!(from_bytes_in(&[10, 0, 0, 8, 0, 1], &bump).is_err());
+}
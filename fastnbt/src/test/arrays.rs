@@ -0,0 +1,243 @@
+use serde::Deserialize;
+
+use super::builder::Builder;
+use crate::{borrow, ByteArray, IntArray, LongArray, Value};
+
+#[test]
+fn byte_array_as_bytes() {
+    let arr = ByteArray::new(vec![1, -1, 127, -128]);
+    assert_eq!(arr.as_bytes(), &[1, 255, 127, 128]);
+}
+
+#[test]
+fn byte_array_from_unsigned() {
+    let expected = ByteArray::new(vec![1, -1, 127, -128]);
+
+    assert_eq!(expected, ByteArray::from(vec![1u8, 255, 127, 128]));
+    assert_eq!(expected, ByteArray::from(&[1u8, 255, 127, 128][..]));
+}
+
+#[test]
+fn borrowed_arrays_into_owned() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        #[serde(borrow)]
+        bytes: borrow::ByteArray<'a>,
+        #[serde(borrow)]
+        ints: borrow::IntArray<'a>,
+        #[serde(borrow)]
+        longs: borrow::LongArray<'a>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .byte_array("bytes", &[1, -1])
+        .int_array("ints", &[1, -1, i32::MAX])
+        .long_array("longs", &[1, -1, i64::MIN])
+        .end_compound()
+        .build();
+
+    let v: V = crate::from_bytes(&payload).unwrap();
+    let (bytes, ints, longs) = (
+        v.bytes.into_owned(),
+        v.ints.into_owned(),
+        v.longs.into_owned(),
+    );
+    drop(payload);
+
+    assert_eq!(bytes, ByteArray::new(vec![1, -1]));
+    assert_eq!(ints, IntArray::new(vec![1, -1, i32::MAX]));
+    assert_eq!(longs, LongArray::new(vec![1, -1, i64::MIN]));
+}
+
+#[test]
+fn borrowed_arrays_random_access() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        #[serde(borrow)]
+        bytes: borrow::ByteArray<'a>,
+        #[serde(borrow)]
+        ints: borrow::IntArray<'a>,
+        #[serde(borrow)]
+        longs: borrow::LongArray<'a>,
+    }
+
+    let longs: Vec<i64> = (0..1000).map(|i| i * -3).collect();
+    let payload = Builder::new()
+        .start_compound("")
+        .byte_array("bytes", &[1, -1])
+        .int_array("ints", &[])
+        .long_array("longs", &longs)
+        .end_compound()
+        .build();
+
+    let v: V = crate::from_bytes(&payload).unwrap();
+
+    assert_eq!(v.bytes.len(), 2);
+    assert_eq!(v.bytes.get(1), Some(-1));
+    assert_eq!(v.bytes.get(2), None);
+
+    assert!(v.ints.is_empty());
+    assert_eq!(v.ints.get(0), None);
+
+    assert_eq!(v.longs.len(), 1000);
+    assert_eq!(v.longs.get(5), Some(-15));
+    assert_eq!(v.longs.get(999), Some(-2997));
+    assert_eq!(v.longs.get(1000), None);
+    assert_eq!(v.longs.get(usize::MAX), None);
+
+    let native = borrow::LongArray::new(&[7, 8]);
+    assert_eq!(native.len(), 2);
+    assert_eq!(native.get(1), Some(8));
+    assert_eq!(native.get(2), None);
+}
+
+#[test]
+fn owned_arrays_from_borrowed() {
+    assert_eq!(
+        ByteArray::from(borrow::ByteArray::new(&[1, -1])),
+        ByteArray::new(vec![1, -1])
+    );
+    assert_eq!(
+        IntArray::from(borrow::IntArray::new(&[1, -1])),
+        IntArray::new(vec![1, -1])
+    );
+    assert_eq!(
+        LongArray::from(borrow::LongArray::new(&[1, -1])),
+        LongArray::new(vec![1, -1])
+    );
+}
+
+#[test]
+fn build_arrays_incrementally() {
+    let mut longs = LongArray::with_capacity(4);
+    longs.push(1);
+    longs.extend([2, 3]);
+    longs.extend(&[4]);
+    assert_eq!(longs, LongArray::new(vec![1, 2, 3, 4]));
+
+    let bytes = crate::to_bytes(&crate::nbt!({ "a": longs.clone() })).unwrap();
+    let value: crate::Value = crate::from_bytes(&bytes).unwrap();
+    assert_eq!(value, crate::nbt!({ "a": [L; 1, 2, 3, 4] }));
+
+    longs.clear();
+    assert!(longs.is_empty());
+
+    let mut ints = IntArray::default();
+    ints.push(-1);
+    assert_eq!(ints, IntArray::new(vec![-1]));
+
+    let mut bytes = ByteArray::default();
+    bytes.extend([1, -1]);
+    assert_eq!(bytes.as_bytes(), &[1, 255]);
+}
+
+#[test]
+fn arrays_as_slices() {
+    fn sum(values: &[i64]) -> i64 {
+        values.iter().sum()
+    }
+
+    let mut longs = LongArray::new(vec![1, 2, 3, 4]);
+    assert_eq!(sum(&longs), 10);
+    assert_eq!(sum(longs.as_ref()), 10);
+    assert_eq!(longs[1], 2);
+    assert_eq!(longs.windows(2).count(), 3);
+    assert_eq!(longs.chunks(3).last(), Some(&[4][..]));
+
+    longs[0] = 5;
+    longs.sort();
+    assert_eq!(*longs, [2, 3, 4, 5]);
+
+    let mut total = 0;
+    for l in &longs {
+        total += l;
+    }
+    assert_eq!(total, 14);
+    assert_eq!(longs.into_iter().collect::<Vec<_>>(), [2, 3, 4, 5]);
+
+    let ints = IntArray::new(vec![1, -1]);
+    assert_eq!((&ints).into_iter().max(), Some(&1));
+    assert_eq!(ints.into_iter().min(), Some(-1));
+
+    let bytes = ByteArray::new(vec![1, -1]);
+    assert_eq!(bytes.as_ref(), &[1, -1]);
+    assert_eq!(bytes.into_iter().map(i32::from).sum::<i32>(), 0);
+}
+
+#[test]
+fn byte_array_debug_is_compact() {
+    let small = ByteArray::new(vec![0, 1, -1]);
+    assert_eq!(format!("{:?}", small), "ByteArray(len: 3, 0001ff)");
+    assert_eq!(format!("{:?}", ByteArray::default()), "ByteArray(len: 0, )");
+
+    let big = ByteArray::new((0..100).map(|i| i as i8).collect());
+    let expected = format!("ByteArray(len: 100, {}…)", &big.to_hex()[..64]);
+    assert_eq!(format!("{:?}", big), expected);
+    assert_eq!(big.to_hex().len(), 200);
+
+    // Values holding byte arrays print them the same way.
+    let value = Value::ByteArray(small);
+    assert_eq!(
+        format!("{:?}", value),
+        "ByteArray(ByteArray(len: 3, 0001ff))"
+    );
+}
+
+#[test]
+fn long_array_runs() {
+    let empty = LongArray::default();
+    assert_eq!(empty.run_length_summary(), []);
+    assert_eq!(empty.is_uniform(), None);
+
+    let uniform = LongArray::new(vec![-1; 4096]);
+    assert_eq!(uniform.run_length_summary(), [(-1, 4096)]);
+    assert_eq!(uniform.is_uniform(), Some(-1));
+
+    let mixed = LongArray::new(vec![5, 5, 1, 1, 1, 5]);
+    assert_eq!(mixed.run_length_summary(), [(5, 2), (1, 3), (5, 1)]);
+    assert_eq!(mixed.is_uniform(), None);
+    assert_eq!(LongArray::new(vec![3]).is_uniform(), Some(3));
+}
+
+#[test]
+fn arrays_equal_slices_of_elements() {
+    let longs = LongArray::new(vec![1, -1, i64::MIN]);
+    assert_eq!(longs, &[1i64, -1, i64::MIN][..]);
+    assert_eq!(longs, [1, -1, i64::MIN]);
+    assert_eq!(longs, vec![1, -1, i64::MIN]);
+    assert_eq!(vec![1, -1, i64::MIN], longs);
+    assert_ne!(longs, [1, -1]);
+
+    assert_eq!(IntArray::new(vec![1, 2]), [1, 2]);
+    assert_eq!(ByteArray::new(vec![1, -1]), [1, -1]);
+    assert_ne!(ByteArray::new(vec![]), [0]);
+
+    #[derive(Deserialize)]
+    struct V<'a> {
+        #[serde(borrow)]
+        bytes: borrow::ByteArray<'a>,
+        #[serde(borrow)]
+        ints: borrow::IntArray<'a>,
+        #[serde(borrow)]
+        longs: borrow::LongArray<'a>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .byte_array("bytes", &[1, -1])
+        .int_array("ints", &[1, -1, i32::MAX])
+        .long_array("longs", &[1, -1, i64::MIN])
+        .end_compound()
+        .build();
+
+    // These are big endian bytes of the input, compared by element.
+    let v: V = crate::from_bytes(&payload).unwrap();
+    assert_eq!(v.bytes, [1, -1]);
+    assert_eq!(v.ints, &[1, -1, i32::MAX][..]);
+    assert_eq!(v.longs, vec![1, -1, i64::MIN]);
+    assert_ne!(v.ints, [1, -1]);
+    assert_ne!(v.longs, [1, -1, i64::MIN, 0]);
+
+    assert_eq!(borrow::IntArray::new(&[3, 4]), [3, 4]);
+}
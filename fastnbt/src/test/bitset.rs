@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{bitset, from_bytes, test::builder::Builder, to_bytes};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Mask {
+    #[serde(with = "bitset")]
+    mask: Vec<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ShortMask {
+    #[serde(
+        serialize_with = "bitset::serialize",
+        deserialize_with = "bitset::deserialize_len::<_, 3>"
+    )]
+    mask: Vec<bool>,
+}
+
+#[test]
+fn bit_order() {
+    let bits = bitset::from_longs(&[0b101, 1], 128);
+    assert_eq!(bits.len(), 128);
+    assert_eq!(&bits[..4], &[true, false, true, false]);
+    assert!(bits[64]);
+    assert_eq!(bits.iter().filter(|b| **b).count(), 3);
+
+    assert_eq!(bitset::to_longs(&bits), vec![0b101, 1]);
+}
+
+#[test]
+fn high_bit_is_last_index() {
+    let bits = bitset::from_longs(&[i64::MIN], 64);
+    assert!(bits[63]);
+    assert_eq!(bitset::to_longs(&bits), vec![i64::MIN]);
+}
+
+#[test]
+fn to_longs_pads_final_long() {
+    assert_eq!(bitset::to_longs(&[true; 65]), vec![-1, 1]);
+    assert_eq!(bitset::to_longs(&[]), Vec::<i64>::new());
+}
+
+#[test]
+fn deserialize_long_array() {
+    let payload = Builder::new()
+        .start_compound("")
+        .long_array("mask", &[0b11])
+        .end_compound()
+        .build();
+
+    let v: Mask = from_bytes(&payload).unwrap();
+    assert_eq!(v.mask.len(), 64);
+    assert_eq!(&v.mask[..3], &[true, true, false]);
+}
+
+#[test]
+fn deserialize_with_len() {
+    let payload = Builder::new()
+        .start_compound("")
+        .long_array("mask", &[0b110])
+        .end_compound()
+        .build();
+
+    let v: ShortMask = from_bytes(&payload).unwrap();
+    assert_eq!(v.mask, vec![false, true, true]);
+}
+
+#[test]
+fn deserialize_with_len_too_short_errors() {
+    let payload = Builder::new()
+        .start_compound("")
+        .long_array("mask", &[])
+        .end_compound()
+        .build();
+
+    assert!(from_bytes::<ShortMask>(&payload).is_err());
+}
+
+#[test]
+fn serialize_as_long_array() {
+    let v = ShortMask {
+        mask: vec![false, true, true],
+    };
+    let expected = Builder::new()
+        .start_compound("")
+        .long_array("mask", &[0b110])
+        .end_compound()
+        .build();
+
+    assert_eq!(to_bytes(&v).unwrap(), expected);
+    assert_eq!(from_bytes::<ShortMask>(&expected).unwrap(), v);
+}
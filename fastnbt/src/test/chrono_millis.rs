@@ -0,0 +1,34 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{from_bytes, to_bytes};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+struct Level {
+    #[serde(with = "crate::chrono_millis")]
+    last_played: DateTime<Utc>,
+}
+
+#[test]
+fn chrono_millis_round_trip() {
+    let last_played = Utc.timestamp_millis_opt(1_700_000_000_123).unwrap();
+    let bytes = to_bytes(&Level { last_played }).unwrap();
+
+    assert_eq!(
+        from_bytes::<crate::Value>(&bytes).unwrap(),
+        nbt!({"LastPlayed": 1_700_000_000_123_i64})
+    );
+    assert_eq!(from_bytes::<Level>(&bytes).unwrap(), Level { last_played });
+}
+
+#[test]
+fn chrono_millis_from_other_integers_and_out_of_range() {
+    let bytes = to_bytes(&nbt!({"LastPlayed": 1000})).unwrap();
+    let level: Level = from_bytes(&bytes).unwrap();
+    assert_eq!(level.last_played, Utc.timestamp_opt(1, 0).unwrap());
+
+    let bytes = to_bytes(&nbt!({"LastPlayed": i64::MAX})).unwrap();
+    let err = from_bytes::<Level>(&bytes).unwrap_err();
+    assert!(err.to_string().contains("timestamp out of range"), "{err}");
+}
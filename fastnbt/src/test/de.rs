@@ -4,10 +4,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     borrow,
+    de::{read_root_header, Deserializer},
     error::{Error, Result},
-    from_bytes, from_bytes_with_opts, from_reader,
+    from_bytes, from_bytes_with_opts, from_reader, from_reader_with_opts,
     test::builder::Builder,
-    to_bytes, ByteArray, DeOpts, IntArray, LongArray, Tag, Value,
+    to_bytes, ByteArray, DeOpts, Endian, IntArray, LongArray, Tag, Value,
 };
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -667,6 +668,59 @@ fn newtype_struct() {
     assert_eq!(v.a.0, 123);
 }
 
+#[test]
+fn newtype_struct_of_int() {
+    #[derive(Deserialize, Debug, PartialEq, Eq, Hash)]
+    struct SlotId(i32);
+
+    #[derive(Deserialize, Debug, PartialEq, Eq, Hash)]
+    struct Name(String);
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        id: SlotId,
+        ids: Vec<SlotId>,
+        named: HashMap<Name, SlotId>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("id", 7)
+        .start_list("ids", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(2)
+        .start_compound("named")
+        .int("offhand", 40)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let v: V = from_all(payload.as_slice());
+    assert_eq!(v.id, SlotId(7));
+    assert_eq!(v.ids, [SlotId(1), SlotId(2)]);
+    assert_eq!(v.named[&Name("offhand".to_string())], SlotId(40));
+}
+
+#[test]
+fn newtype_struct_at_root() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Inner {
+        a: i32,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Root(Inner);
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .end_compound()
+        .build();
+
+    let v: Root = from_all(payload.as_slice());
+    assert_eq!(v, Root(Inner { a: 1 }));
+}
+
 #[test]
 fn vec_from_nbt_byte_array() {
     #[derive(Deserialize)]
@@ -1258,6 +1312,101 @@ fn pathological_tuples() {
     assert_eq!(v.list[0].value, ((1, 2, 3), (4, 5)))
 }
 
+#[test]
+fn tuple_from_list() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Pos(f64, f64, f64);
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        color: (u8, u8, u8),
+        pos: Pos,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("color", Tag::Byte, 3)
+        .byte_payload(1)
+        .byte_payload(2)
+        .byte_payload(3)
+        .start_list("pos", Tag::Double, 3)
+        .double_payload(1.0)
+        .double_payload(2.0)
+        .double_payload(3.0)
+        .end_compound()
+        .build();
+
+    let v: V = from_all(&payload);
+    assert_eq!(v.color, (1, 2, 3));
+    assert_eq!(v.pos, Pos(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn tuple_from_list_length_mismatch_errors() {
+    #[derive(Deserialize, Serialize, Debug)]
+    struct Pos(i32, i32, i32);
+
+    for len in [2, 4] {
+        let payload = Builder::new()
+            .start_compound("")
+            .start_list("val", Tag::Int, len)
+            .int_array_payload(&vec![0; len as usize])
+            .end_compound()
+            .build();
+
+        assert!(from_bytes::<Single<(i32, i32, i32)>>(&payload).is_err());
+        assert!(from_bytes::<Single<Pos>>(&payload).is_err());
+        assert!(from_reader::<_, Single<Pos>>(payload.as_slice()).is_err());
+    }
+}
+
+#[test]
+fn tuple_from_array_errors() {
+    let payload = Builder::new()
+        .start_compound("")
+        .int_array("val", &[1, 2, 3])
+        .end_compound()
+        .build();
+
+    assert!(from_bytes::<Single<(i32, i32, i32)>>(&payload).is_err());
+}
+
+#[test]
+fn list_into_boxed_and_shared_slices() {
+    use std::{rc::Rc, sync::Arc};
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Palette {
+        #[serde(rename = "Name")]
+        name: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        boxed: Box<[i32]>,
+        rc: Rc<[i16]>,
+        palette: Arc<[Palette]>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("boxed", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(2)
+        .start_list("rc", Tag::Short, 1)
+        .short_payload(3)
+        .start_list("palette", Tag::Compound, 1)
+        .string("Name", "minecraft:stone")
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let v: V = from_all(&payload);
+    assert_eq!(&*v.boxed, &[1, 2]);
+    assert_eq!(&*v.rc, &[3]);
+    assert_eq!(v.palette[0].name, "minecraft:stone");
+}
+
 #[test]
 fn byte_array() -> Result<()> {
     #[derive(Deserialize)]
@@ -1436,26 +1585,72 @@ fn array_subslice_doesnt_panic() {
 }
 
 #[test]
-fn nice_error_if_deserialize_array_to_seq() {
-    // Since the handling of NBT Arrays is a bit surprising, we want to make
-    // that if someone tries to deserialize an Array into a serde seq (like a
-    // Vec) rather than the dedicated types, we give a nice error message to them.
-    #[derive(Deserialize)]
+fn deserialize_array_to_seq() {
+    // Some tools write lists where vanilla uses arrays and vice versa, so
+    // sequences accept either.
+    #[derive(Deserialize, Debug, PartialEq)]
     struct V {
-        _data: Vec<i64>,
+        bytes: Vec<u8>,
+        ints: Vec<i32>,
+        longs: Vec<i64>,
+        int_list: Vec<i32>,
     }
 
     let payload = Builder::new()
         .start_compound("")
-        .long_array("_data", &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+        .byte_array("bytes", &[1, 2])
+        .int_array("ints", &[3, -4])
+        .long_array("longs", &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+        .start_list("int_list", Tag::Int, 2)
+        .int_payload(5)
+        .int_payload(6)
+        .end_compound()
+        .build();
+
+    let v: V = from_all(&payload);
+    assert_eq!(
+        v,
+        V {
+            bytes: vec![1, 2],
+            ints: vec![3, -4],
+            longs: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            int_list: vec![5, 6],
+        }
+    );
+}
+
+#[test]
+fn nice_error_if_deserialize_list_to_array() {
+    // The array types stay strict, for when exactly an NBT array is wanted.
+    #[derive(Deserialize, Debug)]
+    struct V {
+        _data: IntArray,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("_data", Tag::Int, 1)
+        .int_payload(1)
         .end_compound()
         .build();
 
-    let res = from_bytes::<V>(&payload);
-    match res {
-        Ok(_) => panic!("expected err"),
-        Err(e) => assert!(e.to_string().contains("Array")),
+    assert!(from_bytes::<V>(&payload).is_err());
+}
+
+#[test]
+fn negative_byte_array_to_vec_u8_errors() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        _data: Vec<u8>,
     }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .byte_array("_data", &[1, -1])
+        .end_compound()
+        .build();
+
+    assert!(from_bytes::<V>(&payload).is_err());
 }
 
 #[test]
@@ -1489,6 +1684,75 @@ fn ints_to_bool() {
     )
 }
 
+#[test]
+fn list_of_bytes_to_bools() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct V {
+        flags: Vec<bool>,
+        none: Vec<bool>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("flags", Tag::Byte, 4)
+        .byte_payload(0)
+        .byte_payload(1)
+        .byte_payload(1)
+        .byte_payload(-1)
+        .start_list("none", Tag::End, 0)
+        .end_compound()
+        .build();
+
+    let v = from_all::<V>(&payload);
+    assert_eq!(
+        v,
+        V {
+            flags: vec![false, true, true, true],
+            none: vec![],
+        }
+    );
+
+    let v: V = from_bytes(&crate::to_bytes(&v).unwrap()).unwrap();
+    assert_eq!(v.flags, [false, true, true, true]);
+}
+
+#[test]
+fn jagged_list_of_lists() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct V {
+        grid: Vec<Vec<i32>>,
+        after: i8,
+    }
+
+    // Each inner list has its own element tag, including End when empty.
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("grid", Tag::List, 4)
+        .start_anon_list(Tag::Int, 3)
+        .int_payload(1)
+        .int_payload(2)
+        .int_payload(3)
+        .start_anon_list(Tag::End, 0)
+        .start_anon_list(Tag::Int, 1)
+        .int_payload(4)
+        .start_anon_list(Tag::Int, 0)
+        .byte("after", 5)
+        .end_compound()
+        .build();
+
+    let v = from_all::<V>(&payload);
+    assert_eq!(
+        v,
+        V {
+            grid: vec![vec![1, 2, 3], vec![], vec![4], vec![]],
+            after: 5,
+        }
+    );
+
+    let v: V = from_bytes(&crate::to_bytes(&v).unwrap()).unwrap();
+    assert_eq!(v.grid, [vec![1, 2, 3], vec![], vec![4], vec![]]);
+}
+
 #[test]
 fn direct_to_non_compound() {
     // Ensure that only compounds can be deserialized, not raw values inside
@@ -1497,6 +1761,35 @@ fn direct_to_non_compound() {
     assert!(from_reader::<_, String>(&*payload).is_err());
 }
 
+#[test]
+fn borrow_strings_and_arrays_together() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        name: &'a str,
+        data: &'a [u8],
+        ints: &'a [u8],
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .string("name", "hello")
+        .byte_array("data", &[1, 2, 3])
+        .int_array("ints", &[1, 2])
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes(payload.as_slice()).unwrap();
+    assert_eq!(v.name, "hello");
+    assert_eq!(v.data, [1, 2, 3]);
+    assert_eq!(v.ints, [0, 0, 0, 1, 0, 0, 0, 2]);
+
+    // Each field should be a view into the payload rather than a copy.
+    let range = payload.as_ptr_range();
+    assert!(range.contains(&v.name.as_ptr()));
+    assert!(range.contains(&v.data.as_ptr()));
+    assert!(range.contains(&v.ints.as_ptr()));
+}
+
 #[test]
 fn primitive_to_bytes() {
     // Ensure trying to deserialize a primitive to bytes fails.
@@ -1601,6 +1894,117 @@ fn long_list_invalid_with_option() {
     assert!(from_bytes_with_opts::<V>(&payload, DeOpts::new().max_seq_len(2)).is_ok());
 }
 
+#[test]
+fn prealloc_limit_caps_list_size_hint() {
+    // Records the size hint of the list it is deserialized from.
+    struct Hinted {
+        hint: Option<usize>,
+        len: usize,
+    }
+
+    impl<'de> Deserialize<'de> for Hinted {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+            struct V;
+            impl<'de> serde::de::Visitor<'de> for V {
+                type Value = Hinted;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("list")
+                }
+
+                fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                    self,
+                    mut seq: A,
+                ) -> std::result::Result<Hinted, A::Error> {
+                    let hint = seq.size_hint();
+                    let mut len = 0;
+                    while seq.next_element::<i32>()?.is_some() {
+                        len += 1;
+                    }
+                    Ok(Hinted { hint, len })
+                }
+            }
+            d.deserialize_seq(V)
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct V {
+        list: Hinted,
+    }
+
+    let mut builder = Builder::new()
+        .start_compound("")
+        .start_list("list", Tag::Int, 10);
+    for i in 0..10 {
+        builder = builder.int_payload(i);
+    }
+    let payload = builder.end_compound().build();
+
+    let v: V = from_bytes(&payload).unwrap();
+    assert_eq!((v.list.hint, v.list.len), (Some(10), 10));
+
+    let v: V = from_bytes_with_opts(&payload, DeOpts::new().prealloc_limit(4)).unwrap();
+    assert_eq!((v.list.hint, v.list.len), (Some(4), 10));
+}
+
+#[test]
+fn max_alloc_rejects_huge_array_before_allocating() {
+    // Claims to contain a 2GB byte array.
+    let payload = Builder::new()
+        .start_compound("")
+        .tag(Tag::ByteArray)
+        .name("a")
+        .int_payload(i32::MAX)
+        .build();
+
+    let opts = DeOpts::new().max_alloc(10_000_000);
+    let err = from_reader_with_opts::<_, Value>(&*payload, opts).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "a: deserializing 2147483647 bytes would exceed allocation budget (1 of 10000000 bytes used)"
+    );
+}
+
+#[test]
+fn max_alloc_is_cumulative() {
+    let payload = Builder::new()
+        .start_compound("")
+        .string("a", "1234")
+        .string("b", "5678")
+        .byte_array("c", &[1, 2])
+        .start_list("d", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(2)
+        .end_compound()
+        .build();
+
+    // 4 bytes of keys, 8 of strings, 2 of bytes and 8 of ints.
+    let opts = |max| DeOpts::new().max_alloc(max);
+    assert!(from_bytes_with_opts::<Value>(&payload, opts(22)).is_ok());
+    assert!(from_bytes_with_opts::<Value>(&payload, opts(21)).is_err());
+    assert!(from_reader_with_opts::<_, Value>(&*payload, opts(21)).is_err());
+    assert!(from_bytes_with_opts::<Value>(&payload, DeOpts::new()).is_ok());
+}
+
+#[test]
+fn max_alloc_does_not_count_skipped_data() {
+    let payload = Builder::new()
+        .start_compound("")
+        .byte_array("a", &[1, 2, 3, 4, 5, 6, 7, 8])
+        .end_compound()
+        .build();
+
+    #[derive(Deserialize)]
+    struct Empty {}
+
+    let opts = || DeOpts::new().max_alloc(3);
+    assert!(from_bytes_with_opts::<Value>(&payload, opts()).is_err());
+    assert!(from_bytes_with_opts::<Empty>(&payload, opts()).is_ok());
+    assert!(from_reader_with_opts::<_, Empty>(&*payload, opts()).is_ok());
+    assert!(from_bytes_with_opts::<serde::de::IgnoredAny>(&payload, opts()).is_ok());
+}
+
 #[test]
 fn untagged_enum_with_arrays() {
     #[derive(Debug, Deserialize, PartialEq)]
@@ -1746,35 +2150,123 @@ fn byte_array_value_from_reader() {
 }
 
 #[test]
-fn networked_compound() {
-    // TAG_Compound
-    // {
-    //     TAG_String("networked"): "compound"
-    // }
-    let data = b"\
-        \x0a\
-            \x08\
-                \x00\x09networked\
-                \x00\x08compound\
-        \x00";
+fn reader_skips_values_in_small_reads() {
+    use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+    use std::io::{Read, Write};
 
-    let v: HashMap<String, Value> = from_bytes_with_opts(data, DeOpts::network_nbt()).unwrap();
-    assert_eq!(v["networked"], "compound");
-}
+    // Records the largest read asked of the underlying reader.
+    struct LargestRead<R> {
+        inner: R,
+        largest: usize,
+    }
 
-#[test]
-fn nested_networked_compound() {
-    // TAG_Compound
-    // {
-    //     TAG_Compound("nested")
-    //     {
-    //         TAG_String("networked"): "compound"
-    //     }
-    // }
-    let data = b"\
-        \x0a\
-            \x0a\
-            \x00\x06nested\
+    impl<R: Read> Read for LargestRead<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.largest = self.largest.max(buf.len());
+            self.inner.read(buf)
+        }
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        name: String,
+    }
+
+    let blob = vec![0; 4_000_000];
+    let data = Builder::new()
+        .start_compound("")
+        .byte_array("blob", &blob)
+        .string("name", "small")
+        .string("other", &"a".repeat(60_000))
+        .end_compound()
+        .build();
+
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&data).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut reader = LargestRead {
+        inner: GzDecoder::new(compressed.as_slice()),
+        largest: 0,
+    };
+    let v: V = from_reader(&mut reader).unwrap();
+    assert_eq!(v.name, "small");
+    assert!(reader.largest < 60_000, "largest read {}", reader.largest);
+
+    // Skipping past the end of the input is still an error.
+    let truncated = &data[..data.len() - 100];
+    assert!(from_reader::<_, V>(truncated).is_err());
+}
+
+#[test]
+fn io_errors_are_the_source() {
+    use std::error::Error as _;
+    use std::io::{self, Read};
+
+    fn io_kind(err: &crate::error::Error) -> Option<io::ErrorKind> {
+        err.source()
+            .and_then(|e| e.downcast_ref::<io::Error>())
+            .map(|e| e.kind())
+    }
+
+    struct Denied;
+
+    impl Read for Denied {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+    }
+
+    let err = from_reader::<_, Value>(Denied).unwrap_err();
+    assert_eq!(io_kind(&err), Some(io::ErrorKind::PermissionDenied));
+
+    // Running out of input part way through, whether reading or skipping.
+    let data = Builder::new()
+        .start_compound("")
+        .string("a", "hello")
+        .end_compound()
+        .build();
+    let err = from_reader::<_, Value>(&data[..data.len() - 3]).unwrap_err();
+    assert_eq!(io_kind(&err), Some(io::ErrorKind::UnexpectedEof));
+    let err = from_reader::<_, HashMap<String, ()>>(&data[..data.len() - 3]).unwrap_err();
+    assert_eq!(io_kind(&err), Some(io::ErrorKind::UnexpectedEof));
+
+    // Errors that are not from IO have no source, and still compare and clone.
+    let err = from_bytes::<Value>(&[8, 0, 0, 0, 0]).unwrap_err();
+    assert!(err.source().is_none());
+    assert_eq!(err.clone(), err);
+}
+
+#[test]
+fn networked_compound() {
+    // TAG_Compound
+    // {
+    //     TAG_String("networked"): "compound"
+    // }
+    let data = b"\
+        \x0a\
+            \x08\
+                \x00\x09networked\
+                \x00\x08compound\
+        \x00";
+
+    let v: HashMap<String, Value> = from_bytes_with_opts(data, DeOpts::network_nbt()).unwrap();
+    assert_eq!(v["networked"], "compound");
+}
+
+#[test]
+fn nested_networked_compound() {
+    // TAG_Compound
+    // {
+    //     TAG_Compound("nested")
+    //     {
+    //         TAG_String("networked"): "compound"
+    //     }
+    // }
+    let data = b"\
+        \x0a\
+            \x0a\
+            \x00\x06nested\
                 \x08\
                     \x00\x09networked\
                     \x00\x08compound\
@@ -1807,3 +2299,1017 @@ fn error_nested_non_named_compounds() {
     let v: Result<Value> = from_bytes_with_opts(data, DeOpts::network_nbt());
     assert!(v.is_err())
 }
+
+#[test]
+fn little_endian_compound() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Level {
+        short: i16,
+        int: i32,
+        str: String,
+        ints: IntArray,
+        longs: LongArray,
+        list: Vec<i32>,
+        uuid: u128,
+    }
+
+    let data = b"\
+        \x0a\x00\x00\
+            \x02\x05\x00short\x01\x00\
+            \x03\x03\x00int\x04\x03\x02\x01\
+            \x08\x03\x00str\x02\x00hi\
+            \x0b\x04\x00ints\x02\x00\x00\x00\x01\x00\x00\x00\xff\xff\xff\xff\
+            \x0c\x05\x00longs\x01\x00\x00\x00\x02\x00\x00\x00\x00\x00\x00\x00\
+            \x09\x04\x00list\x03\x02\x00\x00\x00\x05\x00\x00\x00\x06\x00\x00\x00\
+            \x0b\x04\x00uuid\x04\x00\x00\x00\
+                \x01\x00\x00\x00\x02\x00\x00\x00\x03\x00\x00\x00\x04\x00\x00\x00\
+        \x00";
+
+    let expected = Level {
+        short: 1,
+        int: 0x01020304,
+        str: "hi".to_string(),
+        ints: IntArray::new(vec![1, -1]),
+        longs: LongArray::new(vec![2]),
+        list: vec![5, 6],
+        uuid: 1 << 96 | 2 << 64 | 3 << 32 | 4,
+    };
+
+    let opts = DeOpts::new().endianness(Endian::Little);
+    let v: Level = from_bytes_with_opts(data, opts.clone()).unwrap();
+    assert_eq!(v, expected);
+    let v: Level = from_reader_with_opts(&data[..], opts).unwrap();
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn little_endian_data_misread_as_big_endian() {
+    let data = b"\
+        \x0a\x00\x00\
+            \x03\x03\x00val\x01\x00\x00\x00\
+        \x00";
+
+    assert!(from_bytes::<Single<i32>>(data).is_err());
+    let v: Single<i32> =
+        from_bytes_with_opts(data, DeOpts::new().endianness(Endian::Little)).unwrap();
+    assert_eq!(v.val, 1);
+}
+
+#[test]
+fn network_little_endian_entity() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct Entity {
+        #[serde(rename = "identifier")]
+        id: String,
+        age: i32,
+        unique_id: i64,
+        air: i16,
+        fall_distance: f32,
+        pos: Vec<f32>,
+        links: IntArray,
+        bytes: ByteArray,
+    }
+
+    // A compound as found in a Bedrock AddActor packet. Ints, longs and list
+    // and array lengths are zigzag varints, string lengths are varints.
+    let data = b"\
+        \x0a\x00\
+            \x08\x0aidentifier\x10minecraft:zombie\
+            \x03\x03Age\x01\
+            \x04\x08UniqueId\xab\x02\
+            \x02\x03Air\x2c\x01\
+            \x05\x0cFallDistance\x00\x00\x80\x3f\
+            \x09\x03Pos\x05\x04\x00\x00\x00\x40\x00\x00\x80\xbf\
+            \x0b\x05Links\x04\x02\x03\
+            \x07\x05Bytes\x06\x01\x02\x03\
+            \x0c\x07Ignored\x04\x80\x01\x01\
+            \x03\x03Big\xd8\x04\
+        \x00";
+
+    let expected = Entity {
+        id: "minecraft:zombie".to_string(),
+        age: -1,
+        unique_id: -150,
+        air: 300,
+        fall_distance: 1.0,
+        pos: vec![2.0, -1.0],
+        links: IntArray::new(vec![1, -2]),
+        bytes: ByteArray::new(vec![1, 2, 3]),
+    };
+
+    let opts = DeOpts::new().endianness(Endian::NetworkLittle);
+    let v: Entity = from_bytes_with_opts(data, opts.clone()).unwrap();
+    assert_eq!(v, expected);
+    let v: Entity = from_reader_with_opts(&data[..], opts.clone()).unwrap();
+    assert_eq!(v, expected);
+
+    let v: HashMap<String, Value> = from_bytes_with_opts(data, opts).unwrap();
+    assert_eq!(v["Big"], 300);
+    assert_eq!(v["Ignored"], Value::LongArray(LongArray::new(vec![64, -1])));
+}
+
+#[test]
+fn network_little_endian_overlong_varint_errors() {
+    let data = b"\
+        \x0a\x00\
+            \x03\x03val\xff\xff\xff\xff\xff\xff\
+        \x00";
+
+    let opts = DeOpts::new().endianness(Endian::NetworkLittle);
+    assert!(from_bytes_with_opts::<Single<i32>>(data, opts).is_err());
+}
+
+#[test]
+fn error_includes_path() {
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    struct Chunk {
+        #[allow(dead_code)]
+        level: Level,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    struct Level {
+        #[allow(dead_code)]
+        sections: Vec<Section>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    struct Section {
+        #[allow(dead_code)]
+        y: i8,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_compound("Level")
+        .start_list("Sections", Tag::Compound, 3)
+        .byte("Y", 0)
+        .end_anon_compound()
+        .byte("Y", 1)
+        .end_anon_compound()
+        .string("Y", "two")
+        .end_anon_compound()
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let err = from_bytes::<Chunk>(&payload).unwrap_err();
+    assert_eq!(err.path(), "Level.Sections[2].Y");
+    assert_eq!(
+        err.to_string(),
+        "Level.Sections[2].Y: invalid type: string \"two\", expected i8"
+    );
+
+    let err = from_reader::<_, Chunk>(&*payload).unwrap_err();
+    assert_eq!(err.path(), "Level.Sections[2].Y");
+}
+
+#[test]
+fn error_path_for_missing_field_and_nested_lists() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        list: Vec<Vec<i32>>,
+        #[allow(dead_code)]
+        inner: Inner,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Inner {
+        #[allow(dead_code)]
+        a: i32,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("list", Tag::List, 2)
+        .start_anon_list(Tag::Int, 1)
+        .int_payload(1)
+        .start_anon_list(Tag::String, 1)
+        .string_payload("x")
+        .end_compound()
+        .build();
+
+    let err = from_bytes::<V>(&payload).unwrap_err();
+    assert_eq!(err.path(), "list[1][0]");
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("list", Tag::List, 0)
+        .start_compound("inner")
+        .int("b", 1)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let err = from_bytes::<V>(&payload).unwrap_err();
+    assert_eq!(err.to_string(), "inner: missing field `a`");
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn small_lists_into_smallvec() {
+    use smallvec::SmallVec;
+
+    #[derive(Deserialize)]
+    struct V {
+        pos: SmallVec<[f64; 3]>,
+        tags: SmallVec<[String; 2]>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("pos", Tag::Double, 3)
+        .double_payload(1.0)
+        .double_payload(2.0)
+        .double_payload(3.0)
+        .start_list("tags", Tag::String, 3)
+        .string_payload("a")
+        .string_payload("b")
+        .string_payload("c")
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes(&payload).unwrap();
+    assert_eq!(v.pos.as_slice(), &[1.0, 2.0, 3.0]);
+    assert!(!v.pos.spilled());
+    assert_eq!(v.tags.as_slice(), &["a", "b", "c"]);
+    assert!(v.tags.spilled());
+}
+
+#[test]
+fn lone_end_tag_is_empty_document() {
+    #[derive(Deserialize, Debug)]
+    struct V {}
+
+    let err = from_bytes::<Value>(&[0]).unwrap_err();
+    assert!(err.is_empty_document());
+    assert!(from_reader::<_, V>(&[0][..])
+        .unwrap_err()
+        .is_empty_document());
+
+    // Only a lone end tag counts, not one followed by more data.
+    for payload in [&[0, 0][..], &[0, 10, 0, 0, 0]] {
+        let err = from_bytes::<Value>(payload).unwrap_err();
+        assert!(!err.is_empty_document());
+        assert_eq!(
+            err.to_string(),
+            "invalid nbt: end tag followed by more data instead of root compound"
+        );
+        assert!(!from_reader::<_, V>(payload)
+            .unwrap_err()
+            .is_empty_document());
+    }
+
+    // Other invalid documents are not.
+    assert!(!from_bytes::<Value>(&[]).unwrap_err().is_empty_document());
+    assert!(!from_bytes::<Value>(&[1, 0, 0, 5])
+        .unwrap_err()
+        .is_empty_document());
+}
+
+#[test]
+fn root_not_compound() {
+    #[derive(Deserialize, Debug)]
+    struct V {}
+
+    // A byte named "a" with value 1, as if read from the wrong offset.
+    let payload = [1, 0, 1, b'a', 1];
+    let err = from_bytes::<Value>(&payload).unwrap_err();
+    assert_eq!(err.root_not_compound(), Some(Tag::Byte));
+    assert_eq!(err.to_string(), "invalid nbt: root is byte, not a compound");
+
+    let err = from_reader::<_, V>(&[9, 0, 0, 0, 0, 0, 0][..]).unwrap_err();
+    assert_eq!(err.root_not_compound(), Some(Tag::List));
+
+    // Network NBT roots are unnamed, but still compounds.
+    let opts = DeOpts::network_nbt();
+    let err = from_bytes_with_opts::<V>(&[3, 0, 0, 0, 1], opts.clone()).unwrap_err();
+    assert_eq!(err.root_not_compound(), Some(Tag::Int));
+    assert!(from_bytes_with_opts::<V>(&[10, 0], opts).is_ok());
+
+    // Neither empty documents nor other errors count.
+    for payload in [&[0][..], &[], &[13]] {
+        let err = from_bytes::<Value>(payload).unwrap_err();
+        assert_eq!(err.root_not_compound(), None);
+    }
+}
+
+#[test]
+fn byte_array_into_serde_bytes() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        owned: serde_bytes::ByteBuf,
+        #[serde(borrow)]
+        borrowed: &'a serde_bytes::Bytes,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .byte_array("owned", &[1, -1])
+        .byte_array("borrowed", &[2, -2])
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes(&payload).unwrap();
+    assert_eq!(v.owned.as_slice(), &[1, 255]);
+    assert_eq!(&v.borrowed[..], &[2, 254]);
+
+    // The borrowed bytes point into the input.
+    let range = payload.as_ptr_range();
+    assert!(range.contains(&v.borrowed.as_ptr()));
+
+    #[derive(Deserialize)]
+    struct Owned {
+        owned: serde_bytes::ByteBuf,
+    }
+    let v: Owned = from_reader(payload.as_slice()).unwrap();
+    assert_eq!(v.owned.as_slice(), &[1, 255]);
+}
+
+#[test]
+fn borrow_from_memory_mapped_file() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        #[serde(borrow)]
+        name: &'a str,
+        #[serde(borrow)]
+        longs: borrow::LongArray<'a>,
+        #[serde(borrow)]
+        nested: Option<Nested<'a>>,
+    }
+
+    #[derive(Deserialize)]
+    struct Nested<'a> {
+        #[serde(borrow)]
+        bytes: &'a [u8],
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .string("name", "minecraft:full")
+        .long_array("longs", &[1, 2, 3])
+        .start_compound("nested")
+        .byte_array("bytes", &[4, 5])
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let path = std::env::temp_dir().join(format!("fastnbt-mmap-{}.nbt", std::process::id()));
+    std::fs::write(&path, &payload).unwrap();
+    let file = std::fs::File::open(&path).unwrap();
+    let mmap = unsafe { memmap2::Mmap::map(&file) }.unwrap();
+
+    let v: V = from_bytes(&mmap).unwrap();
+    assert_eq!(v.name, "minecraft:full");
+    assert_eq!(v.longs.iter().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(v.nested.as_ref().unwrap().bytes, [4, 5]);
+
+    // Borrowed fields point into the mapped file rather than a copy.
+    let range = mmap.as_ptr_range();
+    assert!(range.contains(&v.name.as_ptr()));
+    assert!(range.contains(&v.nested.unwrap().bytes.as_ptr()));
+
+    // Only remove the file once nothing maps it.
+    drop(mmap);
+    drop(file);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn from_bytes_accepts_byte_containers() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        #[serde(borrow)]
+        s: &'a str,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .string("s", "hello")
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes(&payload).unwrap();
+    assert_eq!(v.s, "hello");
+    assert!(payload.as_ptr_range().contains(&v.s.as_ptr()));
+
+    let boxed = payload.clone().into_boxed_slice();
+    let v: V = from_bytes(&boxed).unwrap();
+    assert!(boxed.as_ptr_range().contains(&v.s.as_ptr()));
+
+    let v: V = from_bytes(payload.as_slice()).unwrap();
+    assert_eq!(v.s, "hello");
+
+    let v: V = from_bytes_with_opts(&payload, DeOpts::default()).unwrap();
+    assert_eq!(v.s, "hello");
+}
+
+#[test]
+fn deny_unknown_fields_rejects_keys_in_nested_structs() {
+    #[derive(Deserialize, Debug)]
+    struct Chunk {
+        #[allow(dead_code)]
+        sections: Vec<Section>,
+        #[allow(dead_code)]
+        extra: HashMap<String, Value>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Section {
+        #[serde(rename = "Y")]
+        #[allow(dead_code)]
+        y: i8,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_list("sections", Tag::Compound, 2)
+        .byte("Y", 0)
+        .end_anon_compound()
+        .byte("Y", 1)
+        .int("SkyLight", 15)
+        .end_anon_compound()
+        .start_compound("extra")
+        .int("anything", 1)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    // Unknown keys are ignored by default.
+    let chunk: Result<Chunk> = from_bytes(&payload);
+    assert!(chunk.is_ok());
+
+    let opts = DeOpts::new().deny_unknown_fields(true);
+    let err = from_bytes_with_opts::<Chunk>(&payload, opts).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "sections[1].SkyLight: unknown field `SkyLight`, expected `Y`"
+    );
+    assert_eq!(err.path(), "sections[1].SkyLight");
+}
+
+#[test]
+fn deny_unknown_fields_at_root_and_with_known_fields() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        a: i32,
+        b: Option<i32>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .end_compound()
+        .build();
+
+    let opts = DeOpts::new().deny_unknown_fields(true);
+    let v: V = from_bytes_with_opts(&payload, opts.clone()).unwrap();
+    assert_eq!(v, V { a: 1, b: None });
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .int("c", 2)
+        .end_compound()
+        .build();
+
+    let err = from_bytes_with_opts::<V>(&payload, opts).unwrap_err();
+    assert_eq!(err.to_string(), "c: unknown field `c`, expected `a` or `b`");
+}
+
+#[test]
+fn case_insensitive_fields() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Chunk {
+        #[serde(rename = "DataVersion")]
+        data_version: i32,
+        level: Level,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Level {
+        #[serde(rename = "xPos")]
+        x_pos: i32,
+        #[serde(rename = "XPOS")]
+        x_pos_upper: Option<i32>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("dataversion", 1)
+        .start_compound("Level")
+        .int("XPOS", 2)
+        .int("XPos", 3)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    // Keys must match exactly by default.
+    assert!(from_bytes::<Chunk>(&payload).is_err());
+
+    let opts = DeOpts::new().case_insensitive_fields(true);
+    let chunk: Chunk = from_bytes_with_opts(&payload, opts.clone()).unwrap();
+    assert_eq!(
+        chunk,
+        Chunk {
+            data_version: 1,
+            level: Level {
+                // XPOS matches its field exactly, so only XPos is for xPos.
+                x_pos: 3,
+                x_pos_upper: Some(2),
+            },
+        }
+    );
+
+    // Keys matching ignoring case are known to deny_unknown_fields.
+    let chunk: Chunk = from_bytes_with_opts(&payload, opts.deny_unknown_fields(true)).unwrap();
+    assert_eq!(chunk.level.x_pos, 3);
+}
+
+#[test]
+fn skipped_fields_are_defaulted() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Section {
+        #[serde(rename = "Y")]
+        y: i8,
+        #[serde(skip)]
+        cache: Vec<u8>,
+    }
+
+    let without = Builder::new()
+        .start_compound("")
+        .byte("Y", 2)
+        .end_compound()
+        .build();
+
+    // A key with the skipped field's name is ignored like any unknown key.
+    let with = Builder::new()
+        .start_compound("")
+        .start_list("cache", Tag::Byte, 2)
+        .byte_payload(1)
+        .byte_payload(2)
+        .byte("Y", 2)
+        .end_compound()
+        .build();
+
+    let expected = Section {
+        y: 2,
+        cache: vec![],
+    };
+    assert_eq!(from_all::<Section>(&without), expected);
+    assert_eq!(from_all::<Section>(&with), expected);
+}
+
+#[test]
+fn tolerant_substitutes_defaults() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct Entity {
+        #[serde(rename = "id")]
+        id: String,
+        air: i16,
+        health: f32,
+        on_ground: bool,
+        pos: Vec<f64>,
+        items: Vec<Item>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct Item {
+        #[serde(rename = "id")]
+        id: String,
+        count: u8,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_compound("id")
+        .string("nested", "nope")
+        .end_compound()
+        .int("Air", 100_000)
+        .string("Health", "20")
+        .string("OnGround", "yes")
+        .start_list("Pos", Tag::Int, 3)
+        .int_payload(1)
+        .int_payload(2)
+        .int_payload(3)
+        .start_list("Items", Tag::Compound, 2)
+        .string("id", "minecraft:stone")
+        .byte("Count", 64)
+        .end_compound()
+        .string("id", "minecraft:dirt")
+        .byte("Count", -1)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    // Mismatches still fail by default.
+    assert!(from_bytes::<Entity>(&payload).is_err());
+
+    let opts = DeOpts::new().tolerant(true);
+    let expected = Entity {
+        id: String::new(),
+        air: 0,
+        health: 0.0,
+        on_ground: false,
+        pos: vec![1.0, 2.0, 3.0],
+        items: vec![
+            Item {
+                id: "minecraft:stone".into(),
+                count: 64,
+            },
+            Item {
+                id: "minecraft:dirt".into(),
+                count: 0,
+            },
+        ],
+    };
+    let expected_paths = ["id", "Air", "Health", "OnGround", "Items[1].Count"];
+
+    let mut de = Deserializer::from_bytes(&payload, opts.clone());
+    assert_eq!(Entity::deserialize(&mut de).unwrap(), expected);
+    assert_eq!(de.coerced_paths(), expected_paths);
+
+    let mut de = Deserializer::from_reader(Cursor::new(&payload), opts);
+    assert_eq!(Entity::deserialize(&mut de).unwrap(), expected);
+    assert_eq!(de.coerced_paths(), expected_paths);
+}
+
+#[test]
+fn tolerant_only_coerces_scalars() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        list: Vec<i32>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("list", 1)
+        .end_compound()
+        .build();
+
+    let opts = DeOpts::new().tolerant(true);
+    let err = from_bytes_with_opts::<V>(&payload, opts).unwrap_err();
+    assert_eq!(err.path(), "list");
+}
+
+#[test]
+fn option_distinguishes_missing_from_empty() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Item {
+        tag: Option<Value>,
+        list: Option<Vec<i32>>,
+    }
+
+    let missing = Builder::new().start_compound("").end_compound().build();
+    let item: Item = from_bytes(&missing).unwrap();
+    assert_eq!(
+        item,
+        Item {
+            tag: None,
+            list: None
+        }
+    );
+
+    let empty = Builder::new()
+        .start_compound("")
+        .start_compound("tag")
+        .end_compound()
+        .start_list("list", Tag::End, 0)
+        .end_compound()
+        .build();
+    let item: Item = from_bytes(&empty).unwrap();
+    assert_eq!(
+        item,
+        Item {
+            tag: Some(Value::Compound(HashMap::new())),
+            list: Some(vec![]),
+        }
+    );
+
+    let non_empty = Builder::new()
+        .start_compound("")
+        .start_compound("tag")
+        .int("Damage", 3)
+        .end_compound()
+        .end_compound()
+        .build();
+    let item: Item = from_bytes(&non_empty).unwrap();
+    assert_eq!(
+        item.tag,
+        Some(Value::Compound(HashMap::from([(
+            "Damage".to_string(),
+            Value::Int(3)
+        )])))
+    );
+}
+
+#[test]
+fn read_root_header_of_documents() {
+    let payload = Builder::new()
+        .start_compound("Level")
+        .int("x", 1)
+        .end_compound()
+        .build();
+
+    let (tag, name, len) = read_root_header(&payload).unwrap();
+    assert_eq!((tag, name.as_str(), len), (Tag::Compound, "Level", 8));
+
+    // The rest of the data follows the header.
+    assert_eq!(payload[len], Tag::Int as u8);
+
+    let payload = Builder::new()
+        .tag(Tag::List)
+        .name("")
+        .tag(Tag::Int)
+        .int_payload(0)
+        .build();
+    let (tag, name, len) = read_root_header(&payload).unwrap();
+    assert_eq!((tag, name.as_str(), len), (Tag::List, "", 3));
+
+    assert_eq!(
+        read_root_header(&[0]).unwrap(),
+        (Tag::End, String::new(), 1)
+    );
+    assert!(read_root_header(&[]).is_err());
+    assert!(read_root_header(&[10, 0, 5, b'a']).is_err());
+    assert!(read_root_header(&[13]).is_err());
+}
+
+#[test]
+fn into_serde_json_value() {
+    let v = crate::nbt!({
+        "DataVersion": 3465,
+        "Pos": [1.5, 64.0, -3.0],
+        "Name": "pig",
+        "Items": [{"Count": 1_i8}],
+        "Empty": [],
+        "Data": {"bytes": [B; 1, -1], "ints": [I; 2], "longs": [L; -3]},
+    });
+    let payload = to_bytes(&v).unwrap();
+
+    // Arrays are not lists by default.
+    assert!(from_bytes::<serde_json::Value>(&payload).is_err());
+
+    let opts = DeOpts::new().arrays_as_lists(true);
+    let json: serde_json::Value = from_bytes_with_opts(&payload, opts.clone()).unwrap();
+    assert_eq!(
+        json,
+        from_reader_with_opts::<_, serde_json::Value>(payload.as_slice(), opts.clone()).unwrap()
+    );
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "DataVersion": 3465,
+            "Pos": [1.5, 64.0, -3.0],
+            "Name": "pig",
+            "Items": [{"Count": 1}],
+            "Empty": [],
+            "Data": {"bytes": [1, -1], "ints": [2], "longs": [-3]},
+        })
+    );
+
+    let map: serde_json::Map<String, serde_json::Value> =
+        from_bytes_with_opts(&payload, opts.clone()).unwrap();
+    assert_eq!(map.len(), 6);
+
+    // The value types still keep arrays apart from lists.
+    let value: Value = from_bytes_with_opts(&payload, opts.clone()).unwrap();
+    assert_eq!(value, v);
+    let value: HashMap<String, Value> = from_bytes_with_opts(&payload, opts.clone()).unwrap();
+    assert_eq!(value["Data"], *v.get_path(&["Data"]).unwrap());
+
+    // Array types still deserialize as arrays.
+    #[derive(Deserialize)]
+    struct Data<'a> {
+        bytes: ByteArray,
+        ints: IntArray,
+        #[serde(borrow)]
+        longs: borrow::LongArray<'a>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct V<'a> {
+        #[serde(borrow)]
+        data: Data<'a>,
+    }
+    let v: V = from_bytes_with_opts(&payload, opts).unwrap();
+    assert_eq!(v.data.bytes, ByteArray::new(vec![1, -1]));
+    assert_eq!(v.data.ints, IntArray::new(vec![2]));
+    assert_eq!(v.data.longs.get(0), Some(-3));
+}
+
+#[test]
+fn flattened_values_keep_arrays() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct V {
+        id: String,
+        #[serde(flatten)]
+        other: HashMap<String, Value>,
+    }
+
+    let payload = to_bytes(&crate::nbt!({
+        "id": "minecraft:zombie",
+        "UUID": [I; 1, 2, 3, 4],
+        "Data": {"longs": [L; 5]},
+    }))
+    .unwrap();
+
+    let v: V = from_all(&payload);
+    assert_eq!(v.other["UUID"], crate::nbt!([I; 1, 2, 3, 4]));
+    assert_eq!(v.other["Data"], crate::nbt!({"longs": [L; 5]}));
+
+    // serde buffers flattened values without asking for a type, so with
+    // arrays as lists it is given lists.
+    let opts = DeOpts::new().arrays_as_lists(true);
+    let v: V = from_bytes_with_opts(&payload, opts).unwrap();
+    assert_eq!(v.other["UUID"], crate::nbt!([1, 2, 3, 4]));
+}
+
+#[test]
+fn into_serde_json_value_with_strict_arrays() {
+    let err = |v: Value| {
+        let payload = to_bytes(&crate::nbt!({ "a": v })).unwrap();
+        from_bytes::<serde_json::Value>(&payload)
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(
+        err(crate::nbt!([B; 1])),
+        "a: invalid type: byte array, expected any valid JSON value"
+    );
+    assert_eq!(
+        err(crate::nbt!([I; 1])),
+        "a: invalid type: int array, expected any valid JSON value"
+    );
+    assert_eq!(
+        err(crate::nbt!([L; 1])),
+        "a: invalid type: long array, expected any valid JSON value"
+    );
+}
+
+#[test]
+fn nonzero_integers() {
+    use std::num::{NonZeroI32, NonZeroI64, NonZeroU8};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct V {
+        stack_id: NonZeroI32,
+        count: NonZeroU8,
+        seed: Option<NonZeroI64>,
+    }
+
+    let value = crate::nbt!({"stack_id": -5, "count": 3_i8, "seed": 7_i64});
+    let v: V = from_bytes(&to_bytes(&value).unwrap()).unwrap();
+    assert_eq!(v.stack_id.get(), -5);
+    assert_eq!(v.count.get(), 3);
+    assert_eq!(v.seed.map(NonZeroI64::get), Some(7));
+
+    // Serializing gives the plain integers back.
+    assert_eq!(from_bytes::<Value>(&to_bytes(&v).unwrap()).unwrap(), value);
+
+    let payload = to_bytes(&crate::nbt!({"stack_id": 0, "count": 3_i8})).unwrap();
+    let err = from_bytes::<V>(&payload).unwrap_err();
+    assert_eq!(err.path(), "stack_id");
+
+    let payload = to_bytes(&crate::nbt!({"stack_id": 1, "count": 0_i8})).unwrap();
+    assert!(from_bytes::<V>(&payload).is_err());
+}
+
+#[test]
+fn internally_tagged_enum() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(tag = "id")]
+    enum Entity {
+        #[serde(rename = "minecraft:zombie")]
+        Zombie {
+            #[serde(rename = "Pos")]
+            pos: Vec<f64>,
+            #[serde(rename = "UUID")]
+            uuid: IntArray,
+            #[serde(rename = "IsBaby", deserialize_with = "crate::de::deserialize_bool")]
+            is_baby: bool,
+            #[serde(rename = "CustomName")]
+            custom_name: Option<String>,
+        },
+        #[serde(rename = "minecraft:bat")]
+        Bat {
+            #[serde(rename = "BatFlags")]
+            bat_flags: i8,
+        },
+    }
+
+    let zombie = to_bytes(&nbt!({
+        "id": "minecraft:zombie",
+        "Pos": [1.0, 64.0, -2.5],
+        "UUID": [I; 1, 2, 3, 4],
+        "IsBaby": 1_i8,
+    }))
+    .unwrap();
+    let bat = to_bytes(&nbt!({"BatFlags": 2_i8, "id": "minecraft:bat"})).unwrap();
+    let creeper = to_bytes(&nbt!({"id": "minecraft:creeper"})).unwrap();
+
+    assert_eq!(
+        from_all::<Entity>(&zombie),
+        Entity::Zombie {
+            pos: vec![1.0, 64.0, -2.5],
+            uuid: IntArray::new(vec![1, 2, 3, 4]),
+            is_baby: true,
+            custom_name: None,
+        }
+    );
+    assert_eq!(from_all::<Entity>(&bat), Entity::Bat { bat_flags: 2 });
+    assert!(from_bytes::<Entity>(&creeper).is_err());
+}
+
+#[test]
+fn compounds_as_btree_maps_are_sorted() {
+    use std::collections::BTreeMap;
+
+    let payload = Builder::new()
+        .start_compound("")
+        .start_compound("zombie")
+        .int("Health", 20)
+        .int("Air", 300)
+        .end_compound()
+        .start_compound("bat")
+        .int("BatFlags", 2)
+        .end_compound()
+        .start_list("list", Tag::Compound, 1)
+        .int("z", 1)
+        .int("a", 2)
+        .end_anon_compound()
+        .end_compound()
+        .build();
+
+    let v = from_all::<BTreeMap<String, Value>>(&payload);
+    assert_eq!(v.keys().collect::<Vec<_>>(), ["bat", "list", "zombie"]);
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Mobs {
+        zombie: BTreeMap<String, i32>,
+        bat: BTreeMap<String, i32>,
+        list: Vec<BTreeMap<String, i32>>,
+    }
+
+    let v = from_all::<Mobs>(&payload);
+    assert_eq!(v.zombie.keys().collect::<Vec<_>>(), ["Air", "Health"]);
+    assert_eq!(v.bat["BatFlags"], 2);
+    assert_eq!(v.list[0].keys().collect::<Vec<_>>(), ["a", "z"]);
+
+    // Written back, the keys stay in order.
+    let sorted = Builder::new()
+        .start_compound("")
+        .start_compound("bat")
+        .int("BatFlags", 2)
+        .end_compound()
+        .start_compound("zombie")
+        .int("Air", 300)
+        .int("Health", 20)
+        .end_compound()
+        .end_compound()
+        .build();
+    let mobs = BTreeMap::from([("zombie", v.zombie), ("bat", v.bat)]);
+    assert_eq!(to_bytes(&mobs).unwrap(), sorted);
+}
+
+#[test]
+fn root_compound_as_map() {
+    use std::collections::BTreeMap;
+
+    // The root name is dropped, whatever it is.
+    let payload = Builder::new()
+        .start_compound("root name")
+        .start_compound("a")
+        .int("x", 1)
+        .end_compound()
+        .start_compound("b")
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let v = from_all::<HashMap<String, Value>>(&payload);
+    assert_eq!(v["a"], nbt!({"x": 1}));
+    assert_eq!(v["b"], nbt!({}));
+
+    let v = from_all::<HashMap<String, HashMap<String, i32>>>(&payload);
+    assert_eq!(v["a"]["x"], 1);
+    assert!(v["b"].is_empty());
+
+    let v = from_all::<BTreeMap<String, BTreeMap<String, Value>>>(&payload);
+    assert_eq!(v.keys().collect::<Vec<_>>(), ["a", "b"]);
+
+    let v: HashMap<&str, Value> = from_bytes(&payload).unwrap();
+    assert_eq!(v.len(), 2);
+
+    let empty = Builder::new().start_compound("").end_compound().build();
+    assert!(from_all::<HashMap<String, Value>>(&empty).is_empty());
+}
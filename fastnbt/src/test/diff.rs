@@ -0,0 +1,83 @@
+use crate::{
+    diff,
+    diff::{Change, Diff},
+};
+
+#[test]
+fn equal_values_have_no_diffs() {
+    let v = nbt!({"a": [1, 2], "b": {"c": "d"}, "e": [L; 1, 2]});
+    assert!(diff(&v, &v.clone()).is_empty());
+}
+
+#[test]
+fn nested_changes_have_paths() {
+    let before = nbt!({
+        "Level": {
+            "Sections": [{"Y": 0_i8}, {"Y": 1_i8, "Sky": [B; 1]}],
+            "Status": "features",
+        },
+    });
+    let after = nbt!({
+        "Level": {
+            "Sections": [{"Y": 0_i8}, {"Y": 1_i8, "Sky": [B; 2]}, {"Y": 2_i8}],
+            "Status": "full",
+        },
+    });
+
+    assert_eq!(
+        diff(&before, &after),
+        [
+            Diff {
+                path: "Level.Sections[1].Sky".to_string(),
+                change: Change::Changed {
+                    before: nbt!([B; 1]),
+                    after: nbt!([B; 2]),
+                },
+            },
+            Diff {
+                path: "Level.Sections[2]".to_string(),
+                change: Change::Added(nbt!({"Y": 2_i8})),
+            },
+            Diff {
+                path: "Level.Status".to_string(),
+                change: Change::Changed {
+                    before: nbt!("features"),
+                    after: nbt!("full"),
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn removed_list_elements_and_changed_types() {
+    let before = nbt!({"list": [1, 2, 3], "ty": 1});
+    let after = nbt!({"list": [1], "ty": 1_i64});
+
+    let report: Vec<_> = diff(&before, &after)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(report, ["- list[1]: 2", "- list[2]: 3", "~ ty: 1 -> 1L"]);
+}
+
+#[test]
+fn top_level_change_has_no_path() {
+    let report: Vec<_> = diff(&nbt!(1), &nbt!({"a": [1_i8]}))
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(report, ["~ 1 -> {\"a\": [1b]}"]);
+}
+
+#[test]
+fn floats_are_compared_by_bits() {
+    let v = nbt!({"nan": f64::NAN, "list": [f32::NAN]});
+    assert!(diff(&v, &v.clone()).is_empty());
+
+    let report: Vec<_> = diff(&nbt!([0.0]), &nbt!([-0.0]))
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(report, ["~ [0]: 0.0d -> -0.0d"]);
+}
@@ -1,6 +1,16 @@
-use std::{collections::HashMap, iter::FromIterator};
+use std::{borrow::Cow, collections::HashMap, iter::FromIterator};
 
-use crate::{error::Result, from_bytes, test::builder::Builder, Tag, Value};
+use serde::{de::IgnoredAny, Deserialize};
+
+use crate::{
+    borrow,
+    error::Result,
+    from_bytes, from_bytes_with_opts, from_reader, from_reader_with_opts,
+    intern::{from_bytes_interned, StringPool},
+    stream::{self, Parser},
+    test::builder::Builder,
+    ByteArray, DeOpts, Endian, IntArray, LongArray, Tag, Value,
+};
 
 /// Bugs found via cargo-fuzz.
 
@@ -60,3 +70,154 @@ fn compound_using_nbt_token_key() {
     ];
     assert!(from_bytes::<Value>(data).is_err());
 }
+
+#[test]
+fn ignored_list_of_end() {
+    #[derive(Deserialize)]
+    struct V {}
+
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("unknown", Tag::End, 2)
+        .end_compound()
+        .build();
+
+    let err = from_bytes::<V>(&input).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "unknown: unexpected list of type 'end', which is not supported"
+    );
+    assert!(from_reader::<_, IgnoredAny>(&*input).is_err());
+}
+
+#[test]
+fn stream_list_of_end() {
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("", Tag::End, 1)
+        .end_compound()
+        .build();
+
+    let mut parser = Parser::new(&*input);
+    let err = std::iter::from_fn(|| Some(parser.next()))
+        .find_map(|v| v.err())
+        .unwrap();
+    assert!(err.to_string().contains("list of type 'end'"), "{}", err);
+}
+
+#[test]
+fn negative_lengths() {
+    let byte_array = Builder::new()
+        .start_compound("")
+        .tag(Tag::ByteArray)
+        .name("a")
+        .int_payload(-1)
+        .end_compound()
+        .build();
+    let list = Builder::new()
+        .start_compound("")
+        .start_list("a", Tag::Int, -5)
+        .end_compound()
+        .build();
+
+    for input in [byte_array, list] {
+        assert!(from_bytes::<Value>(&input).is_err());
+        assert!(from_reader::<_, Value>(&*input).is_err());
+        assert!(from_bytes::<IgnoredAny>(&input).is_err());
+
+        let mut parser = Parser::new(&*input);
+        parser.next().unwrap();
+        assert!(parser.next().is_err());
+    }
+}
+
+#[test]
+fn huge_array_length_from_reader() {
+    // The length is a lie, and reading from a reader should not allocate for
+    // all of it before finding that out.
+    let input = Builder::new()
+        .start_compound("")
+        .tag(Tag::LongArray)
+        .name("a")
+        .int_payload(i32::MAX)
+        .long_payload(1)
+        .build();
+
+    assert!(from_reader::<_, Value>(&*input).is_err());
+    assert!(from_reader::<_, LongArray>(&*input).is_err());
+
+    let mut parser = Parser::new(&*input);
+    parser.next().unwrap();
+    assert!(parser.next().is_err());
+}
+
+/// Every way to parse `data` that takes untrusted input. None of them may
+/// panic, whatever the input.
+fn parse_every_way(data: &[u8]) {
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Typed<'a> {
+        #[serde(borrow)]
+        ints: Option<borrow::IntArray<'a>>,
+        #[serde(borrow)]
+        longs: Option<borrow::LongArray<'a>>,
+        #[serde(borrow)]
+        bytes: Option<borrow::ByteArray<'a>>,
+        #[serde(borrow)]
+        str: Option<Cow<'a, str>>,
+        grid: Option<Vec<Vec<i32>>>,
+        int_array: Option<IntArray>,
+        long_array: Option<LongArray>,
+        byte_array: Option<ByteArray>,
+        #[serde(rename = "UUID")]
+        uuid: Option<u128>,
+        b: Option<bool>,
+        #[serde(rename = "Items")]
+        items: Option<Vec<HashMap<String, IgnoredAny>>>,
+    }
+
+    let opts = [
+        DeOpts::new(),
+        DeOpts::network_nbt(),
+        DeOpts::new().endianness(Endian::Little),
+        DeOpts::new().arrays_as_lists(true),
+    ];
+    for opts in opts {
+        let _ = from_bytes_with_opts::<Value>(data, opts.clone());
+        let _ = from_bytes_with_opts::<Typed>(data, opts.clone());
+        let _ = from_bytes_with_opts::<IgnoredAny>(data, opts.clone());
+        let _ = from_reader_with_opts::<_, Value>(data, opts.clone());
+        let _ = from_reader_with_opts::<_, IgnoredAny>(data, opts);
+    }
+    let _ = from_bytes_interned(data, &mut StringPool::new());
+
+    let mut parser = Parser::new(data).with_path_tracking(true);
+    while parser.next_ref().is_ok() {}
+    let _ = stream::to_pretty_snbt(data, std::io::sink(), 2);
+}
+
+#[test]
+fn corpus_never_panics() {
+    // Seeds for cargo-fuzz too, eg `cargo fuzz run deserialize_value
+    // ../fastnbt/src/test/resources/fuzz`. Add crashing inputs it finds here.
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/test/resources/fuzz");
+    let mut count = 0;
+
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let data = std::fs::read(entry.unwrap().path()).unwrap();
+        count += 1;
+
+        for end in 0..=data.len() {
+            parse_every_way(&data[..end]);
+        }
+        for i in 0..data.len() {
+            for b in [0x00, 0x80, 0xff] {
+                let mut data = data.clone();
+                data[i] = b;
+                parse_every_way(&data);
+            }
+        }
+    }
+
+    assert!(count > 0);
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::{
+    from_bytes,
+    intern::{from_bytes_interned, InternedValue, StringPool},
+    to_bytes, Value,
+};
+
+use super::resources::CHUNK_RAW;
+
+#[test]
+fn matches_value() {
+    let mut pool = StringPool::new();
+    let interned = from_bytes_interned(CHUNK_RAW, &mut pool).unwrap();
+    let value: Value = from_bytes(CHUNK_RAW).unwrap();
+    assert_eq!(interned.to_value(), value);
+
+    let v = nbt!({"b": [B; 1, 2], "i": [I; 3], "l": [L; 4], "s": "x", "list": [{}]});
+    let interned = from_bytes_interned(&to_bytes(&v).unwrap(), &mut pool).unwrap();
+    assert_eq!(interned.to_value(), v);
+    assert_eq!(interned.tag(), crate::Tag::Compound);
+}
+
+#[test]
+fn strings_are_shared_between_values() {
+    let data = to_bytes(&nbt!({
+        "palette": [{"Name": "minecraft:stone"}, {"Name": "minecraft:dirt"}],
+        "other": "minecraft:stone",
+    }))
+    .unwrap();
+
+    let mut pool = StringPool::new();
+    let a = from_bytes_interned(&data, &mut pool).unwrap();
+    let b = from_bytes_interned(&data, &mut pool).unwrap();
+
+    // palette, other, Name and the two block names.
+    assert_eq!(pool.len(), 5);
+
+    let string = |v: Option<&InternedValue>| match v {
+        Some(InternedValue::String(s)) => s.clone(),
+        v => panic!("not a string: {:?}", v),
+    };
+    let first_name = |v: &InternedValue| match v.get("palette") {
+        Some(InternedValue::List(palette)) => string(palette[0].get("Name")),
+        v => panic!("not a list: {:?}", v),
+    };
+    assert!(Arc::ptr_eq(&first_name(&a), &first_name(&b)));
+    assert!(Arc::ptr_eq(&first_name(&a), &string(b.get("other"))));
+}
+
+#[test]
+fn remove_unused_keeps_strings_in_use() {
+    let mut pool = StringPool::new();
+    let kept = from_bytes_interned(&to_bytes(&nbt!({"a": "x"})).unwrap(), &mut pool).unwrap();
+    from_bytes_interned(&to_bytes(&nbt!({"b": "y"})).unwrap(), &mut pool).unwrap();
+    assert_eq!(pool.len(), 4);
+
+    pool.remove_unused();
+    assert_eq!(pool.len(), 2);
+    let x = pool.intern("x");
+    assert_eq!(pool.len(), 2);
+    assert!(matches!(kept.get("a"), Some(InternedValue::String(s)) if Arc::ptr_eq(s, &x)));
+    drop(x);
+
+    drop(kept);
+    pool.remove_unused();
+    assert!(pool.is_empty());
+}
@@ -0,0 +1,160 @@
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression};
+
+use crate::{
+    from_bytes,
+    minecraft::{read_player_dat, EntityChunk, Item, PlayerDat},
+    to_bytes, uuid, Value,
+};
+
+fn player() -> Value {
+    nbt!({
+        "DataVersion": 3465,
+        "Pos": [1.5, 64.0, -3.0],
+        "Motion": [0.0, -0.08, 0.0],
+        "Rotation": [90.0_f32, 0.0_f32],
+        "Dimension": "minecraft:the_nether",
+        "Health": 18.5_f32,
+        "foodLevel": 20,
+        "XpLevel": 3,
+        "XpTotal": 40,
+        "playerGameType": 0,
+        "SelectedItemSlot": 2,
+        "Inventory": [
+            {"Slot": 0_i8, "id": "minecraft:stone", "Count": 64_i8},
+            {"Slot": 1_i8, "id": "minecraft:diamond_sword", "Count": 1_i8, "tag": {"Damage": 3}},
+        ],
+        "EnderItems": [
+            {"Slot": 5_i8, "id": "minecraft:apple", "count": 3},
+        ],
+        "abilities": {"flying": 0_i8},
+        "UUID": [I; 1, 2, 3, 4],
+    })
+}
+
+#[test]
+fn read_gzipped_player_dat() {
+    let nbt = to_bytes(&player()).unwrap();
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&nbt).unwrap();
+    let gzipped = encoder.finish().unwrap();
+
+    let p: PlayerDat = read_player_dat(gzipped.as_slice()).unwrap();
+
+    assert_eq!(p.data_version, Some(3465));
+    assert_eq!(p.pos, (1.5, 64.0, -3.0));
+    assert_eq!(p.rotation, (90.0, 0.0));
+    assert_eq!(p.dimension.as_deref(), Some("minecraft:the_nether"));
+    assert_eq!(p.health, 18.5);
+    assert_eq!((p.food_level, p.xp_level, p.xp_total), (20, 3, 40));
+    assert_eq!((p.game_type, p.selected_item_slot), (0, 2));
+
+    assert_eq!(p.inventory.len(), 2);
+    assert_eq!(p.inventory[0].id, "minecraft:stone");
+    assert_eq!(p.inventory[0].count, 64);
+    assert_eq!(p.inventory[1].other["tag"], nbt!({"Damage": 3}));
+
+    // Items from 1.20.5 on have a lowercase count.
+    assert_eq!(
+        p.ender_items,
+        [Item {
+            slot: Some(5),
+            id: "minecraft:apple".to_string(),
+            count: 3,
+            other: Default::default(),
+        }]
+    );
+
+    // Unknown fields are kept, including arrays.
+    assert_eq!(p.other.len(), 2);
+    assert_eq!(p.other["abilities"], nbt!({"flying": 0_i8}));
+    assert_eq!(p.other["UUID"], nbt!([I; 1, 2, 3, 4]));
+
+    // Uncompressed data works too.
+    assert_eq!(read_player_dat(nbt.as_slice()).unwrap(), p);
+}
+
+#[test]
+fn read_old_player_dat() {
+    // Before 1.16 the dimension was a number, and before 1.9 there was no
+    // data version.
+    let mut v = player();
+    if let Value::Compound(c) = &mut v {
+        c.insert("Dimension".to_string(), nbt!(-1));
+        c.remove("DataVersion");
+        c.remove("EnderItems");
+    }
+
+    let p = read_player_dat(to_bytes(&v).unwrap().as_slice()).unwrap();
+    assert_eq!(p.dimension, None);
+    assert_eq!(p.data_version, None);
+    assert!(p.ender_items.is_empty());
+}
+
+#[test]
+fn round_trip_player_uuid() {
+    // The UUID tag as the game writes it for the player
+    // 069a79f4-44e9-4726-a5be-fca90e38aaf5, most significant int first.
+    const UUID_TAG: &[u8] = b"\x0b\x00\x04UUID\x00\x00\x00\x04\
+        \x06\x9a\x79\xf4\x44\xe9\x47\x26\xa5\xbe\xfc\xa9\x0e\x38\xaa\xf5";
+
+    let mut v = player();
+    if let Value::Compound(c) = &mut v {
+        c.remove("UUID");
+    }
+    let mut nbt = to_bytes(&v).unwrap();
+    let end = nbt.pop();
+    nbt.extend_from_slice(UUID_TAG);
+    nbt.extend(end);
+
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&nbt).unwrap();
+    let p = read_player_dat(encoder.finish().unwrap().as_slice()).unwrap();
+
+    let Value::IntArray(ints) = &p.other["UUID"] else {
+        panic!("UUID is not an IntArray: {:?}", p.other["UUID"]);
+    };
+    let u = uuid::from_int_array(ints).unwrap();
+    assert_eq!(u, 0x069a79f4_44e9_4726_a5be_fca90e38aaf5);
+
+    let written = to_bytes(&nbt!({ "UUID": uuid::to_int_array(u) })).unwrap();
+    assert_eq!(written, [b"\x0a\x00\x00", UUID_TAG, b"\x00"].concat());
+}
+
+#[test]
+fn read_entity_chunk() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Position": [I; -3, 7],
+        "Entities": [
+            {
+                "id": "minecraft:zombie",
+                "Pos": [-40.5, 64.0, 120.25],
+                "UUID": [I; 1, 2, 3, 4],
+                "Health": 20.0_f32,
+            },
+            {"id": "minecraft:item", "Pos": [-41.0, 63.0, 121.0]},
+        ],
+    });
+
+    let chunk: EntityChunk = from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    assert_eq!(chunk.data_version, 3465);
+    assert_eq!(chunk.position, (-3, 7));
+    assert_eq!(chunk.entities.len(), 2);
+
+    let zombie = &chunk.entities[0];
+    assert_eq!(zombie.id, "minecraft:zombie");
+    assert_eq!(zombie.pos, (-40.5, 64.0, 120.25));
+    assert_eq!(zombie.uuid(), Some(0x00000001_00000002_00000003_00000004));
+    assert_eq!(zombie.other["Health"], nbt!(20.0_f32));
+    assert_eq!(chunk.entities[1].uuid(), None);
+
+    // Chunks with no entities left are written with an empty list.
+    let empty = nbt!({"DataVersion": 3465, "Position": [I; 0, 0], "Entities": []});
+    let empty: EntityChunk = from_bytes(&to_bytes(&empty).unwrap()).unwrap();
+    assert!(empty.entities.is_empty());
+
+    let bad = nbt!({"DataVersion": 3465, "Position": [I; 0], "Entities": []});
+    assert!(from_bytes::<EntityChunk>(&to_bytes(&bad).unwrap()).is_err());
+}
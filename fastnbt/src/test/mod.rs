@@ -10,13 +10,28 @@ mod de;
 #[allow(clippy::float_cmp)]
 mod value;
 
+#[cfg(feature = "bumpalo")]
+mod arena;
+mod arrays;
+mod bitset;
 pub mod builder;
+#[cfg(feature = "chrono")]
+mod chrono_millis;
+mod diff;
 mod fuzz;
+mod intern;
 mod macros;
+#[cfg(feature = "minecraft")]
+mod minecraft;
 mod minecraft_chunk;
+mod primitives;
+mod raw;
 mod resources;
+mod schema;
 mod ser;
 mod stream;
+mod uuid;
+mod writer;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 struct Single<T: Serialize> {
@@ -26,6 +41,18 @@ struct Single<T: Serialize> {
 #[derive(Serialize, Deserialize)]
 struct Wrap<T: Serialize>(T);
 
+/// Drop a value without recursing, for values too deep to drop normally.
+fn drop_deep(value: crate::Value) {
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            crate::Value::List(v) => stack.extend(v),
+            crate::Value::Compound(v) => stack.extend(v.into_values()),
+            _ => {}
+        }
+    }
+}
+
 macro_rules! check_tags {
     {$($tag:ident = $val:literal),* $(,)?} => {
         $(
@@ -56,3 +83,104 @@ fn exhaustive_tag_check() {
         assert!(Tag::try_from(value).is_err())
     }
 }
+
+#[test]
+fn tag_table_matches_try_from() {
+    // The same table as benches/tag_decoding.rs compares the match against.
+    static TAGS: [Option<Tag>; 256] = {
+        let mut table = [None; 256];
+        let mut i = 0;
+        while i < Tag::ALL.len() {
+            table[i] = Some(Tag::ALL[i]);
+            i += 1;
+        }
+        table
+    };
+
+    for t in 0..=u8::MAX {
+        assert_eq!(TAGS[t as usize], Tag::try_from(t).ok(), "tag {}", t);
+    }
+}
+
+#[test]
+fn all_tags_round_trip() {
+    assert_eq!(Tag::all().count(), 13);
+    for (id, tag) in Tag::all().enumerate() {
+        assert_eq!(u8::from(tag), id as u8);
+        assert_eq!(Tag::try_from(u8::from(tag)), Ok(tag));
+    }
+    assert!(Tag::all().eq(Tag::ALL));
+}
+
+#[test]
+fn payload_sizes() {
+    assert_eq!(Tag::End.payload_size(), Some(0));
+    assert_eq!(Tag::Byte.payload_size(), Some(1));
+    assert_eq!(Tag::Short.payload_size(), Some(2));
+    assert_eq!(Tag::Int.payload_size(), Some(4));
+    assert_eq!(Tag::Long.payload_size(), Some(8));
+    assert_eq!(Tag::Float.payload_size(), Some(4));
+    assert_eq!(Tag::Double.payload_size(), Some(8));
+
+    for tag in [
+        Tag::ByteArray,
+        Tag::String,
+        Tag::List,
+        Tag::Compound,
+        Tag::IntArray,
+        Tag::LongArray,
+    ] {
+        assert_eq!(tag.payload_size(), None);
+    }
+}
+
+#[test]
+fn tag_predicates() {
+    for tag in Tag::all() {
+        let expected = match tag {
+            Tag::Byte | Tag::Short | Tag::Int | Tag::Long | Tag::Float | Tag::Double => {
+                (true, false, false)
+            }
+            Tag::ByteArray | Tag::IntArray | Tag::LongArray => (false, true, false),
+            Tag::List | Tag::Compound => (false, false, true),
+            Tag::End | Tag::String => (false, false, false),
+        };
+        assert_eq!(
+            (tag.is_scalar(), tag.is_array(), tag.is_container()),
+            expected,
+            "{}",
+            tag
+        );
+    }
+}
+
+#[test]
+fn tag_serde_as_id() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct V {
+        tag: Tag,
+    }
+
+    for id in 0..=12u8 {
+        let v = V {
+            tag: Tag::try_from(id).unwrap(),
+        };
+
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, format!(r#"{{"tag":{}}}"#, id));
+        assert_eq!(serde_json::from_str::<V>(&json).unwrap(), v);
+
+        let nbt = crate::to_bytes(&v).unwrap();
+        let value: crate::Value = crate::from_bytes(&nbt).unwrap();
+        assert_eq!(value, crate::nbt!({ "tag": id as i8 }));
+        assert_eq!(crate::from_bytes::<V>(&nbt).unwrap(), v);
+    }
+
+    // Any integer type works, but only for valid ids.
+    let nbt = crate::to_bytes(&crate::nbt!({"tag": 10})).unwrap();
+    assert_eq!(crate::from_bytes::<V>(&nbt).unwrap().tag, Tag::Compound);
+
+    assert!(serde_json::from_str::<V>(r#"{"tag":13}"#).is_err());
+    assert!(serde_json::from_str::<V>(r#"{"tag":-1}"#).is_err());
+    assert!(serde_json::from_str::<V>(r#"{"tag":"Byte"}"#).is_err());
+}
@@ -0,0 +1,113 @@
+use crate::{de::primitives::*, ByteArray, IntArray, LongArray, Tag};
+
+use super::builder::Builder;
+
+#[test]
+fn scalars_and_lengths() {
+    let data = Builder::new()
+        .byte_payload(-1)
+        .short_payload(300)
+        .int_payload(-5)
+        .long_payload(1 << 40)
+        .float_payload(1.5)
+        .double_payload(-2.25)
+        .build();
+
+    let (b, n) = read_byte(&data).unwrap();
+    assert_eq!((b, n), (-1, 1));
+    let data = &data[n..];
+    let (s, n) = read_short(data).unwrap();
+    assert_eq!((s, n), (300, 2));
+    let data = &data[n..];
+    let (i, n) = read_int(data).unwrap();
+    assert_eq!((i, n), (-5, 4));
+    let data = &data[n..];
+    let (l, n) = read_long(data).unwrap();
+    assert_eq!((l, n), (1 << 40, 8));
+    let data = &data[n..];
+    let (f, n) = read_float(data).unwrap();
+    assert_eq!((f, n), (1.5, 4));
+    let data = &data[n..];
+    let (d, n) = read_double(data).unwrap();
+    assert_eq!((d, n), (-2.25, 8));
+    assert_eq!(data.len(), n);
+
+    assert_eq!(read_tag(&[10, 0]).unwrap(), (Tag::Compound, 1));
+    assert!(read_tag(&[13]).is_err());
+}
+
+#[test]
+fn strings_are_modified_utf8() {
+    let data = Builder::new().string_payload("minecraft:stone").build();
+    assert_eq!(
+        read_string(&data).unwrap(),
+        ("minecraft:stone".to_string(), 17)
+    );
+
+    // Characters outside the basic multilingual plane are stored as a
+    // surrogate pair in Java's encoding.
+    let encoded = cesu8::to_java_cesu8("🐦");
+    let data = Builder::new()
+        .raw_str_len(encoded.len())
+        .raw_bytes(&encoded)
+        .build();
+    assert_eq!(read_string(&data).unwrap(), ("🐦".to_string(), 8));
+
+    let invalid = Builder::new().raw_str_len(1).raw_bytes(&[0xff]).build();
+    assert!(read_string(&invalid).is_err());
+}
+
+#[test]
+fn arrays() {
+    let data = Builder::new()
+        .int_payload(3)
+        .byte_array_payload(&[1, -2, 3])
+        .build();
+    assert_eq!(
+        read_byte_array(&data).unwrap(),
+        (ByteArray::new(vec![1, -2, 3]), 7)
+    );
+
+    let data = Builder::new()
+        .int_payload(2)
+        .int_array_payload(&[i32::MIN, 7])
+        .build();
+    assert_eq!(
+        read_int_array(&data).unwrap(),
+        (IntArray::new(vec![i32::MIN, 7]), 12)
+    );
+
+    let data = Builder::new()
+        .int_payload(1)
+        .long_array_payload(&[-1])
+        .build();
+    assert_eq!(
+        read_long_array(&data).unwrap(),
+        (LongArray::new(vec![-1]), 12)
+    );
+
+    let empty = Builder::new().int_payload(0).build();
+    assert_eq!(
+        read_long_array(&empty).unwrap(),
+        (LongArray::new(vec![]), 4)
+    );
+}
+
+#[test]
+fn truncated_and_negative_lengths_error() {
+    assert!(read_int(&[0, 0, 1]).is_err());
+    assert!(read_long(&[]).is_err());
+
+    let truncated = Builder::new().raw_str_len(10).raw_bytes(b"abc").build();
+    assert!(read_string(&truncated).is_err());
+
+    let truncated = Builder::new().int_payload(2).int_payload(1).build();
+    assert!(read_int_array(&truncated).is_err());
+
+    let negative = Builder::new().int_payload(-1).build();
+    let err = read_byte_array(&negative).unwrap_err();
+    assert_eq!(err.to_string(), "size was negative");
+
+    let huge = Builder::new().int_payload(i32::MAX).build();
+    assert!(read_long_array(&huge).is_err());
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    from_bytes, from_reader, from_value, raw::RawNbt, test::builder::Builder, to_bytes, to_value,
+    Tag, Value,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Chunk {
+    #[serde(rename = "DataVersion")]
+    data_version: i32,
+    #[serde(with = "crate::raw")]
+    sections: RawNbt,
+    #[serde(rename = "Heightmaps", with = "crate::raw")]
+    heightmaps: RawNbt,
+}
+
+fn chunk(data_version: i32) -> Vec<u8> {
+    Builder::new()
+        .start_compound("")
+        .int("DataVersion", data_version)
+        .start_list("sections", Tag::Compound, 2)
+        .byte("Y", -4)
+        .string("id", "minecraft:plains")
+        .end_anon_compound()
+        .string("id", "minecraft:desert")
+        .byte("Y", 20)
+        .end_anon_compound()
+        .start_compound("Heightmaps")
+        .long_array("WORLD_SURFACE", &[1, 2, 3])
+        .int("unknown", 7)
+        .end_compound()
+        .end_compound()
+        .build()
+}
+
+#[test]
+fn edit_one_field_keeps_the_rest_byte_identical() {
+    let input = chunk(3465);
+
+    let from_slice: Chunk = from_bytes(&input).unwrap();
+    let read: Chunk = from_reader(input.as_slice()).unwrap();
+    assert_eq!(from_slice, read);
+
+    let mut chunk_value = from_slice;
+    assert_eq!(chunk_value.sections.tag(), Tag::List);
+    assert_eq!(chunk_value.heightmaps.tag(), Tag::Compound);
+
+    chunk_value.data_version = 3700;
+    assert_eq!(to_bytes(&chunk_value).unwrap(), chunk(3700));
+}
+
+#[test]
+fn raw_payload_is_as_in_input() {
+    let input = Builder::new()
+        .start_compound("")
+        .short("a", 0x0102)
+        .string("b", "hi")
+        .end_compound()
+        .build();
+
+    #[derive(Deserialize)]
+    struct V {
+        a: RawNbt,
+        b: RawNbt,
+    }
+
+    let v: V = from_bytes(&input).unwrap();
+    assert_eq!(v.a, RawNbt::new(Tag::Short, vec![1, 2]));
+    assert_eq!(v.b.payload(), &[0, 2, b'h', b'i']);
+    assert_eq!(v.b.clone().into_payload(), vec![0, 2, b'h', b'i']);
+    assert_eq!(format!("{:?}", v.b), "RawNbt(String, 4 bytes)");
+}
+
+#[test]
+fn raw_elements_and_options() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct V {
+        list: Vec<RawNbt>,
+        present: Option<RawNbt>,
+        missing: Option<RawNbt>,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("list", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(2)
+        .start_compound("present")
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes(&input).unwrap();
+    assert_eq!(v, from_reader(input.as_slice()).unwrap());
+    assert_eq!(v.list[1], RawNbt::new(Tag::Int, vec![0, 0, 0, 2]));
+    assert_eq!(v.missing, None);
+
+    // Missing options serialize as nothing, so the rest is as it was.
+    assert_eq!(to_bytes(&v).unwrap(), input);
+}
+
+#[test]
+fn raw_elements_must_match_the_list() {
+    #[derive(Serialize)]
+    struct V {
+        list: Vec<RawNbt>,
+    }
+
+    let list = vec![
+        RawNbt::new(Tag::Int, vec![0, 0, 0, 1]),
+        RawNbt::new(Tag::Byte, vec![1]),
+    ];
+    let err = to_bytes(&V { list }).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "heterogeneous list: element 1 is byte, but the list is of int"
+    );
+}
+
+#[test]
+fn raw_root() {
+    let input = chunk(3465);
+
+    let raw: RawNbt = from_bytes(&input).unwrap();
+    assert_eq!(raw.tag(), Tag::Compound);
+    assert_eq!(raw, from_reader(input.as_slice()).unwrap());
+    assert_eq!(to_bytes(&raw).unwrap(), input);
+}
+
+#[test]
+fn raw_to_and_from_value() {
+    let input = Builder::new()
+        .start_compound("")
+        .int("DataVersion", 1)
+        .start_list("sections", Tag::Int, 1)
+        .int_payload(5)
+        .start_compound("Heightmaps")
+        .long_array("WORLD_SURFACE", &[1, 2, 3])
+        .end_compound()
+        .end_compound()
+        .build();
+    let chunk: Chunk = from_bytes(&input).unwrap();
+
+    let value = to_value(&chunk).unwrap();
+    assert_eq!(
+        value,
+        nbt!({
+            "DataVersion": 1,
+            "sections": [5],
+            "Heightmaps": {"WORLD_SURFACE": [L; 1, 2, 3]},
+        })
+    );
+    assert_eq!(from_value::<Chunk>(&value).unwrap(), chunk);
+
+    assert_eq!(chunk.sections.to_value().unwrap(), nbt!([5]));
+    assert_eq!(RawNbt::from_value(&nbt!([5])).unwrap(), chunk.sections);
+    assert_eq!(
+        RawNbt::from_value(&Value::Byte(3)).unwrap(),
+        RawNbt::new(Tag::Byte, vec![3])
+    );
+}
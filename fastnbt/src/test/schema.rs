@@ -0,0 +1,90 @@
+use crate::{
+    schema::{Mismatch, Shape},
+    Tag, Value,
+};
+
+fn section_shape() -> Shape {
+    Shape::compound([(
+        "Level",
+        Shape::compound([
+            ("Status", Shape::tag(Tag::String)),
+            (
+                "Sections",
+                Shape::list(Shape::compound([
+                    ("Y", Shape::tag(Tag::Byte)),
+                    ("BlockStates", Shape::Any),
+                ])),
+            ),
+        ]),
+    )])
+}
+
+#[test]
+fn matching_nested_value() {
+    let v = nbt!({
+        "Level": {
+            "Status": "full",
+            "Sections": [
+                {"Y": 0_i8, "BlockStates": [L; 1, 2]},
+                {"Y": 1_i8, "BlockStates": [], "Extra": 1},
+            ],
+        },
+        "DataVersion": 2730,
+    });
+
+    assert!(section_shape().matches(&v));
+    assert_eq!(section_shape().check(&v), Ok(()));
+}
+
+#[test]
+fn mismatch_reports_first_path() {
+    let v = nbt!({
+        "Level": {
+            "Status": "full",
+            "Sections": [
+                {"Y": 0_i8, "BlockStates": 1},
+                {"Y": 1, "BlockStates": 1},
+                {"Y": "two", "BlockStates": 1},
+            ],
+        },
+    });
+
+    assert_eq!(
+        section_shape().check(&v),
+        Err(Mismatch {
+            path: "Level.Sections[1].Y".to_string(),
+            reason: "expected byte, found int".to_string(),
+        })
+    );
+}
+
+#[test]
+fn missing_key() {
+    let v = nbt!({"Level": {"Sections": []}});
+    let err = section_shape().check(&v).unwrap_err();
+
+    assert_eq!(err.path, "Level.Status");
+    assert_eq!(err.to_string(), "at Level.Status: missing key");
+}
+
+#[test]
+fn list_length() {
+    let shape = Shape::list_of_len(3, Shape::tag(Tag::Double));
+
+    assert!(shape.matches(&nbt!([1.0, 2.0, 3.0])));
+    assert_eq!(
+        shape.check(&nbt!([1.0, 2.0])).unwrap_err().to_string(),
+        "expected list of length 3, found length 2"
+    );
+    assert_eq!(shape.check(&nbt!([1.0, 2.0, 3])).unwrap_err().path, "[2]");
+}
+
+#[test]
+fn top_level_mismatch() {
+    let err = section_shape().check(&Value::Int(1)).unwrap_err();
+    assert_eq!(err.path, "");
+    assert_eq!(err.to_string(), "expected compound, found int");
+
+    assert!(!Shape::list(Shape::Any).matches(&nbt!({})));
+    assert!(Shape::Any.matches(&nbt!({})));
+}
@@ -1,15 +1,15 @@
 use std::{collections::HashMap, io::Cursor, iter::FromIterator};
 
 use crate::{
-    borrow, from_bytes,
+    borrow, from_bytes, from_bytes_with_opts,
     test::{resources::CHUNK_RAW_WITH_ENTITIES, Single, Wrap},
-    to_bytes, to_bytes_with_opts, to_writer_with_opts, ByteArray, IntArray, LongArray, SerOpts,
-    Tag, Value,
+    to_bytes, to_bytes_with_opts, to_writer_with_opts, ByteArray, DeOpts, Endian, IntArray,
+    LongArray, SerOpts, Tag, Value,
 };
 use serde::{ser::SerializeMap, Deserialize, Serialize};
 use serde_bytes::{ByteBuf, Bytes};
 
-use super::builder::Builder;
+use super::{builder::Builder, drop_deep};
 
 #[test]
 fn simple_byte() {
@@ -958,3 +958,579 @@ fn serialize_networked_compound() {
         \x00"
     );
 }
+
+#[test]
+fn serialize_little_endian() {
+    #[derive(Serialize)]
+    struct Example {
+        int: i32,
+        str: &'static str,
+        ints: IntArray,
+        list: Vec<i16>,
+    }
+
+    let data = Example {
+        int: 0x01020304,
+        str: "hi",
+        ints: IntArray::new(vec![1, -1]),
+        list: vec![5],
+    };
+    let opts = SerOpts::new().endianness(Endian::Little);
+    let bytes = to_bytes_with_opts(&data, opts).unwrap();
+
+    assert_eq!(
+        bytes,
+        b"\
+        \x0a\x00\x00\
+            \x03\x03\x00int\x04\x03\x02\x01\
+            \x08\x03\x00str\x02\x00hi\
+            \x0b\x04\x00ints\x02\x00\x00\x00\x01\x00\x00\x00\xff\xff\xff\xff\
+            \x09\x04\x00list\x02\x01\x00\x00\x00\x05\x00\
+        \x00"
+    );
+}
+
+#[test]
+fn little_endian_value_roundtrip() {
+    let v = nbt!({
+        "byte": 1_i8,
+        "float": 1.5_f32,
+        "double": -2.5_f64,
+        "long": i64::MIN,
+        "bytes": [B; 1, 2, 3],
+        "ints": [I; 1, i32::MAX],
+        "longs": [L; i64::MAX, 7],
+        "list": [{"a": "b"}, {}],
+        "empty": [],
+    });
+
+    let bytes = to_bytes_with_opts(&v, SerOpts::new().endianness(Endian::Little)).unwrap();
+    assert_ne!(bytes, to_bytes(&v).unwrap());
+
+    let back: Value =
+        from_bytes_with_opts(&bytes, DeOpts::new().endianness(Endian::Little)).unwrap();
+    assert_eq!(back, v);
+}
+
+#[test]
+fn serialize_network_little_endian() {
+    #[derive(Serialize)]
+    struct Example {
+        int: i32,
+        long: i64,
+        str: &'static str,
+        longs: LongArray,
+        list: Vec<i16>,
+    }
+
+    let data = Example {
+        int: 300,
+        long: -1,
+        str: "hi",
+        longs: LongArray::new(vec![64, -1]),
+        list: vec![5],
+    };
+    let opts = SerOpts::new().endianness(Endian::NetworkLittle);
+    let bytes = to_bytes_with_opts(&data, opts).unwrap();
+
+    assert_eq!(
+        bytes,
+        b"\
+        \x0a\x00\
+            \x03\x03int\xd8\x04\
+            \x04\x04long\x01\
+            \x08\x03str\x02hi\
+            \x0c\x05longs\x04\x80\x01\x01\
+            \x09\x04list\x02\x02\x05\x00\
+        \x00"
+    );
+}
+
+#[test]
+fn network_little_endian_value_roundtrip() {
+    let v = nbt!({
+        "byte": 1_i8,
+        "int": i32::MIN,
+        "long": i64::MAX,
+        "double": -2.5_f64,
+        "bytes": [B; 1, 2, 3],
+        "ints": [I; 1, i32::MAX, i32::MIN],
+        "longs": [L; i64::MIN, 7],
+        "list": [{"a": "b"}, {}],
+        "empty": [],
+    });
+
+    let bytes = to_bytes_with_opts(&v, SerOpts::new().endianness(Endian::NetworkLittle)).unwrap();
+    let back: Value =
+        from_bytes_with_opts(&bytes, DeOpts::new().endianness(Endian::NetworkLittle)).unwrap();
+    assert_eq!(back, v);
+}
+
+#[test]
+fn serialize_sorted_keys() {
+    #[derive(Serialize)]
+    struct Inner {
+        z: i8,
+        a: Option<i8>,
+        m: ByteArray,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        list: Vec<Inner>,
+        #[serde(rename = "B")]
+        b: Inner,
+        empty: HashMap<String, i8>,
+    }
+
+    let inner = || Inner {
+        z: 1,
+        a: None,
+        m: ByteArray::new(vec![2]),
+    };
+    let v = Outer {
+        list: vec![inner()],
+        b: inner(),
+        empty: HashMap::new(),
+    };
+    let bytes = to_bytes_with_opts(&v, SerOpts::new().sort_keys(true)).unwrap();
+
+    let expected = Builder::new()
+        .start_compound("")
+        .start_compound("B")
+        .byte_array("m", &[2])
+        .byte("z", 1)
+        .end_compound()
+        .start_compound("empty")
+        .end_compound()
+        .start_list("list", Tag::Compound, 1)
+        .byte_array("m", &[2])
+        .byte("z", 1)
+        .end_anon_compound()
+        .end_compound()
+        .build();
+
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn serialize_sorted_keys_is_deterministic() {
+    let map: HashMap<String, i32> = (0..100).map(|i| (i.to_string(), i)).collect();
+    let other: HashMap<String, i32> = (0..100).rev().map(|i| (i.to_string(), i)).collect();
+
+    let opts = SerOpts::new().sort_keys(true);
+    let bytes = to_bytes_with_opts(&map, opts.clone()).unwrap();
+    assert_eq!(bytes, to_bytes_with_opts(&other, opts).unwrap());
+
+    let value: HashMap<String, i32> = from_bytes(&bytes).unwrap();
+    assert_eq!(value, map);
+}
+
+#[test]
+fn serialize_string_too_long_for_nbt() {
+    // The limit is on the encoded length, and each of these is 3 bytes.
+    let max = "\u{2603}".repeat(65535 / 3);
+    let bytes = to_bytes(&Single { val: max.clone() }).unwrap();
+    let v: Single<String> = from_bytes(&bytes).unwrap();
+    assert_eq!(v.val, max);
+
+    let too_long = format!("lore: {}", "a".repeat(65535));
+    let err = to_bytes(&Single { val: too_long }).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "string of 65541 bytes is longer than the NBT limit of 65535 bytes: \
+         \"lore: aaaaaaaaaaaaaaaaaaaaaaaaaa\"..."
+    );
+
+    let mut map = HashMap::new();
+    map.insert("k".repeat(70000), 1);
+    assert!(to_bytes(&map).is_err());
+}
+
+#[test]
+fn serialize_name_too_long_for_nbt() {
+    let name_err = |len| {
+        format!(
+            "name of {} bytes is longer than the NBT limit of 65535 bytes: \"{}\"...",
+            len,
+            "k".repeat(32)
+        )
+    };
+
+    let max = HashMap::from([("k".repeat(65535), 1)]);
+    let bytes = to_bytes(&max).unwrap();
+    assert_eq!(from_bytes::<HashMap<String, i32>>(&bytes).unwrap(), max);
+
+    let too_long = HashMap::from([("k".repeat(65536), 1)]);
+    let err = to_bytes(&too_long).unwrap_err();
+    assert_eq!(err.to_string(), name_err(65536));
+
+    // The same goes for the root name and keys of values.
+    let opts = SerOpts::new().root_name("k".repeat(70000));
+    let err = to_bytes_with_opts(&Single { val: 1 }, opts).unwrap_err();
+    assert_eq!(err.to_string(), name_err(70000));
+
+    let err = to_bytes(&Value::compound_from_iter([("k".repeat(65536), 1)])).unwrap_err();
+    assert_eq!(err.to_string(), name_err(65536));
+
+    // Network little endian has no limit.
+    let bytes = to_bytes_with_opts(&too_long, SerOpts::new().endianness(Endian::NetworkLittle));
+    assert!(bytes.is_ok());
+}
+
+#[test]
+fn serialize_integer_map_keys_as_strings() {
+    let registry: HashMap<i32, String> =
+        HashMap::from([(1, "stone".to_string()), (-7, "air".to_string())]);
+
+    let bytes = to_bytes(&registry).unwrap();
+    let value: Value = from_bytes(&bytes).unwrap();
+    assert_eq!(value, nbt!({"1": "stone", "-7": "air"}));
+
+    let back: HashMap<i32, String> = from_bytes(&bytes).unwrap();
+    assert_eq!(back, registry);
+
+    let unsigned: HashMap<u8, i8> = HashMap::from([(255, 1)]);
+    let bytes = to_bytes(&unsigned).unwrap();
+    assert_eq!(from_bytes::<HashMap<u8, i8>>(&bytes).unwrap(), unsigned);
+
+    // Keys must parse as the integer type.
+    assert!(from_bytes::<HashMap<i8, i8>>(&bytes).is_err());
+    let err = from_bytes::<HashMap<i32, i32>>(&to_bytes(&nbt!({"id": 1})).unwrap()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "id: invalid type: string \"id\", expected i32"
+    );
+    assert_eq!(err.path(), "id");
+
+    let wide: HashMap<u128, i8> = HashMap::from([(u128::MAX, 1)]);
+    let bytes = to_bytes(&wide).unwrap();
+    assert_eq!(
+        from_bytes::<Value>(&bytes).unwrap(),
+        nbt!({"340282366920938463463374607431768211455": 1_i8})
+    );
+    assert_eq!(from_bytes::<HashMap<u128, i8>>(&bytes).unwrap(), wide);
+    let value = crate::to_value(HashMap::from([(i128::MIN, 1_i8)])).unwrap();
+    assert_eq!(
+        crate::from_value::<HashMap<i128, i8>>(&value).unwrap(),
+        HashMap::from([(i128::MIN, 1)])
+    );
+
+    let floats: HashMap<String, HashMap<i64, f32>> =
+        HashMap::from([("a".to_string(), HashMap::from([(1, 0.5)]))]);
+    let bytes = to_bytes(&floats).unwrap();
+    assert_eq!(
+        from_bytes::<HashMap<String, HashMap<i64, f32>>>(&bytes).unwrap(),
+        floats
+    );
+}
+
+#[test]
+fn serialize_heterogeneous_list() {
+    let mixed = Value::List(vec![
+        Value::Int(1),
+        Value::Int(2),
+        Value::String("3".into()),
+    ]);
+    let err = to_bytes(&Value::Compound(HashMap::from([("l".to_string(), mixed)]))).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "heterogeneous list: element 2 is string, but the list is of int"
+    );
+
+    // Nested lists are checked separately from the outer list.
+    let nested = nbt!({"l": [[1_i8, 2_i8], [3_i8, "x"]]});
+    let err = to_bytes(&nested).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "heterogeneous list: element 1 is string, but the list is of byte"
+    );
+    let nested = nbt!({"l": [[1_i8], 2_i8]});
+    let err = to_bytes(&nested).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "heterogeneous list: element 1 is byte, but the list is of list"
+    );
+
+    // Homogeneous lists of lists are fine, even if their elements differ.
+    let fine = nbt!({"l": [[1_i8], ["a"], [{"k": 1}], []], "c": [{"a": 1}, {"b": "x"}]});
+    let bytes = to_bytes(&fine).unwrap();
+    assert_eq!(from_bytes::<Value>(&bytes).unwrap(), fine);
+    let sorted = to_bytes_with_opts(&fine, SerOpts::new().sort_keys(true)).unwrap();
+    assert_eq!(from_bytes::<Value>(&sorted).unwrap(), fine);
+}
+
+#[test]
+fn value_list_checked() {
+    assert_eq!(Value::list_checked(vec![]).unwrap(), Value::List(vec![]));
+    assert_eq!(
+        Value::list_checked(vec![nbt!({"a": 1}), nbt!({"b": "x"})]).unwrap(),
+        nbt!([{"a": 1}, {"b": "x"}])
+    );
+
+    let err = Value::list_checked(vec![nbt!(1.0), nbt!(2.0), nbt!(3.0_f32)]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "heterogeneous list: element 2 is float, but the list is of double"
+    );
+}
+
+#[test]
+fn list_of_compounds_declares_tag_once() {
+    let entities = Value::List(vec![
+        nbt!({"id": "minecraft:pig"}),
+        nbt!({"Pos": [1.0, 2.0, 3.0]}),
+    ]);
+    let v = nbt!({ "Entities": entities });
+
+    let bytes = to_bytes(&v).unwrap();
+    let expected = Builder::new()
+        .start_compound("")
+        .start_list("Entities", Tag::Compound, 2)
+        .string("id", "minecraft:pig")
+        .end_compound()
+        .start_list("Pos", Tag::Double, 3)
+        .double_payload(1.0)
+        .double_payload(2.0)
+        .double_payload(3.0)
+        .end_compound()
+        .end_compound()
+        .build();
+    assert_eq!(bytes, expected);
+    assert_eq!(from_bytes::<Value>(&bytes).unwrap(), v);
+
+    // Compounds of several keys round trip too, whatever order they are in.
+    let v = nbt!({
+        "Entities": [
+            {"id": "minecraft:pig", "Saddle": 1_i8, "Pos": [1.0, 2.0, 3.0]},
+            {"id": "minecraft:item", "Item": {"id": "minecraft:stone", "Count": 1_i8}},
+        ],
+    });
+    let bytes = to_bytes(&v).unwrap();
+    assert_eq!(from_bytes::<Value>(&bytes).unwrap(), v);
+}
+
+#[test]
+fn rooted_subtrees_are_standalone_documents() {
+    let section = nbt!({"Y": 3_i8});
+    let bs = crate::ser::to_bytes_rooted("section", &section).unwrap();
+    let expected = Builder::new()
+        .start_compound("section")
+        .byte("Y", 3)
+        .end_compound()
+        .build();
+    assert_eq!(bs, expected);
+
+    let list = nbt!([1_i8, 2_i8]);
+    let bs = crate::ser::to_bytes_rooted("heights", &list).unwrap();
+    let expected = Builder::new()
+        .start_compound("")
+        .start_list("heights", Tag::Byte, 2)
+        .byte_payload(1)
+        .byte_payload(2)
+        .end_compound()
+        .build();
+    assert_eq!(bs, expected);
+
+    let bs = crate::ser::to_bytes_rooted("Y", &Value::Int(7)).unwrap();
+    let expected = Builder::new()
+        .start_compound("")
+        .int("Y", 7)
+        .end_compound()
+        .build();
+    assert_eq!(bs, expected);
+}
+
+#[test]
+fn named_root_round_trips() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    struct Schematic {
+        version: i32,
+        width: i16,
+    }
+
+    let schem = Schematic {
+        version: 2,
+        width: 16,
+    };
+    let bs = crate::ser::to_bytes_named("Schematic", &schem).unwrap();
+    let expected = Builder::new()
+        .start_compound("Schematic")
+        .int("Version", 2)
+        .short("Width", 16)
+        .end_compound()
+        .build();
+    assert_eq!(bs, expected);
+
+    let (name, back) = crate::de::from_bytes_with_name::<Schematic>(&bs).unwrap();
+    assert_eq!(name, "Schematic");
+    assert_eq!(back, schem);
+
+    let (name, _) = crate::de::from_bytes_with_name::<Value>(&to_bytes(&schem).unwrap()).unwrap();
+    assert_eq!(name, "");
+    assert!(crate::de::from_bytes_with_name::<Value>(&[0]).is_err());
+}
+
+#[test]
+fn values_without_recursing_match_serde() {
+    let chunk: Value = from_bytes(CHUNK_RAW_WITH_ENTITIES).unwrap();
+    let values = [
+        chunk,
+        nbt!({}),
+        nbt!({"empty": [], "lists": [[1_i8], [], [{"a": [L; 1, -1]}]], "s": "\u{0}☃"}),
+        nbt!({"bytes": [B; 1, -1], "ints": [I; 3], "f": 1.5_f32, "d": -0.5}),
+    ];
+
+    let opts = [
+        SerOpts::new().sort_keys(true),
+        SerOpts::new().sort_keys(true).root_name("root"),
+        SerOpts::network_nbt().sort_keys(true),
+        SerOpts::new().sort_keys(true).endianness(Endian::Little),
+    ];
+
+    for v in &values {
+        for opts in &opts {
+            let expected = to_bytes_with_opts(v, opts.clone()).unwrap();
+            let bs = crate::ser::value_to_bytes(v, opts.clone()).unwrap();
+            assert_eq!(bs, expected);
+        }
+
+        let bs = crate::ser::value_to_bytes(v, SerOpts::new()).unwrap();
+        assert_eq!(&from_bytes::<Value>(&bs).unwrap(), v);
+    }
+
+    assert!(crate::ser::value_to_bytes(&nbt!([1]), SerOpts::new()).is_err());
+    let err = crate::ser::value_to_bytes(&nbt!({"l": [1, "a"]}), SerOpts::new()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "heterogeneous list: element 1 is string, but the list is of int"
+    );
+}
+
+#[test]
+fn deeply_nested_value_without_recursing() {
+    let depth = 100_000;
+    let mut deep = Value::Compound(HashMap::new());
+    for i in 0..depth {
+        deep = match i % 2 {
+            0 => Value::List(vec![deep]),
+            _ => Value::compound_from_iter([("a", deep)]),
+        };
+    }
+    assert_eq!(deep.depth(), depth + 1);
+
+    // Serializing through serde stops at the default limit, before it could
+    // overflow the stack.
+    let err = to_bytes(&deep).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "value is nested deeper than the maximum depth of 512"
+    );
+    assert!(crate::ser::value_to_bytes(&deep, SerOpts::new()).is_err());
+
+    // Up to the default limit serde is fine.
+    let mut shallow = Value::Compound(HashMap::new());
+    for _ in 1..512 {
+        shallow = Value::compound_from_iter([("a", shallow)]);
+    }
+    assert_eq!(shallow.depth(), 512);
+    let bs = to_bytes(&shallow).unwrap();
+    assert_eq!(
+        bs,
+        crate::ser::value_to_bytes(&shallow, SerOpts::new()).unwrap()
+    );
+
+    let opts = SerOpts::new().max_depth(usize::MAX);
+    let bs = crate::ser::value_to_bytes(&deep, opts).unwrap();
+    // Each level is the tag and name of an entry, or the element tag and
+    // length of a list, and compounds end with a tag.
+    assert_eq!(bs.len(), 3 + 1 + 5 * depth);
+
+    let opts = SerOpts::new().max_depth(depth + 1);
+    assert!(crate::ser::value_to_bytes(&deep, opts).is_ok());
+    let opts = SerOpts::new().max_depth(depth);
+    let err = crate::ser::value_to_bytes(&deep, opts).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "value is nested deeper than the maximum depth of 100000"
+    );
+
+    drop_deep(deep);
+}
+
+#[test]
+fn max_depth_through_serde() {
+    let values = [
+        nbt!({}),
+        nbt!({"a": 1, "b": [B; 1, 2]}),
+        nbt!({"a": []}),
+        nbt!({"a": {"b": {}}}),
+        nbt!({"a": [[1], []], "b": {"c": [{"d": {}}]}}),
+    ];
+
+    for v in &values {
+        let depth = v.depth();
+        for sort_keys in [false, true] {
+            let opts = SerOpts::new().sort_keys(sort_keys);
+            let expected = to_bytes_with_opts(v, opts.clone()).unwrap();
+            let bs = to_bytes_with_opts(v, opts.clone().max_depth(depth)).unwrap();
+            assert_eq!(bs, expected);
+
+            let err = to_bytes_with_opts(v, opts.clone().max_depth(depth - 1)).unwrap_err();
+            let value_err = crate::ser::value_to_bytes(v, opts.max_depth(depth - 1)).unwrap_err();
+            assert_eq!(err.to_string(), value_err.to_string());
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Chunk {
+        sections: Vec<Section>,
+    }
+
+    #[derive(Serialize)]
+    struct Section {
+        palette: Vec<String>,
+        data: LongArray,
+    }
+
+    let chunk = Chunk {
+        sections: vec![Section {
+            palette: vec!["minecraft:air".to_string()],
+            data: LongArray::new(vec![1, 2]),
+        }],
+    };
+    assert!(to_bytes_with_opts(&chunk, SerOpts::new().max_depth(4)).is_ok());
+    let err = to_bytes_with_opts(&chunk, SerOpts::new().max_depth(3)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "value is nested deeper than the maximum depth of 3"
+    );
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn to_bytes_mut_appends_to_buffer() {
+    use bytes::BytesMut;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Packet {
+        id: i32,
+        name: String,
+    }
+
+    let packet = Packet {
+        id: 3,
+        name: "test".to_owned(),
+    };
+
+    let mut buf = BytesMut::from(&[0xAB][..]);
+    crate::ser::to_bytes_mut(&packet, &mut buf).unwrap();
+
+    assert_eq!(buf[0], 0xAB);
+    assert_eq!(&buf[1..], to_bytes(&packet).unwrap());
+
+    let frame = buf.split_off(1).freeze();
+    let back: Packet = from_bytes(&frame).unwrap();
+    assert_eq!(back, packet);
+}
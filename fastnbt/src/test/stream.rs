@@ -1,5 +1,7 @@
 use super::builder::Builder;
-use crate::stream::{ErrorKind, Name, Parser, Result, Value};
+use crate::stream::{
+    count_tags, to_pretty_snbt, ErrorKind, Name, Parser, PathElem, Result, Value, ValueRef,
+};
 use crate::Tag;
 
 fn name(n: &str) -> Name {
@@ -437,3 +439,287 @@ fn list_of_compounds() -> Result<()> {
     assert!(matches!(parser.next(), Err(e) if e.is_eof()));
     Ok(())
 }
+
+#[test]
+fn current_path() -> Result<()> {
+    let payload = Builder::new()
+        .start_compound("")
+        .start_compound("Level")
+        .start_list("Sections", Tag::Compound, 2)
+        .byte("Y", 0)
+        .end_anon_compound()
+        .byte("Y", 1)
+        .start_list("Palette", Tag::String, 1)
+        .string_payload("stone")
+        .end_anon_compound()
+        .end_compound()
+        .int("DataVersion", 1)
+        .end_compound()
+        .build();
+
+    fn key(k: &str) -> PathElem {
+        PathElem::Key(k.to_owned())
+    }
+
+    let mut parser = Parser::new(payload.as_slice()).with_path_tracking(true);
+    let mut check = |expected: &[PathElem]| -> Result<()> {
+        parser.next()?;
+        assert_eq!(parser.current_path(), expected);
+        Ok(())
+    };
+
+    let sections = [key("Level"), key("Sections")];
+    let second = [key("Level"), key("Sections"), PathElem::Index(1)];
+
+    check(&[])?; // root
+    check(&[key("Level")])?;
+    check(&sections)?;
+    check(&[key("Level"), key("Sections"), PathElem::Index(0)])?;
+    check(&[key("Level"), key("Sections"), PathElem::Index(0), key("Y")])?;
+    check(&[key("Level"), key("Sections"), PathElem::Index(0)])?; // end
+    check(&second)?;
+    check(&[second.as_slice(), &[key("Y")]].concat())?;
+    check(&[second.as_slice(), &[key("Palette")]].concat())?;
+    check(&[second.as_slice(), &[key("Palette"), PathElem::Index(0)]].concat())?;
+    check(&[second.as_slice(), &[key("Palette")]].concat())?; // list end
+    check(&second)?; // end
+    check(&sections)?; // list end
+    check(&[key("Level")])?; // end
+    check(&[key("DataVersion")])?;
+    check(&[])?; // root end
+
+    Ok(())
+}
+
+#[test]
+fn current_path_off_by_default() -> Result<()> {
+    let payload = Builder::new()
+        .start_compound("")
+        .byte("a", 1)
+        .end_compound()
+        .build();
+
+    let mut parser = Parser::new(payload.as_slice());
+    parser.next()?;
+    assert_eq!(parser.next()?, Value::Byte(name("a"), 1));
+    assert!(parser.current_path().is_empty());
+    Ok(())
+}
+
+#[test]
+fn next_ref_borrows_values() -> Result<()> {
+    let payload = Builder::new()
+        .start_compound("")
+        .string("id", "minecraft:chest")
+        .start_list("Items", Tag::Compound, 1)
+        .byte_array("bytes", &[1, -1])
+        .int_array("ints", &[2, 3])
+        .long_array("longs", &[4])
+        .end_anon_compound()
+        .end_compound()
+        .build();
+
+    let mut parser = Parser::new(payload.as_slice());
+
+    assert_eq!(parser.next_ref()?, ValueRef::Compound(Some("")));
+    assert_eq!(
+        parser.next_ref()?,
+        ValueRef::String(Some("id"), "minecraft:chest")
+    );
+    assert_eq!(
+        parser.next_ref()?,
+        ValueRef::List(Some("Items"), Tag::Compound, 1)
+    );
+    assert_eq!(parser.next_ref()?, ValueRef::Compound(None));
+    assert_eq!(
+        parser.next_ref()?,
+        ValueRef::ByteArray(Some("bytes"), &[1, -1])
+    );
+    assert_eq!(
+        parser.next_ref()?,
+        ValueRef::IntArray(Some("ints"), &[2, 3])
+    );
+    assert_eq!(parser.next_ref()?, ValueRef::LongArray(Some("longs"), &[4]));
+    assert_eq!(parser.next_ref()?, ValueRef::CompoundEnd);
+    assert_eq!(parser.next_ref()?, ValueRef::ListEnd);
+    assert_eq!(parser.next_ref()?, ValueRef::CompoundEnd);
+    assert!(parser.next_ref().unwrap_err().is_eof());
+    Ok(())
+}
+
+#[test]
+fn next_ref_decodes_modified_utf8() -> Result<()> {
+    // Both of these are encoded differently in Java's modified UTF-8.
+    let payload = Builder::new()
+        .start_compound("")
+        .string("😈", "null\0char")
+        .string("plain", "😈")
+        .string("after", "plain")
+        .end_compound()
+        .build();
+
+    let mut parser = Parser::new(payload.as_slice());
+
+    assert_eq!(parser.next_ref()?, ValueRef::Compound(Some("")));
+    assert_eq!(
+        parser.next_ref()?,
+        ValueRef::String(Some("😈"), "null\0char")
+    );
+    assert_eq!(parser.next_ref()?, ValueRef::String(Some("plain"), "😈"));
+    assert_eq!(parser.next_ref()?, ValueRef::String(Some("after"), "plain"));
+    assert_eq!(parser.next_ref()?, ValueRef::CompoundEnd);
+    Ok(())
+}
+
+#[test]
+fn next_ref_to_owned_matches_next() -> Result<()> {
+    let payload = Builder::new()
+        .start_compound("root")
+        .string("s", "value")
+        .start_list("l", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(2)
+        .int_array("ints", &[5])
+        .end_compound()
+        .build();
+
+    let mut by_ref = Parser::new(payload.as_slice()).with_path_tracking(true);
+    let mut owned = Parser::new(payload.as_slice()).with_path_tracking(true);
+
+    loop {
+        match (by_ref.next_ref(), owned.next()) {
+            (Ok(r), Ok(v)) => {
+                assert_eq!(r.into_value(), v);
+                assert_eq!(by_ref.current_path(), owned.current_path());
+            }
+            (Err(a), Err(b)) => {
+                assert!(a.is_eof() && b.is_eof());
+                break;
+            }
+            (a, b) => panic!("parsers differ: {:?} vs {:?}", a, b),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn count_tags_in_documents() -> Result<()> {
+    let payload = Builder::new()
+        .start_compound("root")
+        .string("s", "value")
+        .start_list("l", Tag::Compound, 2)
+        .byte("a", 1)
+        .end_compound()
+        .end_compound()
+        .int_array("ints", &[5, 6])
+        .end_compound()
+        .build();
+
+    let counts = count_tags(payload.as_slice())?;
+    assert_eq!(counts.len(), 5);
+    assert_eq!(counts[&Tag::Compound], 3);
+    assert_eq!(counts[&Tag::List], 1);
+    assert_eq!(counts[&Tag::String], 1);
+    assert_eq!(counts[&Tag::Byte], 1);
+    assert_eq!(counts[&Tag::IntArray], 1);
+
+    // Documents one after the other are all counted.
+    let twice = [payload.as_slice(), payload.as_slice()].concat();
+    assert_eq!(count_tags(twice.as_slice())?[&Tag::Compound], 6);
+
+    assert!(count_tags(&[][..])?.is_empty());
+
+    // Ending part way through is an error, even between values in a compound.
+    let err = count_tags(&payload[..payload.len() - 1]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof));
+    Ok(())
+}
+
+#[test]
+fn pretty_snbt() -> Result<()> {
+    let longs: Vec<i64> = (0..18).collect();
+    let payload = Builder::new()
+        .start_compound("root")
+        .string("id", "say \"hi\" \\o/")
+        .byte("b", 1)
+        .short("s", 2)
+        .long("l", 3)
+        .float("f", 0.5)
+        .double("d", 1.0)
+        .start_list("list", Tag::Compound, 2)
+        .int("a", 1)
+        .end_compound()
+        .end_compound()
+        .start_list("empty", Tag::End, 0)
+        .start_compound("nothing")
+        .end_compound()
+        .byte_array("bytes", &[1, -2])
+        .long_array("longs", &longs)
+        .end_compound()
+        .build();
+
+    let mut snbt = vec![];
+    to_pretty_snbt(payload.as_slice(), &mut snbt, 2)?;
+    let expected = r#"{
+  "id": "say \"hi\" \\o/",
+  "b": 1b,
+  "s": 2s,
+  "l": 3L,
+  "f": 0.5f,
+  "d": 1.0d,
+  "list": [
+    {
+      "a": 1
+    },
+    {}
+  ],
+  "empty": [],
+  "nothing": {},
+  "bytes": [B; 1b, -2b],
+  "longs": [L;
+    0L, 1L, 2L, 3L, 4L, 5L, 6L, 7L, 8L, 9L, 10L, 11L, 12L, 13L, 14L, 15L,
+    16L, 17L
+  ]
+}
+"#;
+    assert_eq!(String::from_utf8(snbt).unwrap(), expected);
+
+    // Documents one after the other are all printed.
+    let mut snbt = vec![];
+    let twice = [payload.as_slice(), payload.as_slice()].concat();
+    to_pretty_snbt(twice.as_slice(), &mut snbt, 0)?;
+    let unindented: String = expected
+        .lines()
+        .map(|l| l.trim_start().to_owned() + "\n")
+        .collect();
+    assert_eq!(String::from_utf8(snbt).unwrap(), unindented.repeat(2));
+
+    let err = to_pretty_snbt(&payload[..payload.len() - 1], &mut vec![], 2).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof));
+    Ok(())
+}
+
+#[test]
+fn io_errors_are_the_source() {
+    use std::error::Error as _;
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .end_compound()
+        .build();
+    let mut parser = Parser::new(&payload[..payload.len() - 2]);
+    let err = loop {
+        if let Err(e) = parser.next() {
+            break e;
+        }
+    };
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof));
+    let io = err
+        .source()
+        .and_then(|e| e.downcast_ref::<std::io::Error>());
+    assert_eq!(
+        io.map(|e| e.kind()),
+        Some(std::io::ErrorKind::UnexpectedEof)
+    );
+}
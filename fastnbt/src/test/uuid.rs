@@ -0,0 +1,62 @@
+use super::resources::CHUNK_RAW_WITH_ENTITIES;
+use crate::{uuid, IntArray, Value};
+
+#[test]
+fn most_significant_int_first() {
+    let u = 1 << 96 | 2 << 64 | 3 << 32 | 4;
+    assert_eq!(uuid::to_int_array(u), IntArray::new(vec![1, 2, 3, 4]));
+    assert_eq!(uuid::from_int_array(&[1, 2, 3, 4]), Some(u));
+
+    let u = u128::MAX;
+    assert_eq!(uuid::to_int_array(u), IntArray::new(vec![-1; 4]));
+    assert_eq!(uuid::from_int_array(&[-1; 4]), Some(u));
+}
+
+#[test]
+fn matches_u128_conversions() {
+    let u = 0xa597_ce71_739b_4e1d_ab7f_baa4_047b_ae4f;
+    assert_eq!(Value::IntArray(uuid::to_int_array(u)), Value::from(u));
+    assert_eq!(
+        Value::IntArray(uuid::to_int_array(u)),
+        crate::to_value(u).unwrap()
+    );
+}
+
+#[test]
+fn wrong_length_is_none() {
+    assert_eq!(uuid::from_int_array(&[]), None);
+    assert_eq!(uuid::from_int_array(&[1, 2, 3]), None);
+    assert_eq!(uuid::from_int_array(&[1, 2, 3, 4, 5]), None);
+}
+
+#[test]
+fn round_trip_real_uuids() {
+    // The attribute modifiers of the entities in this chunk have UUIDs stored
+    // the way the game stores them for players.
+    const HEADER: &[u8] = b"\x0b\x00\x04UUID\x00\x00\x00\x04";
+    const LEN: usize = HEADER.len() + 16;
+
+    let starts: Vec<_> = CHUNK_RAW_WITH_ENTITIES
+        .windows(HEADER.len())
+        .enumerate()
+        .filter(|(_, w)| *w == HEADER)
+        .map(|(i, _)| i)
+        .collect();
+    assert!(!starts.is_empty());
+
+    for start in starts {
+        let raw = &CHUNK_RAW_WITH_ENTITIES[start..start + LEN];
+        let ints: Vec<_> = raw[HEADER.len()..]
+            .chunks_exact(4)
+            .map(|c| i32::from_be_bytes(c.try_into().unwrap()))
+            .collect();
+
+        let u = uuid::from_int_array(&ints).unwrap();
+        let written = crate::to_bytes(&nbt!({ "UUID": uuid::to_int_array(u) })).unwrap();
+
+        let mut expected = vec![10, 0, 0];
+        expected.extend_from_slice(raw);
+        expected.push(0);
+        assert_eq!(written, expected);
+    }
+}
@@ -144,3 +144,27 @@ fn no_root_compound() {
     );
     assert_eq!(Ok(vec![1, 2, 3, 4]), from_value(&nbt!([1, 2, 3, 4])));
 }
+
+#[test]
+fn seq_from_arrays() {
+    assert_eq!(Ok(vec![1_u8, 2]), from_value(&nbt!([B; 1, 2])));
+    assert_eq!(Ok(vec![1, -2]), from_value(&nbt!([I; 1, -2])));
+    assert_eq!(Ok(vec![1_i64, 2]), from_value(&nbt!([L; 1, 2])));
+    assert!(from_value::<(i32, i32)>(&nbt!([I; 1, 2])).is_err());
+    assert!(from_value::<IntArray>(&nbt!([1, 2])).is_err());
+}
+
+#[test]
+fn byte_array_into_serde_bytes() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        owned: serde_bytes::ByteBuf,
+        #[serde(borrow)]
+        borrowed: &'a serde_bytes::Bytes,
+    }
+
+    let value = nbt!({"owned": [B; 1, -1], "borrowed": [B; 2]});
+    let v: V = V::deserialize(&value).unwrap();
+    assert_eq!(v.owned.as_slice(), &[1, 255]);
+    assert_eq!(&v.borrowed[..], &[2]);
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use crate::{IntArray, Value};
+
+#[test]
+fn content_hash_ignores_compound_order() {
+    let mut a = HashMap::new();
+    let mut b = HashMap::new();
+    for i in 0..100 {
+        a.insert(format!("key{}", i), Value::Int(i));
+        b.insert(format!("key{}", 99 - i), Value::Int(99 - i));
+    }
+    let a = Value::Compound(a);
+    let b = Value::Compound(b);
+
+    assert_eq!(a, b);
+    assert_eq!(a.content_hash(), b.content_hash());
+}
+
+#[test]
+fn content_hash_normalizes_floats() {
+    assert_eq!(
+        Value::Double(0.0).content_hash(),
+        Value::Double(-0.0).content_hash()
+    );
+    assert_eq!(
+        Value::Float(f32::NAN).content_hash(),
+        Value::Float(-f32::from_bits(0x7fc0_0001)).content_hash()
+    );
+    assert_ne!(
+        Value::Double(1.0).content_hash(),
+        Value::Double(-1.0).content_hash()
+    );
+}
+
+#[test]
+fn content_hash_covers_tags_and_boundaries() {
+    assert_ne!(Value::Int(1).content_hash(), Value::Long(1).content_hash());
+    assert_ne!(
+        Value::List(vec![Value::Int(1), Value::Int(2)]).content_hash(),
+        Value::IntArray(IntArray::new(vec![1, 2])).content_hash()
+    );
+    assert_ne!(
+        Value::List(vec![]).content_hash(),
+        Value::Compound(HashMap::new()).content_hash()
+    );
+    assert_ne!(
+        nbt!({"ab": "c"}).content_hash(),
+        nbt!({"a": "bc"}).content_hash()
+    );
+    assert_ne!(
+        nbt!({"a": [{"b": 1}]}).content_hash(),
+        nbt!({"a": [{"b": 1}, {}]}).content_hash()
+    );
+}
+
+#[test]
+fn content_hash_of_canonical_form() {
+    use sha2::{Digest, Sha256};
+
+    let mut expected = vec![10];
+    expected.extend(2_u64.to_be_bytes());
+    expected.extend(1_u64.to_be_bytes());
+    expected.push(b'a');
+    expected.extend([9, 1]);
+    expected.extend(1_u64.to_be_bytes());
+    expected.extend([1, 7]);
+    expected.extend(1_u64.to_be_bytes());
+    expected.push(b'b');
+    expected.push(8);
+    expected.extend(1_u64.to_be_bytes());
+    expected.push(b'x');
+
+    let hash: [u8; 32] = Sha256::digest(&expected).into();
+    assert_eq!(nbt!({"b": "x", "a": [7_i8]}).content_hash(), hash);
+}
+
+#[test]
+fn content_hash_of_deep_value() {
+    let deep = |depth| {
+        let mut v = Value::Int(0);
+        for _ in 0..depth {
+            v = Value::compound_from_iter([("a", Value::List(vec![v]))]);
+        }
+        v
+    };
+
+    // Deep enough to overflow the stack if hashing recursed.
+    let a = deep(100_000);
+    let b = deep(99_999);
+    assert_ne!(a.content_hash(), b.content_hash());
+
+    crate::test::drop_deep(a);
+    crate::test::drop_deep(b);
+}
@@ -1,9 +1,17 @@
-mod ser;
 mod de;
+#[cfg(feature = "sha2")]
+mod hash;
+mod ser;
+#[cfg(feature = "snbt")]
+mod snbt;
 
 use std::collections::HashMap;
 
-use crate::{from_bytes, to_bytes, Tag, Value};
+use crate::{
+    from_bytes, to_bytes,
+    value::{PruneOpts, PATCH_DELETE},
+    LongArray, Tag, Value,
+};
 
 use super::builder::Builder;
 
@@ -216,3 +224,559 @@ fn distinguish_compound() {
     let v: Value = from_bytes(&input).unwrap();
     assert_contains!(v, "a", Value::Compound(_));
 }
+
+#[test]
+fn depth() {
+    assert_eq!(Value::Int(1).depth(), 0);
+    assert_eq!(Value::LongArray(LongArray::new(vec![1, 2])).depth(), 0);
+    assert_eq!(Value::List(vec![]).depth(), 1);
+    assert_eq!(Value::Compound(HashMap::new()).depth(), 1);
+
+    let v = nbt!({
+        "a": 1,
+        "b": [[{"c": [1, 2]}], []],
+        "d": {},
+    });
+    assert_eq!(v.depth(), 5);
+}
+
+#[test]
+fn depth_of_deep_value() {
+    // Deep enough to overflow the stack if this recursed.
+    const DEPTH: usize = 100_000;
+
+    let mut v = Value::Int(0);
+    for _ in 0..DEPTH {
+        v = Value::List(vec![v]);
+    }
+
+    assert_eq!(v.depth(), DEPTH);
+    crate::test::drop_deep(v);
+}
+
+#[test]
+fn entry_on_compound() {
+    let mut v = Value::Compound(HashMap::new());
+
+    *v.entry("a").unwrap().or_insert(Value::Int(0)) = Value::Int(1);
+    v.entry("a").unwrap().or_insert(Value::Int(2));
+
+    assert_eq!(v.entry("a").unwrap().key(), "a");
+    assert_contains!(v, "a", Value::Int(1));
+}
+
+#[test]
+fn entry_on_non_compound_is_none() {
+    assert!(Value::Int(1).entry("a").is_none());
+    assert!(Value::List(vec![]).entry("a").is_none());
+}
+
+#[test]
+fn value_tag() {
+    assert_eq!(nbt!(1_i8).tag(), Tag::Byte);
+    assert_eq!(nbt!("a").tag(), Tag::String);
+    assert_eq!(nbt!([I; 1]).tag(), Tag::IntArray);
+    assert_eq!(nbt!([]).tag(), Tag::List);
+    assert_eq!(nbt!({}).tag(), Tag::Compound);
+}
+
+#[test]
+fn default_is_empty_compound() {
+    assert_eq!(Value::default(), Value::Compound(HashMap::new()));
+
+    let mut v = nbt!({"a": 1});
+    let taken = std::mem::take(&mut v);
+    assert_eq!(taken, nbt!({"a": 1}));
+    assert_eq!(v, nbt!({}));
+}
+
+#[test]
+fn list_take_and_swap_remove() {
+    let mut list = nbt!([1, 2, 3]);
+
+    assert_eq!(list.list_take(1), Some(Value::Int(2)));
+    assert_eq!(
+        list,
+        Value::List(vec![Value::Int(1), nbt!({}), Value::Int(3)])
+    );
+    assert_eq!(list.list_take(3), None);
+
+    assert_eq!(list.list_swap_remove(0), Some(Value::Int(1)));
+    assert_eq!(list, Value::List(vec![Value::Int(3), nbt!({})]));
+    assert_eq!(list.list_swap_remove(2), None);
+
+    let mut not_list = nbt!({"a": 1});
+    assert_eq!(not_list.list_take(0), None);
+    assert_eq!(not_list.list_swap_remove(0), None);
+}
+
+#[test]
+fn construct_from_iterators() {
+    let compound = Value::compound_from_iter([
+        ("a".to_string(), Value::Int(1)),
+        ("b".to_string(), nbt!("two")),
+        ("a".to_string(), Value::Int(3)),
+    ]);
+    assert_eq!(compound, nbt!({"a": 3, "b": "two"}));
+
+    let list = Value::list_from_iter(["x", "y"].into_iter().map(Value::from));
+    assert_eq!(list, nbt!(["x", "y"]));
+
+    assert_eq!(Value::list_from_iter(Vec::<Value>::new()), nbt!([]));
+    assert_eq!(
+        Value::compound_from_iter(HashMap::<String, Value>::new()),
+        nbt!({})
+    );
+}
+
+#[test]
+fn find_all_by_path() {
+    let v = nbt!({
+        "Level": {
+            "Sections": [
+                {"Palette": [{"Name": "stone"}, {"Name": "dirt"}]},
+                {"Palette": [{"Name": "air"}]},
+                {"NoPalette": 1},
+            ],
+        },
+        "Other": {"Name": "ignored"},
+    });
+
+    let names: Vec<_> = v
+        .find_all("Level/Sections/*/Palette/*/Name")
+        .into_iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(names, ["stone", "dirt", "air"]);
+
+    assert_eq!(
+        v.find_all("Level/Sections/1/Palette/0/Name"),
+        [&nbt!("air")]
+    );
+    assert_eq!(v.find_all(""), [&v]);
+    assert!(v.find_all("Level/Missing/*").is_empty());
+    assert!(v.find_all("Level/Sections/3").is_empty());
+    assert!(v.find_all("Level/Sections/x").is_empty());
+
+    let mut all_names: Vec<_> = v
+        .find_all("*/Name")
+        .into_iter()
+        .filter_map(Value::as_str)
+        .collect();
+    all_names.sort();
+    assert_eq!(all_names, ["ignored"]);
+}
+
+#[test]
+fn aggregate_by_path() {
+    let v = nbt!({
+        "Entities": [
+            {"id": "pig", "Health": 10.0_f32, "Age": 3},
+            {"id": "cow", "Health": 4.5_f32, "Age": 2_i16},
+            {"id": "pig", "Health": 1_i8},
+            {"id": 7},
+        ],
+    });
+
+    assert_eq!(v.sum_of_type("Entities/*/Health", Tag::Float), 14.5);
+    assert_eq!(v.sum_of_type("Entities/*/Health", Tag::Byte), 1.0);
+    assert_eq!(v.sum_of_type("Entities/*/Age", Tag::Int), 3.0);
+    assert_eq!(v.sum_of_type("Entities/*/id", Tag::String), 0.0);
+    assert_eq!(v.sum_of_type("Missing", Tag::Int), 0.0);
+
+    let counts = v.count_strings("Entities/*/id");
+    assert_eq!(counts, HashMap::from([("pig", 2), ("cow", 1)]));
+    assert!(v.count_strings("Entities/*/Health").is_empty());
+}
+
+#[test]
+fn get_path_follows_keys_and_indices() {
+    let mut v = nbt!({
+        "Level": {
+            "Sections": [{"Y": 0_i8}, {"Y": 1_i8, "*": "star"}],
+            "7": "key, not index",
+        },
+    });
+
+    for path in ["Level/Sections/1/Y", "Level/7", ""] {
+        let parts: Vec<_> = path.split('/').filter(|p| !p.is_empty()).collect();
+        assert_eq!(v.get_path(&parts), v.find_all(path).first().copied());
+    }
+
+    assert_eq!(
+        v.get_path(&["Level", "Sections", "1", "*"]),
+        Some(&nbt!("star"))
+    );
+    assert_eq!(v.get_path(&["Level", "Sections", "2"]), None);
+    assert_eq!(v.get_path(&["Level", "Sections", "-1"]), None);
+    assert_eq!(v.get_path(&["Level", "Sections", "0", "Y", "Z"]), None);
+    assert_eq!(v.get_path(&["Missing"]), None);
+
+    *v.get_path_mut(&["Level", "Sections", "0", "Y"]).unwrap() = nbt!(5_i8);
+    assert_eq!(
+        v.get_path(&["Level", "Sections", "0", "Y"]),
+        Some(&nbt!(5_i8))
+    );
+}
+
+#[test]
+fn value_is_empty() {
+    assert!(nbt!({}).is_empty());
+    assert!(nbt!([]).is_empty());
+    assert!(nbt!("").is_empty());
+    assert!(nbt!([B;]).is_empty());
+    assert!(nbt!([I;]).is_empty());
+    assert!(nbt!([L;]).is_empty());
+
+    assert!(!nbt!({"a": {}}).is_empty());
+    assert!(!nbt!([[]]).is_empty());
+    assert!(!nbt!("a").is_empty());
+    assert!(!nbt!([L; 0]).is_empty());
+
+    assert!(!nbt!(0_i8).is_empty());
+    assert!(!nbt!(0).is_empty());
+    assert!(!nbt!(0.0).is_empty());
+}
+
+#[test]
+fn flatten_to_paths() {
+    let v = nbt!({
+        "Level": {
+            "Sections": [{"Y": -4_i8, "Name": "a b"}, {"Y": 1_i8}],
+            "Empty": {},
+        },
+        "Longs": [L; 1, -2],
+        "Health": 20.5_f32,
+    });
+
+    let flat = v.flatten();
+    let flat: Vec<_> = flat.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    assert_eq!(
+        flat,
+        [
+            ("Health", "20.5"),
+            ("Level.Sections.0.Name", "a b"),
+            ("Level.Sections.0.Y", "-4"),
+            ("Level.Sections.1.Y", "1"),
+            ("Longs", "2"),
+        ]
+    );
+
+    let opts = crate::value::FlattenOpts::new()
+        .separator("/")
+        .expand_arrays(true);
+    let flat = nbt!({"a": {"b": [L; 1, -2]}}).flatten_with_opts(&opts);
+    assert_eq!(
+        flat,
+        [
+            ("a/b/0".to_string(), "1".to_string()),
+            ("a/b/1".to_string(), "-2".to_string()),
+        ]
+    );
+
+    // A scalar on its own has an empty path.
+    assert_eq!(nbt!(1).flatten(), [(String::new(), "1".to_string())]);
+}
+
+#[test]
+fn key_paths_merge_list_elements() {
+    let v = nbt!({
+        "Level": {
+            "Sections": [{"Y": -4_i8, "Data": [L; 1]}, {"Y": 1}],
+            "Empty": [],
+        },
+        "Nested": [[1, 2], ["a"]],
+    });
+
+    let paths = v.key_paths();
+    let paths: Vec<_> = paths
+        .iter()
+        .map(|(k, tags)| (k.as_str(), tags.iter().copied().collect::<Vec<_>>()))
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            ("Level", vec![Tag::Compound]),
+            ("Level.Empty", vec![Tag::List]),
+            ("Level.Sections", vec![Tag::List]),
+            ("Level.Sections.*", vec![Tag::Compound]),
+            ("Level.Sections.*.Data", vec![Tag::LongArray]),
+            ("Level.Sections.*.Y", vec![Tag::Byte, Tag::Int]),
+            ("Nested", vec![Tag::List]),
+            ("Nested.*", vec![Tag::List]),
+            ("Nested.*.*", vec![Tag::Int, Tag::String]),
+        ]
+    );
+
+    assert!(nbt!(1).key_paths().is_empty());
+}
+
+#[test]
+fn canonicalize_widens_integers() {
+    let mut v = nbt!({
+        "b": -1_i8,
+        "s": 300_i16,
+        "i": 70000,
+        "l": 1_i64,
+        "f": 1.0_f32,
+        "list": [[1_i8]],
+        "arr": [B; 1],
+    });
+
+    let mut int = v.clone();
+    int.canonicalize(Tag::Int);
+    assert_eq!(
+        int,
+        nbt!({
+            "b": -1,
+            "s": 300,
+            "i": 70000,
+            "l": 1_i64,
+            "f": 1.0_f32,
+            "list": [[1]],
+            "arr": [B; 1],
+        })
+    );
+
+    v.canonicalize(Tag::Short);
+    assert_eq!(v.find_all("b"), [&Value::Short(-1)]);
+    assert_eq!(v.find_all("i"), [&Value::Int(70000)]);
+
+    // Non-integer widths change nothing.
+    let before = v.clone();
+    v.canonicalize(Tag::Double);
+    v.canonicalize(Tag::Byte);
+    assert_eq!(v, before);
+}
+
+#[test]
+fn approx_eq_compares_floats_within_epsilon() {
+    let a = nbt!({
+        "Pos": [1.0, 64.000_000_1, -3.0],
+        "Rotation": [90.0_f32, 0.0_f32],
+        "nested": [{"f": 0.5_f32}],
+        "id": "minecraft:pig",
+    });
+    let b = nbt!({
+        "Pos": [1.0, 64.0, -3.0],
+        "Rotation": [90.000_01_f32, 0.0_f32],
+        "nested": [{"f": 0.5_f32}],
+        "id": "minecraft:pig",
+    });
+
+    assert_ne!(a, b);
+    assert!(a.approx_eq(&a, 0.0));
+    assert!(a.approx_eq(&b, 1e-3));
+    assert!(!a.approx_eq(&b, 1e-9));
+
+    // Everything other than floats must still match exactly.
+    assert!(!nbt!(1.0_f32).approx_eq(&nbt!(1.0), 1.0));
+    assert!(!nbt!([1, 2]).approx_eq(&nbt!([1]), 1.0));
+    assert!(!nbt!({"a": 1}).approx_eq(&nbt!({"b": 1}), 1.0));
+    assert!(!nbt!({"a": "x"}).approx_eq(&nbt!({"a": "y"}), 1.0));
+    assert!(!nbt!(f64::NAN).approx_eq(&nbt!(f64::NAN), 1.0));
+    assert!(nbt!(f64::INFINITY).approx_eq(&nbt!(f64::INFINITY), 0.0));
+}
+
+#[test]
+fn heap_size_counts_capacity() {
+    use std::mem::size_of;
+
+    assert_eq!(Value::Int(1).heap_size(), 0);
+    assert_eq!(Value::String(String::with_capacity(100)).heap_size(), 100);
+    assert_eq!(
+        Value::LongArray(LongArray::new(vec![0; 10])).heap_size(),
+        80
+    );
+
+    let list = Value::List(vec![Value::String("abc".into()), Value::Int(1)]);
+    assert_eq!(list.heap_size(), 2 * size_of::<Value>() + 3);
+
+    // Compounds count more than their entries and keys, for the map itself.
+    let compound = nbt!({"key": "abc"});
+    assert!(compound.heap_size() >= size_of::<(String, Value)>() + 6);
+
+    let chunk: Value = from_bytes(super::resources::CHUNK_RAW).unwrap();
+    assert!(chunk.heap_size() > super::resources::CHUNK_RAW.len());
+}
+
+#[test]
+fn coerce_to_converts_numbers() {
+    let coerced = |mut v: Value, tag| v.coerce_to(tag).map(|_| v);
+
+    assert_eq!(coerced(Value::Int(-128), Tag::Byte), Ok(Value::Byte(-128)));
+    assert_eq!(coerced(Value::Byte(5), Tag::Long), Ok(Value::Long(5)));
+    assert_eq!(
+        coerced(Value::Long(1 << 40), Tag::Double),
+        Ok(Value::Double((1_i64 << 40) as f64))
+    );
+    assert_eq!(coerced(Value::Double(3.0), Tag::Short), Ok(Value::Short(3)));
+    assert_eq!(
+        coerced(Value::Float(1.5), Tag::Double),
+        Ok(Value::Double(1.5))
+    );
+    assert_eq!(
+        coerced(Value::Double(0.25), Tag::Float),
+        Ok(Value::Float(0.25))
+    );
+    assert_eq!(coerced(Value::Short(7), Tag::Float), Ok(Value::Float(7.0)));
+    // Just past the largest float, but still rounding down to it.
+    assert_eq!(
+        coerced(Value::Double(f32::MAX as f64 * (1.0 + 1e-9)), Tag::Float),
+        Ok(Value::Float(f32::MAX))
+    );
+
+    let err = |v, tag| coerced(v, tag).unwrap_err().to_string();
+    assert_eq!(
+        err(Value::Long(40000), Tag::Short),
+        "cannot coerce long 40000 to short: out of range"
+    );
+    assert_eq!(
+        err(Value::Double(2.5), Tag::Int),
+        "cannot coerce double 2.5 to int: not a whole number"
+    );
+    assert_eq!(
+        err(Value::Float(f32::NAN), Tag::Long),
+        "cannot coerce float NaN to long: not a whole number"
+    );
+    assert_eq!(
+        err(Value::Double(1e39), Tag::Float),
+        "cannot coerce double 1000000000000000000000000000000000000000 to float: out of range"
+    );
+    assert_eq!(
+        err(Value::Double(1e19), Tag::Long),
+        "cannot coerce double 10000000000000000000 to long: out of range"
+    );
+    assert_eq!(
+        err(Value::Int(1), Tag::String),
+        "cannot coerce int to string"
+    );
+    assert_eq!(
+        err(Value::List(vec![]), Tag::Int),
+        "cannot coerce list to int"
+    );
+
+    // Coercing to the same tag works for any value.
+    let list = nbt!(["a"]);
+    assert_eq!(coerced(list.clone(), Tag::List), Ok(list));
+}
+
+#[test]
+fn prune_empty_removes_nested_empty_entries() {
+    let mut v = nbt!({
+        "keep": 1,
+        "zero": 0_i8,
+        "empty": {},
+        "nested": {"a": {"b": []}, "bytes": [B;], "longs": [L;]},
+        "list": [{}, {"c": {}, "d": 0.0}],
+        "name": "",
+    });
+    let original = v.clone();
+
+    v.prune_empty();
+    assert_eq!(
+        v,
+        nbt!({"keep": 1, "zero": 0_i8, "list": [{}, {"d": 0.0}], "name": ""})
+    );
+
+    let mut v = original.clone();
+    v.prune_empty_with_opts(&PruneOpts::new().zeros(true));
+    assert_eq!(v, nbt!({"keep": 1, "list": [{}, {}], "name": ""}));
+
+    let mut v = original;
+    v.prune_empty_with_opts(&PruneOpts::new().strings(true));
+    assert_eq!(v, nbt!({"keep": 1, "zero": 0_i8, "list": [{}, {"d": 0.0}]}));
+
+    // The value itself stays, even when empty.
+    let mut v = nbt!({"a": {}});
+    v.prune_empty();
+    assert_eq!(v, nbt!({}));
+}
+
+#[test]
+fn remap_ids_only_touches_named_fields() {
+    let mapping: HashMap<String, String> = [
+        ("minecraft:grass", "minecraft:short_grass"),
+        ("minecraft:chain", "minecraft:iron_chain"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+
+    let mut v = nbt!({
+        "sections": [
+            {"block_states": {"palette": [
+                {"Name": "minecraft:grass"},
+                {"Name": "minecraft:chain", "Properties": {"axis": "y"}},
+            ]}},
+            {"block_states": {"palette": [{"Name": "minecraft:air"}]}},
+        ],
+        "block_entities": [{
+            "id": "minecraft:chest",
+            "Items": [{"id": "minecraft:grass", "Count": 3_i8}],
+        }],
+        "id": "minecraft:chain",
+        "Name": ["minecraft:grass"],
+        "note": "minecraft:grass",
+    });
+
+    assert_eq!(v.remap_ids(&["Name", "id"], &mapping), 4);
+    assert_eq!(
+        v,
+        nbt!({
+            "sections": [
+                {"block_states": {"palette": [
+                    {"Name": "minecraft:short_grass"},
+                    {"Name": "minecraft:iron_chain", "Properties": {"axis": "y"}},
+                ]}},
+                {"block_states": {"palette": [{"Name": "minecraft:air"}]}},
+            ],
+            "block_entities": [{
+                "id": "minecraft:chest",
+                "Items": [{"id": "minecraft:short_grass", "Count": 3_i8}],
+            }],
+            "id": "minecraft:iron_chain",
+            "Name": ["minecraft:grass"],
+            "note": "minecraft:grass",
+        })
+    );
+
+    // Only the given field names are touched.
+    assert_eq!(v.remap_ids(&["note"], &mapping), 1);
+    assert_eq!(v.remap_ids(&[], &mapping), 0);
+}
+
+#[test]
+fn apply_patch_merges_replaces_and_deletes() {
+    let mut v = nbt!({
+        "id": "minecraft:zombie",
+        "Pos": [1.0, 64.0, 1.0],
+        "Fire": 40_i16,
+        "tag": {"display": {"Name": "Bob"}, "Damage": 3},
+        "scalar": 1,
+    });
+
+    v.apply_patch(&nbt!({
+        "Pos": [2.0],
+        "Fire": PATCH_DELETE,
+        "missing": PATCH_DELETE,
+        "tag": {"display": {"Lore": []}, "Damage": PATCH_DELETE},
+        "scalar": {"now": "compound", "gone": PATCH_DELETE},
+        "new": {"x": 1, "y": PATCH_DELETE},
+    }));
+
+    assert_eq!(
+        v,
+        nbt!({
+            "id": "minecraft:zombie",
+            "Pos": [2.0],
+            "tag": {"display": {"Name": "Bob", "Lore": []}},
+            "scalar": {"now": "compound"},
+            "new": {"x": 1},
+        })
+    );
+
+    // Anything but a compound replaces the whole value.
+    v.apply_patch(&nbt!(5_i8));
+    assert_eq!(v, nbt!(5_i8));
+}
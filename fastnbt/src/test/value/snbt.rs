@@ -0,0 +1,102 @@
+use crate::Value;
+
+#[test]
+fn parse_snbt() {
+    let v: Value = r#"{Pos:[1.5d,64.0d],UUID:[I;1,2,3,4],data:[B;1b,-2b],"quoted key":"a"}"#
+        .parse()
+        .unwrap();
+    assert_eq!(
+        v,
+        nbt!({
+            "Pos": [1.5, 64.0],
+            "UUID": [I; 1, 2, 3, 4],
+            "data": [B; 1, -2],
+            "quoted key": "a",
+        })
+    );
+}
+
+#[test]
+fn parse_snbt_round_trip() {
+    let v = nbt!({
+        "Items": [{"Count": 1_i8, "id": "minecraft:stone"}, {}],
+        "longs": [L; 1, 2],
+        "empty": [],
+        "Health": 20.0_f32,
+    });
+    let snbt = fastsnbt::to_string(&v).unwrap();
+    assert_eq!(snbt.parse::<Value>().unwrap(), v);
+}
+
+#[test]
+fn parse_snbt_errors_give_position() {
+    let err = "{a:1}x".parse::<Value>().unwrap_err();
+    assert_eq!(err.snbt_position(), Some(5));
+    assert_eq!(
+        err.to_string(),
+        "invalid snbt at 5: input wasn't fully consumed"
+    );
+
+    let err = "{a:[1,}".parse::<Value>().unwrap_err();
+    assert!(err.snbt_position().is_some());
+
+    // Errors from binary NBT have no position.
+    let err = crate::from_bytes::<Value>(&[]).unwrap_err();
+    assert_eq!(err.snbt_position(), None);
+}
+
+#[test]
+fn patch_from_snbt() {
+    let mut v = nbt!({"Health": 12.0_f32, "Fire": 40_i16});
+    let patch: Value = r#"{Health:20.0f,Fire:"$delete"}"#.parse().unwrap();
+    v.apply_patch(&patch);
+    assert_eq!(v, nbt!({"Health": 20.0_f32}));
+}
+
+#[test]
+fn parse_written_snbt() {
+    let v = nbt!({
+        "id": "say \"hi\" \\o/",
+        "Pos": [1.5, 64.0, -0.25],
+        "Health": 20.0_f32,
+        "tiny": 1e-7_f32,
+        "Items": [{"Count": 1_i8, "id": "minecraft:stone"}, {}],
+        "empty": [],
+        "bytes": [B; 1, -2],
+        "longs": [L; 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17],
+    });
+
+    let mut pretty = vec![];
+    crate::to_pretty_snbt(crate::to_bytes(&v).unwrap().as_slice(), &mut pretty, 2).unwrap();
+    let pretty = String::from_utf8(pretty).unwrap();
+    assert_eq!(pretty.parse::<Value>().unwrap(), v);
+
+    // Diffs write values the same way.
+    for diff in crate::diff(&nbt!({}), &v) {
+        let line = diff.to_string();
+        let snbt = line.split_once(": ").unwrap().1;
+        assert_eq!(
+            snbt.parse::<Value>().unwrap(),
+            v.get_path(&[&diff.path]).unwrap().clone()
+        );
+    }
+    let line = crate::diff(&nbt!(1), &v)[0].to_string();
+    let snbt = line.strip_prefix("~ 1 -> ").unwrap();
+    assert_eq!(snbt.parse::<Value>().unwrap(), v);
+}
+
+#[test]
+fn parse_snbt_whitespace_only_between_tokens() {
+    assert_eq!(
+        " { a : [ 1b , 2b ] ,\n\tb: \" x \" } "
+            .parse::<Value>()
+            .unwrap(),
+        nbt!({"a": [1_i8, 2_i8], "b": " x "})
+    );
+
+    // Whitespace within a value is not removed.
+    let err = "{a:1 2}".parse::<Value>().unwrap_err();
+    assert_eq!(err.snbt_position(), Some(4));
+    let err = "{a: 1} x".parse::<Value>().unwrap_err();
+    assert_eq!(err.snbt_position(), Some(7));
+}
@@ -0,0 +1,156 @@
+use crate::{from_bytes, ser::NbtWriter, to_bytes, Endian, Tag, Value};
+
+use super::builder::Builder;
+
+#[test]
+fn write_all_types() {
+    let mut w = NbtWriter::new(Vec::new());
+    w.compound("").unwrap();
+    w.field_byte("byte", 1).unwrap();
+    w.field_short("short", 2).unwrap();
+    w.field_int("int", 3).unwrap();
+    w.field_long("long", 4).unwrap();
+    w.field_float("float", 5.0).unwrap();
+    w.field_double("double", 6.0).unwrap();
+    w.field_string("string", "seven").unwrap();
+    w.field_byte_array("bytes", &[-1]).unwrap();
+    w.field_int_array("ints", &[1, 2]).unwrap();
+    w.field_long_array("longs", &[3]).unwrap();
+    w.compound("inner").unwrap();
+    w.end().unwrap();
+    w.list("lists", Tag::List, 2).unwrap();
+    w.element_list(Tag::Int, 1).unwrap();
+    w.element_int(8).unwrap();
+    w.end().unwrap();
+    w.element_list(Tag::End, 0).unwrap();
+    w.end().unwrap();
+    w.end().unwrap();
+    w.end().unwrap();
+    let bytes = w.finish().unwrap();
+
+    let expected = Builder::new()
+        .start_compound("")
+        .byte("byte", 1)
+        .short("short", 2)
+        .int("int", 3)
+        .long("long", 4)
+        .float("float", 5.0)
+        .double("double", 6.0)
+        .string("string", "seven")
+        .byte_array("bytes", &[-1])
+        .int_array("ints", &[1, 2])
+        .long_array("longs", &[3])
+        .start_compound("inner")
+        .end_compound()
+        .start_list("lists", Tag::List, 2)
+        .start_anon_list(Tag::Int, 1)
+        .int_payload(8)
+        .start_anon_list(Tag::End, 0)
+        .end_compound()
+        .build();
+
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn write_list_of_compounds() {
+    let mut w = NbtWriter::new(Vec::new());
+    w.compound("").unwrap();
+    w.list("sections", Tag::Compound, 2).unwrap();
+    for y in 0..2 {
+        w.element_compound().unwrap();
+        w.field_byte("Y", y).unwrap();
+        w.field_string("name", "air").unwrap();
+        w.end().unwrap();
+    }
+    w.end().unwrap();
+    w.end().unwrap();
+    let bytes = w.finish().unwrap();
+
+    let expected = nbt!({
+        "sections": [{"Y": 0_i8, "name": "air"}, {"Y": 1_i8, "name": "air"}],
+    });
+    assert_eq!(from_bytes::<Value>(&bytes).unwrap(), expected);
+}
+
+#[test]
+fn write_little_endian() {
+    let mut w = NbtWriter::new(Vec::new()).endianness(Endian::Little);
+    w.compound("").unwrap();
+    w.field_int("int", 1).unwrap();
+    w.end().unwrap();
+
+    let bytes = w.finish().unwrap();
+    assert_eq!(bytes, b"\x0a\x00\x00\x03\x03\x00int\x01\x00\x00\x00\x00");
+}
+
+#[test]
+fn write_same_as_serializer() {
+    let mut w = NbtWriter::new(Vec::new());
+    w.compound("").unwrap();
+    w.list("pos", Tag::Double, 3).unwrap();
+    for d in [1.0, 2.0, 3.0] {
+        w.element_double(d).unwrap();
+    }
+    w.end().unwrap();
+    w.end().unwrap();
+
+    let bytes = w.finish().unwrap();
+    assert_eq!(bytes, to_bytes(&nbt!({"pos": [1.0, 2.0, 3.0]})).unwrap());
+}
+
+#[test]
+fn write_enforces_structure() {
+    let mut w = NbtWriter::new(Vec::new());
+    assert!(w.field_int("a", 1).is_err());
+    assert!(w.end().is_err());
+
+    w.compound("").unwrap();
+    assert!(w.element_int(1).is_err());
+
+    w.list("list", Tag::Int, 1).unwrap();
+    assert!(w.field_int("a", 1).is_err());
+    assert!(w.element_long(1).is_err());
+    assert!(w.end().is_err());
+
+    let mut w = NbtWriter::new(Vec::new());
+    w.compound("").unwrap();
+    w.list("list", Tag::Int, 1).unwrap();
+    w.element_int(1).unwrap();
+    assert!(w.element_int(2).is_err());
+    w.end().unwrap();
+    assert!(w.list("ends", Tag::End, 1).is_err());
+}
+
+#[test]
+fn finish_requires_closed_root() {
+    assert!(NbtWriter::new(Vec::new()).finish().is_err());
+
+    let mut w = NbtWriter::new(Vec::new());
+    w.compound("").unwrap();
+    assert!(NbtWriter::finish(w).is_err());
+
+    let mut w = NbtWriter::new(Vec::new());
+    w.compound("").unwrap();
+    w.end().unwrap();
+    assert!(w.compound("again").is_err());
+    assert!(w.end().is_err());
+    assert!(w.finish().is_ok());
+}
+
+#[test]
+fn write_rejects_long_names_before_writing() {
+    let long = "k".repeat(65536);
+
+    let mut w = NbtWriter::new(Vec::new());
+    assert!(w.compound(&long).is_err());
+    w.compound("").unwrap();
+    let err = w.field_int(&long, 1).unwrap_err();
+    assert!(err.to_string().starts_with("name of 65536 bytes"));
+
+    // Nothing of the rejected field was written.
+    w.field_int("ok", 2).unwrap();
+    w.end().unwrap();
+    let v: Value = from_bytes(&w.finish().unwrap()).unwrap();
+    assert_eq!(v, nbt!({"ok": 2}));
+}
@@ -0,0 +1,46 @@
+//! This module contains functions for converting UUIDs to and from the
+//! `IntArray` form Minecraft stores them in since 1.16, eg the `UUID` tag of
+//! entities and players.
+//!
+//! The array holds the 128 bits of the UUID as four ints, most significant
+//! first. This is the same layout used when (de)serializing `u128` and `i128`,
+//! see [`de`][`crate::de`] and [`ser`][`crate::ser`].
+//!
+//! ```
+//! use fastnbt::uuid;
+//!
+//! let u = 0x1234_5678_9abc_def0_0fed_cba9_8765_4321;
+//! let arr = uuid::to_int_array(u);
+//! assert_eq!(
+//!     &*arr,
+//!     &[0x1234_5678, 0x9abc_def0_u32 as i32, 0x0fed_cba9, 0x8765_4321_u32 as i32]
+//! );
+//! assert_eq!(uuid::from_int_array(&arr), Some(u));
+//! ```
+
+use crate::IntArray;
+
+/// Split a UUID into the four ints Minecraft stores it as, most significant
+/// first.
+pub fn to_int_array(u: u128) -> IntArray {
+    IntArray::new(vec![
+        (u >> 96) as i32,
+        (u >> 64) as i32,
+        (u >> 32) as i32,
+        u as i32,
+    ])
+}
+
+/// Join the four ints of a UUID stored by Minecraft, most significant first.
+/// Returns `None` if there are not exactly four ints.
+pub fn from_int_array(arr: &[i32]) -> Option<u128> {
+    match *arr {
+        [a, b, c, d] => Some(
+            (a as u32 as u128) << 96
+                | (b as u32 as u128) << 64
+                | (c as u32 as u128) << 32
+                | d as u32 as u128,
+        ),
+        _ => None,
+    }
+}
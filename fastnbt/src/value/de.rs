@@ -10,7 +10,7 @@ use serde::{
 };
 use serde_bytes::ByteBuf;
 
-use crate::{error::Error, ByteArray, IntArray, LongArray, Value};
+use crate::{error::Error, value::VALUE_TOKEN, ByteArray, IntArray, LongArray, Value};
 
 impl<'de> Deserialize<'de> for Value {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -25,6 +25,13 @@ impl<'de> Deserialize<'de> for Value {
                 formatter.write_str("valid NBT")
             }
 
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_any(ValueVisitor)
+            }
+
             fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
             where
                 E: serde::de::Error,
@@ -131,7 +138,7 @@ impl<'de> Deserialize<'de> for Value {
             }
         }
 
-        deserializer.deserialize_any(ValueVisitor)
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, ValueVisitor)
     }
 }
 
@@ -234,7 +241,7 @@ macro_rules! deserialize_number {
     };
 }
 
-fn visit_list<'de, V>(list: &'de Vec<Value>, visitor: V) -> Result<V::Value, Error>
+fn visit_list<'de, V>(list: &'de [Value], visitor: V) -> Result<V::Value, Error>
 where
     V: Visitor<'de>,
 {
@@ -252,6 +259,17 @@ where
     }
 }
 
+fn visit_array<'de, T, V>(array: &[T], visitor: V) -> Result<V::Value, Error>
+where
+    T: Copy + IntoDeserializer<'de, Error>,
+    V: Visitor<'de>,
+{
+    let mut deserializer = serde::de::value::SeqDeserializer::new(array.iter().copied());
+    let seq = visitor.visit_seq(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(seq)
+}
+
 fn visit_compound<'de, V>(
     compound: &'de HashMap<String, Value>,
     visitor: V,
@@ -483,6 +501,7 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
         match self {
             Value::String(v) => visitor.visit_borrowed_str(v),
             Value::List(v) => visit_list(v, visitor),
+            Value::ByteArray(v) => visitor.visit_borrowed_bytes(v.as_bytes()),
             _ => Err(self.invalid_type(&visitor)),
         }
     }
@@ -493,6 +512,9 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
     {
         match self {
             Value::List(v) => visit_list(v, visitor),
+            Value::ByteArray(v) => visit_array(v, visitor),
+            Value::IntArray(v) => visit_array(v, visitor),
+            Value::LongArray(v) => visit_array(v, visitor),
             _ => Err(self.invalid_type(&visitor)),
         }
     }
@@ -501,7 +523,10 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        self.deserialize_seq(visitor)
+        match self {
+            Value::List(v) => visit_list(v, visitor),
+            _ => Err(self.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_tuple_struct<V>(
@@ -525,13 +550,16 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
+        if name == crate::raw::RAW_TOKEN {
+            return visitor.visit_map(crate::raw::RawAccess::from_value(self)?);
+        }
         match self {
             Value::List(v) => visit_list(v, visitor),
             Value::Compound(v) => visit_compound(v, visitor),
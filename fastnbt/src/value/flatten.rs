@@ -0,0 +1,221 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Value;
+use crate::Tag;
+
+/// Options for [`Value::flatten_with_opts`]. This object follows a builder
+/// pattern.
+#[derive(Debug, Clone)]
+pub struct FlattenOpts {
+    separator: String,
+    expand_arrays: bool,
+}
+
+impl FlattenOpts {
+    /// Create new options. The default separator is `.` and arrays are
+    /// summarized by their length.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the string put between the parts of each path.
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Set whether the elements of byte, int and long arrays are each given
+    /// their own entry like list elements, rather than the array being given a
+    /// single entry for its length. Arrays in chunks can be thousands of
+    /// elements long, so this is off by default.
+    pub fn expand_arrays(mut self, value: bool) -> Self {
+        self.expand_arrays = value;
+        self
+    }
+}
+
+impl Default for FlattenOpts {
+    fn default() -> Self {
+        Self {
+            separator: ".".to_string(),
+            expand_arrays: false,
+        }
+    }
+}
+
+impl Value {
+    /// Flatten this value into a path and string for every scalar it
+    /// contains, for exporting to tabular formats like CSV. Paths are the
+    /// compound keys and list indices leading to the value, separated by `.`,
+    /// eg `Level.Sections.0.Y`. Numbers are written without SNBT suffixes and
+    /// strings as they are.
+    ///
+    /// Arrays are summarized by their length, and empty compounds and lists
+    /// give no entries. Entries within a compound are ordered by key, so the
+    /// result is the same every time. Use [`Value::flatten_with_opts`] to
+    /// change the separator or expand arrays.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let player = nbt!({
+    ///     "Pos": [1.5, 64.0, -3.0],
+    ///     "abilities": {"flying": 0_i8},
+    ///     "UUID": [I; 1, 2, 3, 4],
+    /// });
+    ///
+    /// let rows: Vec<_> = player
+    ///     .flatten()
+    ///     .into_iter()
+    ///     .map(|(path, value)| format!("{},{}", path, value))
+    ///     .collect();
+    ///
+    /// assert_eq!(rows, [
+    ///     "Pos.0,1.5",
+    ///     "Pos.1,64",
+    ///     "Pos.2,-3",
+    ///     "UUID,4",
+    ///     "abilities.flying,0",
+    /// ]);
+    /// ```
+    pub fn flatten(&self) -> Vec<(String, String)> {
+        self.flatten_with_opts(&FlattenOpts::default())
+    }
+
+    /// Similar to [`Value::flatten`] but with options.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, value::FlattenOpts};
+    /// let opts = FlattenOpts::new().separator("/").expand_arrays(true);
+    /// let flat = nbt!({"data": {"ids": [B; 7, 8]}}).flatten_with_opts(&opts);
+    ///
+    /// assert_eq!(flat, [
+    ///     ("data/ids/0".to_string(), "7".to_string()),
+    ///     ("data/ids/1".to_string(), "8".to_string()),
+    /// ]);
+    /// ```
+    pub fn flatten_with_opts(&self, opts: &FlattenOpts) -> Vec<(String, String)> {
+        let mut entries = vec![];
+        flatten_at(self, opts, &mut String::new(), &mut entries);
+        entries
+    }
+
+    /// Every distinct path in this value, with the tags of the values found
+    /// at it. This is for discovering the structure of undocumented data, eg
+    /// to write a struct to deserialize it into.
+    ///
+    /// Paths are the compound keys leading to a value, separated by `.`, like
+    /// [`Value::flatten`]. List indices are collapsed to `*`, so the elements
+    /// of a list share a path and the tags seen across them are merged. The
+    /// value itself has no path, so is not included. The elements of arrays
+    /// are not included either.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Tag};
+    /// # use std::collections::BTreeSet;
+    /// let chunk = nbt!({
+    ///     "Level": {
+    ///         "Sections": [{"Y": 0_i8}, {"Y": 1_i8, "BlockLight": [B; 0]}],
+    ///     },
+    /// });
+    ///
+    /// let paths = chunk.key_paths();
+    /// let keys: Vec<_> = paths.keys().map(String::as_str).collect();
+    /// assert_eq!(keys, [
+    ///     "Level",
+    ///     "Level.Sections",
+    ///     "Level.Sections.*",
+    ///     "Level.Sections.*.BlockLight",
+    ///     "Level.Sections.*.Y",
+    /// ]);
+    /// assert_eq!(paths["Level.Sections.*.Y"], BTreeSet::from([Tag::Byte]));
+    /// ```
+    pub fn key_paths(&self) -> BTreeMap<String, BTreeSet<Tag>> {
+        let mut paths = BTreeMap::new();
+        key_paths_at(self, &mut String::new(), &mut paths);
+        paths
+    }
+}
+
+fn key_paths_at(value: &Value, path: &mut String, paths: &mut BTreeMap<String, BTreeSet<Tag>>) {
+    let mut child = |part: &str, el: &Value| {
+        let parent_len = path.len();
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(part);
+        paths.entry(path.clone()).or_default().insert(el.tag());
+        key_paths_at(el, path, paths);
+        path.truncate(parent_len);
+    };
+
+    match value {
+        Value::List(v) => v.iter().for_each(|el| child("*", el)),
+        Value::Compound(v) => v.iter().for_each(|(key, el)| child(key, el)),
+        _ => {}
+    }
+}
+
+fn flatten_at(
+    value: &Value,
+    opts: &FlattenOpts,
+    path: &mut String,
+    entries: &mut Vec<(String, String)>,
+) {
+    fn array<T: ToString>(
+        v: &[T],
+        opts: &FlattenOpts,
+        path: &mut String,
+        entries: &mut Vec<(String, String)>,
+    ) {
+        if opts.expand_arrays {
+            for (i, el) in v.iter().enumerate() {
+                let parent_len = push_part(path, &i.to_string(), opts);
+                entries.push((path.clone(), el.to_string()));
+                path.truncate(parent_len);
+            }
+        } else {
+            entries.push((path.clone(), v.len().to_string()));
+        }
+    }
+
+    match value {
+        Value::Byte(v) => entries.push((path.clone(), v.to_string())),
+        Value::Short(v) => entries.push((path.clone(), v.to_string())),
+        Value::Int(v) => entries.push((path.clone(), v.to_string())),
+        Value::Long(v) => entries.push((path.clone(), v.to_string())),
+        Value::Float(v) => entries.push((path.clone(), v.to_string())),
+        Value::Double(v) => entries.push((path.clone(), v.to_string())),
+        Value::String(v) => entries.push((path.clone(), v.clone())),
+        Value::ByteArray(v) => array(v, opts, path, entries),
+        Value::IntArray(v) => array(v, opts, path, entries),
+        Value::LongArray(v) => array(v, opts, path, entries),
+        Value::List(v) => {
+            for (i, el) in v.iter().enumerate() {
+                let parent_len = push_part(path, &i.to_string(), opts);
+                flatten_at(el, opts, path, entries);
+                path.truncate(parent_len);
+            }
+        }
+        Value::Compound(v) => {
+            let mut children: Vec<_> = v.iter().collect();
+            children.sort_by_key(|(k, _)| *k);
+
+            for (key, el) in children {
+                let parent_len = push_part(path, key, opts);
+                flatten_at(el, opts, path, entries);
+                path.truncate(parent_len);
+            }
+        }
+    }
+}
+
+/// Add `part` to the end of `path`, returning the length to truncate to to
+/// remove it again.
+fn push_part(path: &mut String, part: &str, opts: &FlattenOpts) -> usize {
+    let parent_len = path.len();
+    if !path.is_empty() {
+        path.push_str(&opts.separator);
+    }
+    path.push_str(part);
+    parent_len
+}
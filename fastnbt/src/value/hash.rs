@@ -0,0 +1,145 @@
+use sha2::{Digest, Sha256};
+
+use super::Value;
+
+impl Value {
+    /// A SHA-256 hash of this value in a canonical form, for addressing values
+    /// by their content, eg to store identical chunks only once. Values that
+    /// compare equal hash the same, whatever order their compounds happen to
+    /// be in. Requires the `sha2` feature.
+    ///
+    /// The hash covers the following, so that changing any of it changes the
+    /// hash:
+    ///
+    /// * Every value starts with its tag as a byte, so `Int(1)` and `Long(1)`
+    ///   differ.
+    /// * Integers are their big endian bytes.
+    /// * Floats and doubles are their big endian IEEE 754 bits, except that
+    ///   `-0.0` is written as `0.0` and every NaN as the one canonical NaN.
+    /// * Strings are their length in bytes as a big endian `u64`, followed by
+    ///   their UTF-8 bytes. This is UTF-8, not the CESU-8 NBT itself uses.
+    /// * Arrays are their number of elements as a big endian `u64`, followed by
+    ///   each element's big endian bytes.
+    /// * Lists are the tag of their elements as a byte, or `End` for an empty
+    ///   list, the number of elements as a big endian `u64`, then each element
+    ///   including its own tag.
+    /// * Compounds are their number of entries as a big endian `u64`, then each
+    ///   entry in order of its key's bytes, as the key like a string followed
+    ///   by the value.
+    ///
+    /// Names are the keys of compounds. The name of the root compound is not
+    /// part of a [`Value`], so it is not covered.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let a = nbt!({"x": 1, "y": 2, "z": 0.0});
+    /// let b = nbt!({"z": -0.0, "y": 2, "x": 1});
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    ///
+    /// let c = nbt!({"x": 1, "y": 2, "z": 0.0f32});
+    /// assert_ne!(a.content_hash(), c.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        canonical(self, &mut hasher);
+        hasher.finalize().into()
+    }
+}
+
+/// Feed the canonical form of `value` described by [`Value::content_hash`] to
+/// `hasher`. This keeps its place on the heap rather than recursing, so any
+/// depth can be hashed.
+fn canonical(value: &Value, hasher: &mut Sha256) {
+    // Work left to do, the next last. Keys of compounds are pushed after
+    // their values, so come before them.
+    enum Item<'a> {
+        Value(&'a Value),
+        Key(&'a str),
+    }
+
+    let mut stack = vec![Item::Value(value)];
+    while let Some(item) = stack.pop() {
+        let value = match item {
+            Item::Value(value) => value,
+            Item::Key(k) => {
+                string(k, hasher);
+                continue;
+            }
+        };
+
+        hasher.update([u8::from(value.tag())]);
+
+        match value {
+            Value::Byte(v) => hasher.update(v.to_be_bytes()),
+            Value::Short(v) => hasher.update(v.to_be_bytes()),
+            Value::Int(v) => hasher.update(v.to_be_bytes()),
+            Value::Long(v) => hasher.update(v.to_be_bytes()),
+            Value::Float(v) => hasher.update(canonical_f32(*v).to_be_bytes()),
+            Value::Double(v) => hasher.update(canonical_f64(*v).to_be_bytes()),
+            Value::String(v) => string(v, hasher),
+            Value::ByteArray(v) => {
+                length(v.len(), hasher);
+                for el in v.iter() {
+                    hasher.update(el.to_be_bytes());
+                }
+            }
+            Value::IntArray(v) => {
+                length(v.len(), hasher);
+                for el in v.iter() {
+                    hasher.update(el.to_be_bytes());
+                }
+            }
+            Value::LongArray(v) => {
+                length(v.len(), hasher);
+                for el in v.iter() {
+                    hasher.update(el.to_be_bytes());
+                }
+            }
+            Value::List(v) => {
+                let tag = v.first().map(Value::tag).unwrap_or(crate::Tag::End);
+                hasher.update([u8::from(tag)]);
+                length(v.len(), hasher);
+                stack.extend(v.iter().rev().map(Item::Value));
+            }
+            Value::Compound(v) => {
+                let mut entries: Vec<_> = v.iter().collect();
+                entries.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+
+                length(entries.len(), hasher);
+                for (k, v) in entries.into_iter().rev() {
+                    stack.push(Item::Value(v));
+                    stack.push(Item::Key(k));
+                }
+            }
+        }
+    }
+}
+
+fn length(len: usize, hasher: &mut Sha256) {
+    hasher.update((len as u64).to_be_bytes());
+}
+
+fn string(s: &str, hasher: &mut Sha256) {
+    length(s.len(), hasher);
+    hasher.update(s.as_bytes());
+}
+
+fn canonical_f32(v: f32) -> u32 {
+    if v.is_nan() {
+        f32::NAN.to_bits()
+    } else if v == 0.0 {
+        0
+    } else {
+        v.to_bits()
+    }
+}
+
+fn canonical_f64(v: f64) -> u64 {
+    if v.is_nan() {
+        f64::NAN.to_bits()
+    } else if v == 0.0 {
+        0
+    } else {
+        v.to_bits()
+    }
+}
@@ -1,27 +1,39 @@
 mod array_serializer;
 mod de;
+mod flatten;
+#[cfg(feature = "sha2")]
+mod hash;
+mod patch;
+mod prune;
 mod ser;
 
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{error::Error, ByteArray, IntArray, LongArray};
+use crate::{error::Error, ByteArray, IntArray, LongArray, Tag};
 
+pub use self::flatten::FlattenOpts;
+pub use self::patch::PATCH_DELETE;
+pub use self::prune::PruneOpts;
 pub use self::ser::Serializer;
 
+/// The name of the newtype struct that [`Value`] and the other value types
+/// deserialize as, to be given arrays as arrays rather than as lists.
+pub(crate) const VALUE_TOKEN: &str = "__fastnbt_value";
+
 /// Value is a complete NBT value. It owns its data. Compounds and Lists are
 /// resursively deserialized. This type takes care to preserve all the
 /// information from the original NBT, with the exception of the name of the
 /// root compound (which is usually the empty string).
 ///
-/// ```no_run
+/// ```
 /// # use fastnbt::Value;
 /// # use fastnbt::error::Result;
 /// # use std::collections::HashMap;
 /// #
 /// # fn main() -> Result<()> {
-/// #   let mut buf = vec![];
+/// #   let buf = fastnbt::to_bytes(&fastnbt::nbt!({"DataVersion": 3465}))?;
 ///     let compound: HashMap<String, Value> = fastnbt::from_bytes(buf.as_slice())?;
 ///     match compound["DataVersion"] {
 ///         Value::Int(ver) => println!("Version: {}", ver),
@@ -31,6 +43,8 @@ pub use self::ser::Serializer;
 /// #   Ok(())
 /// # }
 /// ```
+///
+/// A `Value` owns all of its children, so it can never contain a cycle.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Byte(i8),
@@ -47,7 +61,12 @@ pub enum Value {
     Compound(HashMap<String, Value>),
 }
 
-#[cfg(feature = "arbitrary1")]
+/// Deepest nesting of lists and compounds generated by `Arbitrary`. Beyond
+/// this lists and compounds are generated empty.
+#[cfg(feature = "arbitrary")]
+const ARBITRARY_MAX_DEPTH: usize = 32;
+
+#[cfg(feature = "arbitrary")]
 fn het_list<'a, T, F>(u: &mut arbitrary::Unstructured<'a>, f: F) -> arbitrary::Result<Vec<Value>>
 where
     F: FnMut(T) -> Value,
@@ -60,11 +79,68 @@ where
         .collect())
 }
 
-#[cfg(feature = "arbitrary1")]
-fn arb_list(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Vec<Value>> {
-    use crate::Tag;
+#[cfg(feature = "arbitrary")]
+fn arb_vec<'a, F>(u: &mut arbitrary::Unstructured<'a>, mut f: F) -> arbitrary::Result<Vec<Value>>
+where
+    F: FnMut(&mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Value>,
+{
+    let len = u.arbitrary_len::<Value>()?;
+    let mut v = Vec::with_capacity(len);
+    for _ in 0..len {
+        v.push(f(u)?);
+    }
+    Ok(v)
+}
+
+/// A string that fits in NBT, ie its Java CESU-8 encoding is at most
+/// `u16::MAX` bytes.
+#[cfg(feature = "arbitrary")]
+fn arb_string(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let s: String = u.arbitrary()?;
+    let mut encoded_len = 0;
+    let end = s
+        .char_indices()
+        .find(|(_, c)| {
+            encoded_len += match *c as u32 {
+                0 => 2, // Java encodes nul as two bytes.
+                0x01..=0x7f => 1,
+                0x80..=0x7ff => 2,
+                0x800..=0xffff => 3,
+                _ => 6, // surrogate pair of 3 bytes each.
+            };
+            encoded_len > u16::MAX as usize
+        })
+        .map_or(s.len(), |(i, _)| i);
+
+    Ok(s[..end].to_string())
+}
+
+#[cfg(feature = "arbitrary")]
+fn arb_compound(
+    u: &mut arbitrary::Unstructured,
+    depth: usize,
+) -> arbitrary::Result<HashMap<String, Value>> {
+    let mut map = HashMap::new();
+    if depth >= ARBITRARY_MAX_DEPTH {
+        return Ok(map);
+    }
+
+    let len = u.arbitrary_len::<(String, Value)>()?;
+    for _ in 0..len {
+        map.insert(arb_string(u)?, arb_value(u, depth + 1)?);
+    }
+    Ok(map)
+}
+
+#[cfg(feature = "arbitrary")]
+fn arb_list(u: &mut arbitrary::Unstructured, depth: usize) -> arbitrary::Result<Vec<Value>> {
     use Value::*;
 
+    if depth >= ARBITRARY_MAX_DEPTH {
+        return Ok(vec![]);
+    }
+
+    // Lists need to all be the same type.
     Ok(match u.arbitrary::<Tag>()? {
         Tag::End => return Err(arbitrary::Error::IncorrectFormat),
         Tag::Byte => het_list(u, Byte)?,
@@ -74,45 +150,41 @@ fn arb_list(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Vec<Value>> {
         Tag::Float => het_list(u, Float)?,
         Tag::Double => het_list(u, Double)?,
         Tag::ByteArray => het_list(u, ByteArray)?,
-        Tag::String => het_list(u, String)?,
-        Tag::List => {
-            // make a list of lists
-            let len = u.arbitrary_len::<Value>()?;
-            let mut v = vec![];
-            for _ in 0..len {
-                v.push(Value::List(arb_list(u)?));
-            }
-            v
-        }
-        Tag::Compound => het_list(u, Compound)?,
+        Tag::String => arb_vec(u, |u| Ok(String(arb_string(u)?)))?,
+        Tag::List => arb_vec(u, |u| Ok(List(arb_list(u, depth + 1)?)))?,
+        Tag::Compound => arb_vec(u, |u| Ok(Compound(arb_compound(u, depth + 1)?)))?,
         Tag::IntArray => het_list(u, IntArray)?,
         Tag::LongArray => het_list(u, LongArray)?,
     })
 }
 
-#[cfg(feature = "arbitrary1")]
+#[cfg(feature = "arbitrary")]
+fn arb_value(u: &mut arbitrary::Unstructured, depth: usize) -> arbitrary::Result<Value> {
+    use Value::*;
+
+    Ok(match u.arbitrary::<Tag>()? {
+        Tag::End => return Err(arbitrary::Error::IncorrectFormat),
+        Tag::Byte => Byte(u.arbitrary()?),
+        Tag::Short => Short(u.arbitrary()?),
+        Tag::Int => Int(u.arbitrary()?),
+        Tag::Long => Long(u.arbitrary()?),
+        Tag::Float => Float(u.arbitrary()?),
+        Tag::Double => Double(u.arbitrary()?),
+        Tag::ByteArray => ByteArray(u.arbitrary()?),
+        Tag::String => String(arb_string(u)?),
+        Tag::Compound => Compound(arb_compound(u, depth)?),
+        Tag::IntArray => IntArray(u.arbitrary()?),
+        Tag::LongArray => LongArray(u.arbitrary()?),
+        Tag::List => List(arb_list(u, depth)?),
+    })
+}
+
+/// Generates values that can always be serialized: strings fit NBT's length
+/// limit, list elements share a type, and nesting is bounded.
+#[cfg(feature = "arbitrary")]
 impl<'a> arbitrary::Arbitrary<'a> for Value {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
-        use crate::Tag;
-        use Value::*;
-
-        Ok(match u.arbitrary::<Tag>()? {
-            Tag::End => return Err(arbitrary::Error::IncorrectFormat),
-            Tag::Byte => Byte(u.arbitrary()?),
-            Tag::Short => Short(u.arbitrary()?),
-            Tag::Int => Int(u.arbitrary()?),
-            Tag::Long => Long(u.arbitrary()?),
-            Tag::Float => Float(u.arbitrary()?),
-            Tag::Double => Double(u.arbitrary()?),
-            Tag::ByteArray => ByteArray(u.arbitrary()?),
-            Tag::String => String(u.arbitrary()?),
-            Tag::Compound => Compound(u.arbitrary()?),
-            Tag::IntArray => IntArray(u.arbitrary()?),
-            Tag::LongArray => LongArray(u.arbitrary()?),
-
-            // Lists need to all be the same type.
-            Tag::List => List(arb_list(u)?),
-        })
+        arb_value(u, 0)
     }
 }
 
@@ -159,6 +231,648 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Create a compound from key-value pairs. Later values replace earlier
+    /// ones with the same key.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Value};
+    /// let scores = [("Alex", 12), ("Steve", 7)];
+    /// let compound = Value::compound_from_iter(scores.iter().map(|(k, v)| (*k, *v)));
+    ///
+    /// assert_eq!(compound, nbt!({"Alex": 12, "Steve": 7}));
+    /// ```
+    pub fn compound_from_iter<K, V>(iter: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        Value::Compound(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+
+    /// Create a list from values. NBT lists must contain a single type, so
+    /// every element should be the same variant of [`Value`]. This is not
+    /// checked until the list is serialized, see [`Value::list_checked`] to
+    /// check it up front.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Value};
+    /// let list = Value::list_from_iter((1..=3).map(|i| i * 2));
+    ///
+    /// assert_eq!(list, nbt!([2, 4, 6]));
+    /// ```
+    pub fn list_from_iter<V: Into<Value>>(iter: impl IntoIterator<Item = V>) -> Self {
+        Value::List(iter.into_iter().map(Into::into).collect())
+    }
+
+    /// Create a list from values, checking that every element has the same
+    /// tag as NBT requires. The error names the first element with a different
+    /// tag to the first element.
+    ///
+    /// Lists built with [`Value::List`] or [`Value::list_from_iter`] are not
+    /// checked, but serializing one with mixed elements is still an error.
+    ///
+    /// ```
+    /// # use fastnbt::Value;
+    /// let list = Value::list_checked(vec![Value::Int(1), Value::Int(2)]).unwrap();
+    /// assert_eq!(list, fastnbt::nbt!([1, 2]));
+    ///
+    /// let err = Value::list_checked(vec![Value::Int(1), Value::Byte(2)]).unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "heterogeneous list: element 1 is byte, but the list is of int"
+    /// );
+    /// ```
+    pub fn list_checked(values: Vec<Value>) -> Result<Value, Error> {
+        if let Some(first) = values.first() {
+            let expected = first.tag();
+            if let Some(index) = values.iter().position(|v| v.tag() != expected) {
+                return Err(Error::heterogeneous_list(
+                    index,
+                    expected,
+                    values[index].tag(),
+                ));
+            }
+        }
+        Ok(Value::List(values))
+    }
+
+    /// The NBT tag this value is serialized as.
+    pub fn tag(&self) -> Tag {
+        match self {
+            Value::Byte(_) => Tag::Byte,
+            Value::Short(_) => Tag::Short,
+            Value::Int(_) => Tag::Int,
+            Value::Long(_) => Tag::Long,
+            Value::Float(_) => Tag::Float,
+            Value::Double(_) => Tag::Double,
+            Value::String(_) => Tag::String,
+            Value::ByteArray(_) => Tag::ByteArray,
+            Value::IntArray(_) => Tag::IntArray,
+            Value::LongArray(_) => Tag::LongArray,
+            Value::List(_) => Tag::List,
+            Value::Compound(_) => Tag::Compound,
+        }
+    }
+
+    /// Whether this compound, list, array or string has nothing in it. Scalars
+    /// such as numbers are never empty, so this is always false for them.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Value};
+    /// let mut item = nbt!({"id": "minecraft:stick", "Count": 1_i8, "tag": {}});
+    ///
+    /// if let Value::Compound(item) = &mut item {
+    ///     item.retain(|_, v| !v.is_empty());
+    /// }
+    ///
+    /// assert_eq!(item, nbt!({"id": "minecraft:stick", "Count": 1_i8}));
+    /// assert!(!nbt!(0).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Value::Byte(_)
+            | Value::Short(_)
+            | Value::Int(_)
+            | Value::Long(_)
+            | Value::Float(_)
+            | Value::Double(_) => false,
+            Value::String(v) => v.is_empty(),
+            Value::ByteArray(v) => v.is_empty(),
+            Value::IntArray(v) => v.is_empty(),
+            Value::LongArray(v) => v.is_empty(),
+            Value::List(v) => v.is_empty(),
+            Value::Compound(v) => v.is_empty(),
+        }
+    }
+
+    /// Get the entry for `key` in this compound for in-place manipulation, in
+    /// the same way as [`HashMap::entry`]. Returns `None` if this value is not
+    /// a compound.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Value};
+    /// let mut entity = nbt!({"id": "minecraft:pig"});
+    ///
+    /// if let Some(Value::List(tags)) = entity
+    ///     .entry("Tags")
+    ///     .map(|e| e.or_insert_with(|| Value::List(vec![])))
+    /// {
+    ///     tags.push(Value::from("tamed"));
+    /// }
+    ///
+    /// assert_eq!(entity, nbt!({"id": "minecraft:pig", "Tags": ["tamed"]}));
+    /// ```
+    pub fn entry(&mut self, key: impl Into<String>) -> Option<Entry<'_, String, Value>> {
+        match self {
+            Value::Compound(v) => Some(v.entry(key.into())),
+            _ => None,
+        }
+    }
+
+    /// Take element `index` out of this list, leaving [`Value::default`] (an
+    /// empty compound) in its place. Put it back by assigning to the element.
+    /// Returns `None` if this value is not a list or `index` is out of bounds.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Value};
+    /// let mut entities = nbt!([{"id": "minecraft:pig"}]);
+    ///
+    /// let mut pig = entities.list_take(0).unwrap();
+    /// pig.entry("Saddle").unwrap().or_insert(Value::from(true));
+    /// if let Value::List(list) = &mut entities {
+    ///     list[0] = pig;
+    /// }
+    ///
+    /// assert_eq!(entities, nbt!([{"id": "minecraft:pig", "Saddle": 1_i8}]));
+    /// ```
+    pub fn list_take(&mut self, index: usize) -> Option<Value> {
+        match self {
+            Value::List(v) => v.get_mut(index).map(std::mem::take),
+            _ => None,
+        }
+    }
+
+    /// Remove element `index` from this list, replacing it with the last
+    /// element, as [`Vec::swap_remove`]. Returns `None` if this value is not a
+    /// list or `index` is out of bounds.
+    pub fn list_swap_remove(&mut self, index: usize) -> Option<Value> {
+        match self {
+            Value::List(v) if index < v.len() => Some(v.swap_remove(index)),
+            _ => None,
+        }
+    }
+
+    /// Find every value matching a `/` separated path, such as
+    /// `sections/*/block_states/palette/*/Name`. Each part of the path is a
+    /// compound key or a list index, or `*` to match every value of a compound
+    /// or list at that level. An empty path matches this value.
+    ///
+    /// Values within a compound matched by `*` are in no particular order.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let chunk = nbt!({
+    ///     "sections": [
+    ///         {"palette": [{"Name": "minecraft:stone"}, {"Name": "minecraft:air"}], "Y": 0_i8},
+    ///         {"palette": [{"Name": "minecraft:air"}], "Y": 1_i8},
+    ///     ],
+    /// });
+    ///
+    /// let air = chunk
+    ///     .find_all("sections/*/palette/*/Name")
+    ///     .into_iter()
+    ///     .filter(|name| name.as_str() == Some("minecraft:air"))
+    ///     .count();
+    /// assert_eq!(air, 2);
+    ///
+    /// let height: i64 = chunk
+    ///     .find_all("sections/*/Y")
+    ///     .into_iter()
+    ///     .filter_map(|y| y.as_i64())
+    ///     .sum();
+    /// assert_eq!(height, 1);
+    ///
+    /// assert_eq!(chunk.find_all("sections/1/Y"), [&nbt!(1_i8)]);
+    /// ```
+    pub fn find_all(&self, path: &str) -> Vec<&Value> {
+        let mut found = vec![self];
+
+        for part in path.split('/').filter(|part| !part.is_empty()) {
+            let mut next = vec![];
+            for value in found {
+                match (value, part) {
+                    (Value::Compound(v), "*") => next.extend(v.values()),
+                    (Value::List(v), "*") => next.extend(v.iter()),
+                    (Value::Compound(v), key) => next.extend(v.get(key)),
+                    (Value::List(v), index) => {
+                        next.extend(index.parse().ok().and_then(|i: usize| v.get(i)))
+                    }
+                    _ => {}
+                }
+            }
+            found = next;
+        }
+
+        found
+    }
+
+    /// Sum every value of type `tag` matching a path, as in
+    /// [`find_all`][`Value::find_all`]. Matching values of other types are
+    /// skipped, so the sum of what is stored as one type is not thrown off by
+    /// values of the same name stored as another. The sum is a double, so longs
+    /// beyond 2<sup>53</sup> lose precision. Non-numeric tags sum to 0.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Tag};
+    /// let entities = nbt!({"Entities": [{"Health": 20.0_f32}, {"Health": 5.5_f32}, {}]});
+    ///
+    /// assert_eq!(entities.sum_of_type("Entities/*/Health", Tag::Float), 25.5);
+    /// assert_eq!(entities.sum_of_type("Entities/*/Health", Tag::Int), 0.0);
+    /// ```
+    pub fn sum_of_type(&self, path: &str, tag: Tag) -> f64 {
+        self.find_all(path)
+            .into_iter()
+            .filter(|value| value.tag() == tag)
+            .filter_map(Value::as_f64)
+            .sum()
+    }
+
+    /// Count how many times each string matching a path occurs, as in
+    /// [`find_all`][`Value::find_all`]. Matching values that are not strings
+    /// are skipped. This is handy for counting block types in a chunk's
+    /// palettes.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let chunk = nbt!({
+    ///     "sections": [
+    ///         {"palette": [{"Name": "minecraft:stone"}, {"Name": "minecraft:air"}]},
+    ///         {"palette": [{"Name": "minecraft:air"}]},
+    ///     ],
+    /// });
+    ///
+    /// let counts = chunk.count_strings("sections/*/palette/*/Name");
+    /// assert_eq!(counts["minecraft:air"], 2);
+    /// assert_eq!(counts["minecraft:stone"], 1);
+    /// ```
+    pub fn count_strings(&self, path: &str) -> HashMap<&str, usize> {
+        let mut counts = HashMap::new();
+        for s in self.find_all(path).into_iter().filter_map(Value::as_str) {
+            *counts.entry(s).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Get the value at a path that is already split into its parts, such as
+    /// `["sections", "1", "Y"]`. Each part is a compound key, or an index if
+    /// the value at that point is a list. This is like
+    /// [`find_all`][`Value::find_all`] for a single value, but `*` is not
+    /// special and the path is not parsed, so it does not allocate. An empty
+    /// path gives this value.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let chunk = nbt!({"sections": [{"Y": 0_i8}, {"Y": 1_i8}]});
+    ///
+    /// assert_eq!(chunk.get_path(&["sections", "1", "Y"]), Some(&nbt!(1_i8)));
+    /// assert_eq!(chunk.get_path(&["sections", "2", "Y"]), None);
+    /// ```
+    pub fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        path.iter().try_fold(self, |value, part| match value {
+            Value::Compound(v) => v.get(*part),
+            Value::List(v) => v.get(part.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    /// Like [`get_path`][`Value::get_path`], but gives a mutable reference.
+    pub fn get_path_mut(&mut self, path: &[&str]) -> Option<&mut Value> {
+        path.iter().try_fold(self, |value, part| match value {
+            Value::Compound(v) => v.get_mut(*part),
+            Value::List(v) => v.get_mut(part.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    /// The maximum nesting depth of this value. Lists and compounds add one
+    /// level of depth, so a scalar or array has a depth of 0 and an empty
+    /// compound has a depth of 1.
+    ///
+    /// This does not recurse, so is safe to call on arbitrarily deep values,
+    /// eg to reject pathological input after parsing.
+    pub fn depth(&self) -> usize {
+        let mut max = 0;
+        let mut stack = vec![(self, 0)];
+
+        while let Some((value, depth)) = stack.pop() {
+            match value {
+                Value::List(v) => stack.extend(v.iter().map(|child| (child, depth + 1))),
+                Value::Compound(v) => stack.extend(v.values().map(|child| (child, depth + 1))),
+                _ => {
+                    max = max.max(depth);
+                    continue;
+                }
+            }
+
+            max = max.max(depth + 1);
+        }
+
+        max
+    }
+
+    /// Widen every integer in this value narrower than `width` to `width`, so
+    /// that values which only differ in how wide their integers are compare
+    /// equal. For example with a `width` of [`Tag::Long`], `1b`, `1s` and `1`
+    /// all become `1L`. Integers already at least as wide are left alone, as
+    /// are floats, strings and arrays.
+    ///
+    /// `width` should be one of [`Tag::Byte`], [`Tag::Short`], [`Tag::Int`]
+    /// or [`Tag::Long`]. Other tags leave every value unchanged.
+    ///
+    /// This changes the tags of the values, so serializing a canonicalized
+    /// value gives different NBT, which the game may not accept. It is meant
+    /// for comparing values, eg with [`diff`][`crate::diff()`].
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Tag};
+    /// let mut a = nbt!({"OnGround": 1_i8, "Pos": [{"x": 3_i16}]});
+    /// let mut b = nbt!({"OnGround": 1, "Pos": [{"x": 3}]});
+    /// assert_ne!(a, b);
+    ///
+    /// a.canonicalize(Tag::Long);
+    /// b.canonicalize(Tag::Long);
+    /// assert_eq!(a, b);
+    /// assert_eq!(a, nbt!({"OnGround": 1_i64, "Pos": [{"x": 3_i64}]}));
+    /// ```
+    pub fn canonicalize(&mut self, width: Tag) {
+        if !matches!(width, Tag::Short | Tag::Int | Tag::Long) {
+            return;
+        }
+
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            let v = match value {
+                Value::Byte(v) => *v as i64,
+                Value::Short(v) => *v as i64,
+                Value::Int(v) => *v as i64,
+                Value::List(v) => {
+                    stack.extend(v.iter_mut());
+                    continue;
+                }
+                Value::Compound(v) => {
+                    stack.extend(v.values_mut());
+                    continue;
+                }
+                _ => continue,
+            };
+
+            // The payload size of an integer tag is its width.
+            if value.tag().payload_size() < width.payload_size() {
+                *value = match width {
+                    Tag::Short => Value::Short(v as i16),
+                    Tag::Int => Value::Int(v as i32),
+                    _ => Value::Long(v),
+                };
+            }
+        }
+    }
+
+    /// Replace ids throughout this value using `mapping`, eg to rename blocks
+    /// and items that were renamed between versions of the game. Every string
+    /// in a compound under one of the keys in `field_names`, such as `Name`
+    /// in block palettes or `id` in items and entities, is replaced by its
+    /// entry in `mapping` if it has one. Returns how many were replaced.
+    ///
+    /// Strings under any other key are left alone, even if they match, as
+    /// are entries under these keys that are not strings. The whole value is
+    /// searched, including inside lists.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// # use std::collections::HashMap;
+    /// let mapping = HashMap::from([(
+    ///     "minecraft:grass".to_string(),
+    ///     "minecraft:short_grass".to_string(),
+    /// )]);
+    ///
+    /// let mut chunk = nbt!({
+    ///     "palette": [{"Name": "minecraft:grass"}, {"Name": "minecraft:stone"}],
+    ///     "Items": [{"id": "minecraft:grass", "CustomName": "minecraft:grass"}],
+    /// });
+    /// assert_eq!(chunk.remap_ids(&["Name", "id"], &mapping), 2);
+    ///
+    /// assert_eq!(chunk, nbt!({
+    ///     "palette": [{"Name": "minecraft:short_grass"}, {"Name": "minecraft:stone"}],
+    ///     "Items": [{"id": "minecraft:short_grass", "CustomName": "minecraft:grass"}],
+    /// }));
+    /// ```
+    pub fn remap_ids(&mut self, field_names: &[&str], mapping: &HashMap<String, String>) -> usize {
+        let mut replaced = 0;
+
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::List(v) => stack.extend(v.iter_mut()),
+                Value::Compound(v) => {
+                    for (key, el) in v.iter_mut() {
+                        match el {
+                            Value::String(id) if field_names.contains(&key.as_str()) => {
+                                if let Some(new) = mapping.get(id) {
+                                    id.clone_from(new);
+                                    replaced += 1;
+                                }
+                            }
+                            _ => stack.push(el),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        replaced
+    }
+
+    /// Convert this value to the numeric tag `tag`, eg to store a value built
+    /// as an int as the byte the game expects for that field. Any of
+    /// [`Tag::Byte`], [`Tag::Short`], [`Tag::Int`], [`Tag::Long`],
+    /// [`Tag::Float`] and [`Tag::Double`] can be converted to any other.
+    ///
+    /// Integers must fit in the new tag, so `300` cannot become a byte. Floats
+    /// and doubles only become integers if they are whole numbers that fit.
+    /// Integers and doubles that become floats, and longs that become doubles,
+    /// are rounded to the nearest value the new tag can hold, but a double
+    /// beyond the range of a float is an error.
+    ///
+    /// Converting to the tag the value already has does nothing. Any other
+    /// conversion, such as a string to an int, is an error. The value is left
+    /// unchanged if there is an error.
+    ///
+    /// ```
+    /// # use fastnbt::{Tag, Value};
+    /// let mut v = Value::Int(1);
+    /// v.coerce_to(Tag::Byte).unwrap();
+    /// assert_eq!(v, Value::Byte(1));
+    ///
+    /// let mut v = Value::Int(300);
+    /// let err = v.coerce_to(Tag::Byte).unwrap_err();
+    /// assert_eq!(err.to_string(), "cannot coerce int 300 to byte: out of range");
+    /// assert_eq!(v, Value::Int(300));
+    ///
+    /// let mut v = Value::String("1".into());
+    /// let err = v.coerce_to(Tag::Int).unwrap_err();
+    /// assert_eq!(err.to_string(), "cannot coerce string to int");
+    /// ```
+    pub fn coerce_to(&mut self, tag: Tag) -> Result<(), Error> {
+        let from = self.tag();
+        if from == tag {
+            return Ok(());
+        }
+
+        let fail = |v: &dyn std::fmt::Display, why| Error::cannot_coerce_value(v, from, tag, why);
+
+        let coerced = match (&*self, tag) {
+            (Value::Float(v), Tag::Double) => Value::Double(*v as f64),
+            (Value::Double(v), Tag::Float) => {
+                // Doubles just past the largest float still round to it.
+                let float = *v as f32;
+                if v.is_finite() && float.is_infinite() {
+                    return Err(fail(v, "out of range"));
+                }
+                Value::Float(float)
+            }
+            (_, Tag::Byte | Tag::Short | Tag::Int | Tag::Long | Tag::Float | Tag::Double) => {
+                let integer = match *self {
+                    Value::Byte(v) => v as i64,
+                    Value::Short(v) => v as i64,
+                    Value::Int(v) => v as i64,
+                    Value::Long(v) => v,
+                    Value::Float(v) => {
+                        Self::float_to_integer(v as f64).map_err(|why| fail(&v, why))?
+                    }
+                    Value::Double(v) => Self::float_to_integer(v).map_err(|why| fail(&v, why))?,
+                    _ => return Err(Error::cannot_coerce(from, tag)),
+                };
+
+                let out_of_range = |_| fail(&integer, "out of range");
+                match tag {
+                    Tag::Byte => Value::Byte(integer.try_into().map_err(out_of_range)?),
+                    Tag::Short => Value::Short(integer.try_into().map_err(out_of_range)?),
+                    Tag::Int => Value::Int(integer.try_into().map_err(out_of_range)?),
+                    Tag::Long => Value::Long(integer),
+                    Tag::Float => Value::Float(integer as f32),
+                    _ => Value::Double(integer as f64),
+                }
+            }
+            _ => return Err(Error::cannot_coerce(from, tag)),
+        };
+
+        *self = coerced;
+        Ok(())
+    }
+
+    /// The integer a float holds, or why it cannot be held by a long.
+    fn float_to_integer(v: f64) -> Result<i64, &'static str> {
+        if v.fract() != 0.0 {
+            Err("not a whole number")
+        } else if (-9223372036854775808.0..9223372036854775808.0).contains(&v) {
+            // The bounds are exactly representable, unlike `i64::MAX`.
+            Ok(v as i64)
+        } else {
+            Err("out of range")
+        }
+    }
+
+    /// Compare this value with `other` like `==`, except that floats and
+    /// doubles are equal if they are within `epsilon` of each other. Use this
+    /// to compare values that have been through other tools, which may not
+    /// write floats back out bit for bit.
+    ///
+    /// Everything else must be exactly equal, including the tags, so a float
+    /// is never equal to a double. Infinities are equal to themselves, and NaN
+    /// is not equal to anything.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let a = nbt!({"Pos": [1.0, 64.0, 0.1 + 0.2]});
+    /// let b = nbt!({"Pos": [1.0, 64.0, 0.3]});
+    /// assert_ne!(a, b);
+    /// assert!(a.approx_eq(&b, 1e-9));
+    /// ```
+    pub fn approx_eq(&self, other: &Value, epsilon: f64) -> bool {
+        let close = |a: f64, b: f64| a == b || (a - b).abs() <= epsilon;
+
+        let mut stack = vec![(self, other)];
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (Value::Float(a), Value::Float(b)) => {
+                    if !close(*a as f64, *b as f64) {
+                        return false;
+                    }
+                }
+                (Value::Double(a), Value::Double(b)) => {
+                    if !close(*a, *b) {
+                        return false;
+                    }
+                }
+                (Value::List(a), Value::List(b)) => {
+                    if a.len() != b.len() {
+                        return false;
+                    }
+                    stack.extend(a.iter().zip(b));
+                }
+                (Value::Compound(a), Value::Compound(b)) => {
+                    if a.len() != b.len() {
+                        return false;
+                    }
+                    for (key, a) in a {
+                        match b.get(key) {
+                            Some(b) => stack.push((a, b)),
+                            None => return false,
+                        }
+                    }
+                }
+                (a, b) => {
+                    if a != b {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// An estimate of the bytes of heap memory this value holds, for
+    /// bounding the memory used by caches of values. This counts the capacity
+    /// of strings, arrays, lists and compounds, not just their length, and
+    /// does not include the size of this `Value` itself. It has nothing to do
+    /// with the size of the value when serialized.
+    ///
+    /// The size of a compound is approximate, as the exact layout of
+    /// `HashMap` is not known. It counts an entry and a byte of bookkeeping
+    /// for each entry the map has capacity for, which is close to the real
+    /// size for the standard library's map.
+    ///
+    /// Like [`Value::depth`], this does not recurse.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Value};
+    /// let v = nbt!({"Name": "Steve", "Pos": [1.0, 2.0, 3.0]});
+    /// assert!(v.heap_size() > 3 * std::mem::size_of::<Value>());
+    /// assert_eq!(nbt!(1).heap_size(), 0);
+    /// ```
+    pub fn heap_size(&self) -> usize {
+        use std::mem::size_of;
+
+        let mut size = 0;
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            size += match value {
+                Value::String(v) => v.capacity(),
+                Value::ByteArray(v) => v.capacity() * size_of::<i8>(),
+                Value::IntArray(v) => v.capacity() * size_of::<i32>(),
+                Value::LongArray(v) => v.capacity() * size_of::<i64>(),
+                Value::List(v) => {
+                    stack.extend(v);
+                    v.capacity() * size_of::<Value>()
+                }
+                Value::Compound(v) => {
+                    stack.extend(v.values());
+                    let keys: usize = v.keys().map(String::capacity).sum();
+                    keys + v.capacity() * (size_of::<(String, Value)>() + 1)
+                }
+                _ => 0,
+            };
+        }
+
+        size
+    }
 }
 
 // ------------- From<T> impls -------------
@@ -193,6 +907,87 @@ from!(ByteArray, ByteArray);
 from!(IntArray, IntArray);
 from!(LongArray, LongArray);
 
+/// The default value is an empty compound, the most common container in NBT.
+/// This allows taking a value out to edit it with [`std::mem::take`].
+impl Default for Value {
+    fn default() -> Self {
+        Value::Compound(HashMap::new())
+    }
+}
+
+/// Parse a value from SNBT, the text form of NBT used in commands, with the
+/// `fastsnbt` crate. Requires the `snbt` feature. The whole string must be a
+/// single value, and errors give the position into the string where parsing
+/// stopped. Whitespace is allowed around brackets, braces, colons and commas,
+/// so the SNBT written by [`to_pretty_snbt`][`crate::to_pretty_snbt`] and in
+/// [`Diff`][`crate::diff::Diff`] reports parses back.
+///
+/// ```
+/// # use fastnbt::{nbt, Value};
+/// let v: Value = "{Count:1b,id:\"minecraft:stick\"}".parse().unwrap();
+/// assert_eq!(v, nbt!({"Count": 1_i8, "id": "minecraft:stick"}));
+///
+/// let err = "{Count:1b,}".parse::<Value>().unwrap_err();
+/// assert_eq!(err.snbt_position(), Some(10));
+/// ```
+#[cfg(feature = "snbt")]
+impl std::str::FromStr for Value {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // fastsnbt does not allow whitespace between tokens, so it is removed
+        // first, keeping where each byte came from to give in errors.
+        let (compact, positions) = strip_snbt_whitespace(s);
+        let mut de = fastsnbt::de::Deserializer::from_str(&compact);
+        let value =
+            Value::deserialize(&mut de).map_err(|e| Error::snbt(e, positions[de.position()]))?;
+        if de.position() != compact.len() {
+            return Err(Error::snbt(
+                "input wasn't fully consumed",
+                positions[de.position()],
+            ));
+        }
+        Ok(value)
+    }
+}
+
+/// Remove the whitespace around the brackets, braces, colons, semicolons and
+/// commas of SNBT, outside of quoted strings. This also gives the position in
+/// `s` of each byte of the result, and of its end.
+#[cfg(feature = "snbt")]
+fn strip_snbt_whitespace(s: &str) -> (String, Vec<usize>) {
+    let structural = |c: Option<char>| c.is_none_or(|c| "{}[]:;,".contains(c));
+
+    let mut compact = String::with_capacity(s.len());
+    let mut positions = Vec::with_capacity(s.len() + 1);
+    let mut quote = None;
+    let mut escaped = false;
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => {
+                let rest = s[i..].trim_start();
+                let end = s.len() - rest.len();
+                if structural(compact.chars().next_back()) || structural(rest.chars().next()) {
+                    while chars.next_if(|&(j, _)| j < end).is_some() {}
+                    continue;
+                }
+            }
+            None => {}
+        }
+        compact.push(c);
+        positions.extend(std::iter::repeat_n(i, c.len_utf8()));
+    }
+    positions.push(s.len());
+    (compact, positions)
+}
+
 impl From<bool> for Value {
     fn from(val: bool) -> Self {
         Self::Byte(i8::from(val))
@@ -328,12 +1123,7 @@ macro_rules! from_128bit {
         $(
             impl From<$type> for Value {
                 fn from(val: $type) -> Self {
-                    Self::IntArray(IntArray::new(vec![
-                        (val >> 96) as i32,
-                        (val >> 64) as i32,
-                        (val >> 32) as i32,
-                        val as i32,
-                    ]))
+                    Self::IntArray(crate::uuid::to_int_array(val as u128))
                 }
             }
 
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use super::Value;
+
+/// The string that deletes a compound entry when it is the entry's value in a
+/// patch given to [`Value::apply_patch`].
+///
+/// NBT has no null to mark deletions with, so this string is used instead. A
+/// patch can therefore not set a string to this exact value.
+pub const PATCH_DELETE: &str = "$delete";
+
+impl Value {
+    /// Apply `patch` to this value, like a structured `/data modify`. This
+    /// works like JSON Merge Patch, with [`PATCH_DELETE`] instead of null:
+    ///
+    /// * If both are compounds, each entry of the patch is applied to the
+    ///   entry of this compound with the same key. An entry whose value is the
+    ///   string [`PATCH_DELETE`] removes the key, whether or not it exists.
+    /// * A compound in the patch for a key that is missing, or that is not a
+    ///   compound, is applied to an empty compound, so any deletions in it are
+    ///   dropped.
+    /// * Anything else in the patch replaces the value outright. This includes
+    ///   lists, which are never merged element by element.
+    ///
+    /// Patches are themselves values, so they can be written as SNBT and
+    /// parsed with the `snbt` feature, eg `{Health:20.0f,Fire:"$delete"}`.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, value::PATCH_DELETE};
+    /// let mut player = nbt!({
+    ///     "Health": 12.0_f32,
+    ///     "Fire": 40_i16,
+    ///     "abilities": {"flying": 0_i8, "mayfly": 0_i8},
+    /// });
+    /// player.apply_patch(&nbt!({
+    ///     "Health": 20.0_f32,
+    ///     "Fire": PATCH_DELETE,
+    ///     "abilities": {"mayfly": 1_i8},
+    /// }));
+    ///
+    /// assert_eq!(player, nbt!({
+    ///     "Health": 20.0_f32,
+    ///     "abilities": {"flying": 0_i8, "mayfly": 1_i8},
+    /// }));
+    /// ```
+    pub fn apply_patch(&mut self, patch: &Value) {
+        let patch = match patch {
+            Value::Compound(patch) => patch,
+            _ => {
+                *self = patch.clone();
+                return;
+            }
+        };
+
+        match self {
+            Value::Compound(compound) => merge(compound, patch),
+            _ => {
+                let mut compound = HashMap::new();
+                merge(&mut compound, patch);
+                *self = Value::Compound(compound);
+            }
+        }
+    }
+}
+
+fn merge(compound: &mut HashMap<String, Value>, patch: &HashMap<String, Value>) {
+    for (key, value) in patch {
+        if is_delete(value) {
+            compound.remove(key);
+        } else if let Some(existing) = compound.get_mut(key) {
+            existing.apply_patch(value);
+        } else {
+            let mut new = Value::Compound(HashMap::new());
+            new.apply_patch(value);
+            compound.insert(key.clone(), new);
+        }
+    }
+}
+
+fn is_delete(value: &Value) -> bool {
+    matches!(value, Value::String(s) if s == PATCH_DELETE)
+}
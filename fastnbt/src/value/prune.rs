@@ -0,0 +1,112 @@
+use super::Value;
+
+/// Options for [`Value::prune_empty_with_opts`]. This object follows a builder
+/// pattern.
+#[derive(Debug, Clone, Default)]
+pub struct PruneOpts {
+    zeros: bool,
+    strings: bool,
+}
+
+impl PruneOpts {
+    /// Create new options. By default only empty compounds, lists and arrays
+    /// are removed.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set whether compound entries that are numbers equal to zero are
+    /// removed too. Many optional fields default to zero, but some do not, so
+    /// this is off by default.
+    pub fn zeros(mut self, value: bool) -> Self {
+        self.zeros = value;
+        self
+    }
+
+    /// Set whether compound entries that are empty strings are removed too.
+    /// An empty string is often a real value, eg a name cleared on purpose,
+    /// so this is off by default.
+    pub fn strings(mut self, value: bool) -> Self {
+        self.strings = value;
+        self
+    }
+}
+
+impl Value {
+    /// Remove every compound entry in this value that is an empty compound,
+    /// list or array, to make the NBT smaller. Empty strings are kept unless
+    /// [`PruneOpts::strings`] is set. Compounds and lists are pruned inside
+    /// first, so a compound left empty once its own entries are removed is
+    /// removed as well.
+    ///
+    /// Elements of lists are pruned inside but never removed, as that would
+    /// move the elements after them. This value itself is never removed, even
+    /// if it ends up empty.
+    ///
+    /// This is lossy: an entry that is present but empty is not the same as
+    /// one that is missing to anything that checks whether the key exists.
+    /// The game treats many optional fields that way, but not all of them.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let mut item = nbt!({
+    ///     "id": "minecraft:stick",
+    ///     "Count": 1_i8,
+    ///     "tag": {"Enchantments": [], "display": {"Lore": []}},
+    /// });
+    /// item.prune_empty();
+    ///
+    /// assert_eq!(item, nbt!({"id": "minecraft:stick", "Count": 1_i8}));
+    /// ```
+    pub fn prune_empty(&mut self) {
+        self.prune_empty_with_opts(&PruneOpts::default())
+    }
+
+    /// Similar to [`Value::prune_empty`] but with options.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, value::PruneOpts};
+    /// let mut item = nbt!({
+    ///     "id": "minecraft:stick",
+    ///     "tag": {"Damage": 0, "display": {"Name": ""}},
+    /// });
+    /// item.prune_empty_with_opts(&PruneOpts::new().zeros(true).strings(true));
+    ///
+    /// assert_eq!(item, nbt!({"id": "minecraft:stick"}));
+    /// ```
+    pub fn prune_empty_with_opts(&mut self, opts: &PruneOpts) {
+        match self {
+            Value::List(v) => {
+                for el in v {
+                    el.prune_empty_with_opts(opts);
+                }
+            }
+            Value::Compound(v) => {
+                v.retain(|_, el| {
+                    el.prune_empty_with_opts(opts);
+                    !(is_empty(el, opts) || opts.zeros && is_zero(el))
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_empty(value: &Value, opts: &PruneOpts) -> bool {
+    match value {
+        Value::String(_) => opts.strings && value.is_empty(),
+        _ => value.is_empty(),
+    }
+}
+
+fn is_zero(value: &Value) -> bool {
+    match *value {
+        Value::Byte(v) => v == 0,
+        Value::Short(v) => v == 0,
+        Value::Int(v) => v == 0,
+        Value::Long(v) => v == 0,
+        Value::Float(v) => v == 0.0,
+        Value::Double(v) => v == 0.0,
+        _ => false,
+    }
+}
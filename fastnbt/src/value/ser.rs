@@ -5,6 +5,7 @@ use serde::{ser::Impossible, Serialize};
 
 use crate::{
     error::{Error, Result},
+    raw::{RawNbt, RAW_TOKEN},
     ByteArray, IntArray, LongArray, Tag, Value, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN,
     LONG_ARRAY_TOKEN,
 };
@@ -124,21 +125,11 @@ impl serde::Serializer for &mut Serializer {
 
     fn serialize_i128(self, v: i128) -> Result<Value> {
         let v = v as u128;
-        Ok(Value::IntArray(IntArray::new(vec![
-            (v >> 96) as i32,
-            (v >> 64) as i32,
-            (v >> 32) as i32,
-            v as i32,
-        ])))
+        Ok(Value::IntArray(crate::uuid::to_int_array(v)))
     }
 
     fn serialize_u128(self, v: u128) -> Result<Value> {
-        Ok(Value::IntArray(IntArray::new(vec![
-            (v >> 96) as i32,
-            (v >> 64) as i32,
-            (v >> 32) as i32,
-            v as i32,
-        ])))
+        Ok(Value::IntArray(crate::uuid::to_int_array(v)))
     }
 
     #[inline]
@@ -439,6 +430,7 @@ impl serde::ser::SerializeMap for SerializeMap {
                 BYTE_ARRAY_TOKEN => Value::ByteArray(ByteArray::from_bytes(&data())),
                 INT_ARRAY_TOKEN => Value::IntArray(IntArray::from_bytes(&data())?),
                 LONG_ARRAY_TOKEN => Value::LongArray(LongArray::from_bytes(&data())?),
+                RAW_TOKEN => RawNbt::from_data(data())?.to_value()?,
                 _ => Value::Compound(self.map),
             })
         } else {
@@ -519,6 +511,14 @@ impl serde::Serializer for MapKeySerializer {
         Ok(value.to_string())
     }
 
+    fn serialize_i128(self, value: i128) -> Result<String> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<String> {
+        Ok(value.to_string())
+    }
+
     fn serialize_f32(self, _value: f32) -> Result<String> {
         Err(key_must_be_a_string())
     }
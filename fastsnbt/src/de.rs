@@ -45,6 +45,12 @@ impl<'a, 'de: 'a> Deserializer<'de> {
         Self { input, pos: 0 }
     }
 
+    /// The byte offset into the input of the next thing to parse. After an
+    /// error this is where parsing stopped.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
     pub(crate) fn advance(&mut self, new_input: &'de str) {
         self.pos += self.input.len() - new_input.len();
         self.input = new_input;
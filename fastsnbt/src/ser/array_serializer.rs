@@ -71,9 +71,9 @@ impl<'a, W: Write> serde::Serializer for ArraySerializer<'a, W> {
         Err(Error::array_as_other())
     }
 
-    fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok>
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         Err(Error::array_as_other())
     }
@@ -91,18 +91,18 @@ impl<'a, W: Write> serde::Serializer for ArraySerializer<'a, W> {
         Err(Error::array_as_other())
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
         _value: &T,
     ) -> Result<Self::Ok>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         Err(Error::array_as_other())
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -110,7 +110,7 @@ impl<'a, W: Write> serde::Serializer for ArraySerializer<'a, W> {
         _value: &T,
     ) -> Result<Self::Ok>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         Err(Error::array_as_other())
     }
@@ -32,7 +32,7 @@ pub(crate) fn write_escaped_str<W: Write>(mut writer: W, v: &str) -> Result<(),
             continue;
         }
         if start < i {
-            writer.write_all(v[start..i].as_bytes())?;
+            writer.write_all(&v.as_bytes()[start..i])?;
         }
         if byte == b'"' {
             writer.write_all(b"\\\"")?;
@@ -42,7 +42,7 @@ pub(crate) fn write_escaped_str<W: Write>(mut writer: W, v: &str) -> Result<(),
         start = i + 1;
     }
     if start != bytes.len() {
-        writer.write_all(v[start..].as_bytes())?;
+        writer.write_all(&v.as_bytes()[start..])?;
     }
     Ok(writer.write_all(b"\"")?)
 }
@@ -154,9 +154,9 @@ impl<'a, W: 'a + Write> ser::Serializer for &'a mut Serializer<W> {
         Err(Error::bespoke("cannot serialize None".to_string()))
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         value.serialize(self)
     }
@@ -180,18 +180,18 @@ impl<'a, W: 'a + Write> ser::Serializer for &'a mut Serializer<W> {
         self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         value.serialize(self)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -199,7 +199,7 @@ impl<'a, W: 'a + Write> ser::Serializer for &'a mut Serializer<W> {
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         Err(Error::bespoke(
             "cannot serialize newtype variant, please open fastnbt issue".to_string(),
@@ -276,9 +276,9 @@ impl<'a, W: Write> SerializeSeq for ArraySerializer<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         if !self.first {
             self.first = true;
@@ -297,9 +297,9 @@ impl<'a, W: Write + 'a> SerializeTuple for ArraySerializer<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize {
+        T: ?Sized + serde::Serialize {
         SerializeSeq::serialize_element(self, value)
     }
 
@@ -312,9 +312,9 @@ impl<'a, W: Write + 'a> SerializeTupleStruct for ArraySerializer<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         SerializeSeq::serialize_element(self, value)
     }
@@ -328,9 +328,9 @@ impl<'a, W: Write + 'a> SerializeTupleVariant for ArraySerializer<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         SerializeSeq::serialize_element(self, value)
     }
@@ -362,9 +362,9 @@ impl<'a, W: Write + 'a> SerializeMap for CompoundSerializer<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         let mut name = Vec::new();
         key.serialize(&mut NameSerializer { name: &mut name })?;
@@ -372,9 +372,9 @@ impl<'a, W: Write + 'a> SerializeMap for CompoundSerializer<'a, W> {
         Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         let name = self.key.take().ok_or_else(|| {
             Error::bespoke("serialize_value called before serialize_key".to_string())
@@ -429,13 +429,13 @@ impl<'a, W: Write + 'a> SerializeStruct for CompoundSerializer<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
+    fn serialize_field<T>(
         &mut self,
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error>
     where
-        T: serde::Serialize
+        T: ?Sized + serde::Serialize
     {
         SerializeMap::serialize_entry(self, key, value)
     }
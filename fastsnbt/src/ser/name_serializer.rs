@@ -94,9 +94,9 @@ impl<W: Write> Serializer for &mut NameSerializer<W> {
         Err(name_must_be_stringy("none"))
     }
 
-    fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         Err(name_must_be_stringy("some"))
     }
@@ -118,18 +118,18 @@ impl<W: Write> Serializer for &mut NameSerializer<W> {
         Err(name_must_be_stringy("unit_variant"))
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         Err(name_must_be_stringy("newtype_struct"))
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _: &'static str,
         _: u32,
@@ -137,7 +137,7 @@ impl<W: Write> Serializer for &mut NameSerializer<W> {
         _: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         Err(name_must_be_stringy("newtype_variant"))
     }
@@ -0,0 +1,40 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use std::collections::HashMap;
+
+use fastnbt::from_bytes;
+use fastnbt::to_bytes;
+use fastnbt::Value;
+
+// Like `==`, but NaN floats are equal to themselves if their bits match.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+        (Value::Double(a), Value::Double(b)) => a.to_bits() == b.to_bits(),
+        (Value::List(a), Value::List(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+        }
+        (Value::Compound(a), Value::Compound(b)) => {
+            a.len() == b.len() && a.iter().all(|(k, a)| b.get(k).is_some_and(|b| same(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fuzz_target!(|v: Value| {
+    // The root of NBT data must be a compound.
+    let mut inner = HashMap::new();
+    inner.insert("".to_string(), v);
+    let v = Value::Compound(inner);
+
+    let bs = to_bytes(&v).expect("arbitrary values should serialize");
+    let back: Value = from_bytes(&bs).expect("serialized values should deserialize");
+
+    assert!(
+        same(&v, &back),
+        "round trip changed value\n{:?}\n{:?}",
+        v,
+        back
+    );
+});